@@ -1,3 +1,6 @@
+use std::alloc::{Layout, alloc_zeroed, dealloc, handle_alloc_error};
+use std::cell::Cell;
+use std::ffi::{CStr, CString, c_char};
 use std::io::Write;
 
 #[unsafe(no_mangle)]
@@ -18,6 +21,176 @@ pub extern "C" fn read_int() -> i64 {
     x
 }
 
+/// # Safety
+/// `s` must be valid null terminated string
+unsafe fn as_cstr<'a>(s: *const c_char) -> &'a CStr {
+    assert!(!s.is_null(), "Null pointer passed as string");
+    unsafe { CStr::from_ptr(s) }
+}
+
+/// # Safety
+/// `s` must be valid null terminated string
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn print_str(s: *const c_char) {
+    let s = unsafe { as_cstr(s) };
+    let mut stdout = std::io::stdout();
+    stdout.write_all(s.to_bytes()).unwrap();
+    stdout.flush().unwrap();
+}
+
+/// # Safety
+/// `s` must be valid null terminated string
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn str_len(s: *const c_char) -> i64 {
+    unsafe { as_cstr(s) }.count_bytes() as i64
+}
+
+/// # Safety
+/// `lhs` & `rhs` must be valid null terminated strings
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn str_eq(lhs: *const c_char, rhs: *const c_char) -> i32 {
+    let (lhs, rhs) = unsafe { (as_cstr(lhs), as_cstr(rhs)) };
+    (lhs == rhs) as i32
+}
+
+/// Result is heap allocated and should be released via `str_free`
+///
+/// # Safety
+/// `lhs` & `rhs` must be valid null terminated strings
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn str_concat(lhs: *const c_char, rhs: *const c_char) -> *mut c_char {
+    let (lhs, rhs) = unsafe { (as_cstr(lhs), as_cstr(rhs)) };
+    let mut res = lhs.to_bytes().to_vec();
+    res.extend_from_slice(rhs.to_bytes());
+
+    // Both parts have no inner null bytes
+    CString::new(res).unwrap().into_raw()
+}
+
+/// # Safety
+/// `s` must be null or string returned by `str_concat`, which is not released yet
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn str_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// Sorts `len` ints in place, `cmp(a, b)` is negative if `a` goes before `b`
+///
+/// # Safety
+/// `arr` must point to `len` ints, if `len` is positive
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sort_ints(arr: *mut i64, len: i64, cmp: extern "C" fn(i64, i64) -> i32) {
+    if len <= 0 {
+        return;
+    }
+    assert!(!arr.is_null(), "Null pointer passed as array");
+    let arr = unsafe { std::slice::from_raw_parts_mut(arr, len as usize) };
+    arr.sort_by(|a, b| cmp(*a, *b).cmp(&0));
+}
+
+/// Ascending order comparator for `sort_ints`
+#[unsafe(no_mangle)]
+pub extern "C" fn int_cmp(a: i64, b: i64) -> i32 {
+    a.cmp(&b) as i32
+}
+
+/// Replaces each of `len` ints with `f(x)`
+///
+/// # Safety
+/// `arr` must point to `len` ints, if `len` is positive
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn map_ints(arr: *mut i64, len: i64, f: extern "C" fn(i64) -> i64) {
+    if len <= 0 {
+        return;
+    }
+    assert!(!arr.is_null(), "Null pointer passed as array");
+    let arr = unsafe { std::slice::from_raw_parts_mut(arr, len as usize) };
+    for x in arr {
+        *x = f(*x);
+    }
+}
+
+/// Each block allocated by `nya_alloc` starts with header, which keeps its size for `nya_free`
+const ALLOC_HEADER: usize = 16;
+
+thread_local! {
+    /// Number & total size of blocks allocated by `nya_alloc` and not released yet.
+    /// Programs are single threaded, so counters are per thread (and tests don't share them)
+    static LIVE_ALLOCS: Cell<(u64, u64)> = const { Cell::new((0, 0)) };
+}
+
+fn block_layout(size: usize) -> Option<Layout> {
+    let size = size.checked_add(ALLOC_HEADER)?;
+    Layout::from_size_align(size, ALLOC_HEADER).ok()
+}
+
+/// Allocates zero initialized block of `size` bytes, used by `new` expressions.
+/// Block should be released via `nya_free` (`delete` statement).
+/// Invalid size (negative or too big) is reported into stderr and program is aborted,
+/// so it fails at allocation site, panic can't unwind through the program
+#[unsafe(no_mangle)]
+pub extern "C" fn nya_alloc(size: i64) -> *mut u8 {
+    let layout = usize::try_from(size).ok().and_then(block_layout);
+    let Some(layout) = layout else {
+        eprintln!("nya_alloc: invalid allocation size {size}");
+        std::process::abort();
+    };
+
+    let block = unsafe { alloc_zeroed(layout) };
+    if block.is_null() {
+        handle_alloc_error(layout);
+    }
+    unsafe { (block as *mut u64).write(size as u64) };
+
+    LIVE_ALLOCS.with(|live| {
+        let (count, bytes) = live.get();
+        live.set((count + 1, bytes + size as u64));
+    });
+
+    unsafe { block.add(ALLOC_HEADER) }
+}
+
+/// Releases block allocated by `nya_alloc`, null pointer is ignored as in C
+///
+/// # Safety
+/// `ptr` must be null or block returned by `nya_alloc`, which is not released yet
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nya_free(ptr: *mut u8) {
+    if ptr.is_null() {
+        return;
+    }
+
+    let block = unsafe { ptr.sub(ALLOC_HEADER) };
+    let size = unsafe { (block as *const u64).read() };
+    // Size was checked by `nya_alloc`
+    let Some(layout) = block_layout(size as usize) else {
+        eprintln!("nya_free: corrupted block header");
+        std::process::abort();
+    };
+    unsafe { dealloc(block, layout) };
+
+    LIVE_ALLOCS.with(|live| {
+        let (count, bytes) = live.get();
+        live.set((count - 1, bytes - size));
+    });
+}
+
+/// Number & total size in bytes of `nya_alloc` blocks, which are not released yet
+pub fn live_allocations() -> (u64, u64) {
+    LIVE_ALLOCS.with(|live| live.get())
+}
+
+/// Prints blocks, which were not released, into stderr
+#[unsafe(no_mangle)]
+pub extern "C" fn nya_leak_report() {
+    match live_allocations() {
+        (0, _) => eprintln!("Leak report: no leaks"),
+        (count, bytes) => eprintln!("Leak report: {count} blocks ({bytes} bytes) were not released"),
+    }
+}
+
 // TODO: rewrite with proc macro magic
 pub fn register_functions<T>(mut callback: T)
 where T: FnMut(&'static str, *mut ())
@@ -30,4 +203,15 @@ where T: FnMut(&'static str, *mut ())
 
     export_symbol!(print_int);
     export_symbol!(read_int);
+    export_symbol!(print_str);
+    export_symbol!(str_len);
+    export_symbol!(str_eq);
+    export_symbol!(str_concat);
+    export_symbol!(str_free);
+    export_symbol!(sort_ints);
+    export_symbol!(int_cmp);
+    export_symbol!(map_ints);
+    export_symbol!(nya_alloc);
+    export_symbol!(nya_free);
+    export_symbol!(nya_leak_report);
 }
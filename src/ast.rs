@@ -10,20 +10,76 @@ pub mod statement;
 
 pub trait Expression: Acceptor + Debug {
     fn codegen(&self, _: &mut CodegenContext) -> anyhow::Result<TypedValue>;
+
+    /// Codegen address of expression (lvalue), returned type is pointee type
+    fn codegen_ptr(&self, _: &mut CodegenContext) -> anyhow::Result<TypedValue> {
+        anyhow::bail!("Expression {:?} is not addressable", self)
+    }
+
+    /// Expression is lvalue, so `codegen_ptr` can be used
+    fn is_place(&self) -> bool {
+        false
+    }
 }
 
 pub trait Statement: Acceptor + Debug {
     fn codegen(&self, _: &mut CodegenContext) -> anyhow::Result<()>;
 }
 
+/// Type as it's written in source, resolved into `codegen::Type` by `ProgramDefinitions`
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum TypeName {
+    Named(String),
+    Pointer(Box<TypeName>),
+    Array(Box<TypeName>, u64),
+    /// Instance of generic struct, e.g. `Pair<i32>`
+    Generic(String, Vec<TypeName>),
+    /// Function pointer, e.g. `fn(i64) -> i64`
+    Func(Vec<TypeName>, Box<TypeName>),
+    /// Anonymous tuple, e.g. `(i64, f64)`
+    Tuple(Vec<TypeName>),
+}
+
+impl From<&str> for TypeName {
+    fn from(value: &str) -> Self {
+        TypeName::Named(value.into())
+    }
+}
+
+impl Display for TypeName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeName::Named(name) => write!(f, "{}", name),
+            TypeName::Pointer(pointee) => write!(f, "*{}", pointee),
+            TypeName::Array(elem, len) => write!(f, "[{}; {}]", elem, len),
+            TypeName::Generic(name, args) => {
+                let args: Vec<_> = args.iter().map(|arg| arg.to_string()).collect();
+                write!(f, "{}<{}>", name, args.join(", "))
+            }
+            TypeName::Func(args, ret) => {
+                let args: Vec<_> = args.iter().map(|arg| arg.to_string()).collect();
+                write!(f, "fn({})", args.join(", "))?;
+                if **ret != TypeName::Named("void".into()) {
+                    write!(f, " -> {}", ret)?;
+                }
+                Ok(())
+            }
+            TypeName::Tuple(elems) => {
+                let elems: Vec<_> = elems.iter().map(|elem| elem.to_string()).collect();
+                write!(f, "({})", elems.join(", "))
+            }
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct TypedArg {
     pub name: String,
-    pub tp: String,
+    pub tp: TypeName,
 }
 
 impl TypedArg {
-    pub fn new(name: String, tp: String) -> Self {
+    pub fn new(name: String, tp: TypeName) -> Self {
         Self { name, tp }
     }
 }
@@ -52,12 +108,34 @@ impl Display for Comparator {
     }
 }
 
+#[derive(Debug)]
+pub enum LogicOp {
+    And,
+    Or,
+}
+
+impl Display for LogicOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let char = match &self {
+            LogicOp::And => "&&",
+            LogicOp::Or => "||",
+        };
+        write!(f, "{}", char)
+    }
+}
+
 #[derive(Debug)]
 pub enum OpType {
     Mul,
     Div,
+    Rem,
     Add,
     Sub,
+    Shl,
+    Shr,
+    BitAnd,
+    BitXor,
+    BitOr,
 }
 
 impl Display for OpType {
@@ -65,8 +143,14 @@ impl Display for OpType {
         let char = match &self {
             OpType::Mul => "*",
             OpType::Div => "/",
+            OpType::Rem => "%",
             OpType::Add => "+",
             OpType::Sub => "-",
+            OpType::Shl => "<<",
+            OpType::Shr => ">>",
+            OpType::BitAnd => "&",
+            OpType::BitXor => "^",
+            OpType::BitOr => "|",
         };
         write!(f, "{}", char)
     }
@@ -78,7 +162,7 @@ mod macros {
         ($parser:tt, $input:expr, $expected:expr) => {{
             use crate::utils::compare;
 
-            let res = crate::grammar::$parser::new().parse($input);
+            let res = crate::grammar::$parser::new().parse(crate::lexer::Lexer::new($input));
             if let Err(e) = &res {
                 panic!("Failed with err {:?}", e);
             }
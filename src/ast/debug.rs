@@ -45,6 +45,10 @@ macro_rules! print_body {
     }};
 }
 
+fn visibility(is_pub: bool) -> &'static str {
+    if is_pub { "pub " } else { "" }
+}
+
 impl<T: Write> Visitor for ASTPrinter<'_, T> {
     fn visit_arithmetic(&mut self, node: &super::expression::Arithmetic) -> anyhow::Result<()> {
         self.shift()?;
@@ -56,7 +60,11 @@ impl<T: Write> Visitor for ASTPrinter<'_, T> {
 
     fn visit_assignment(&mut self, node: &super::statement::Assignment) -> anyhow::Result<()> {
         self.shift()?;
-        writeln!(self.writer, "Assignment to var {}", node.var)?;
+        match &node.op {
+            Some(op) => writeln!(self.writer, "Assignment {}=", op)?,
+            None => writeln!(self.writer, "Assignment")?,
+        }
+        print_subtree!(self, "Target", node.target);
         print_subtree!(self, "Value", node.expr);
         Ok(())
     }
@@ -81,17 +89,31 @@ impl<T: Write> Visitor for ASTPrinter<'_, T> {
 
     fn visit_float(&mut self, node: &super::expression::Float) -> anyhow::Result<()> {
         self.shift()?;
-        Ok(writeln!(self.writer, "Float {}", node.val)?)
+        Ok(writeln!(
+            self.writer,
+            "Float {}{}",
+            node.val,
+            node.ty.as_deref().unwrap_or_default()
+        )?)
     }
 
     fn visit_int(&mut self, node: &super::expression::Int) -> anyhow::Result<()> {
         self.shift()?;
-        Ok(writeln!(self.writer, "Int {}", node.val)?)
+        Ok(writeln!(
+            self.writer,
+            "Int {}{}",
+            node.val,
+            node.ty.as_deref().unwrap_or_default()
+        )?)
     }
 
     fn visit_for(&mut self, node: &super::statement::For) -> anyhow::Result<()> {
         self.shift()?;
         writeln!(self.writer, "For Loop")?;
+        if let Some(label) = &node.label {
+            self.shift()?;
+            writeln!(self.writer, "- Label: '{}", label)?;
+        }
         print_subtree!(self, "Start", node.start);
         print_subtree!(self, "Check", node.check);
         print_subtree!(self, "Step", node.step);
@@ -106,6 +128,10 @@ impl<T: Write> Visitor for ASTPrinter<'_, T> {
             "FuncDef of fn {} -> {}",
             node.name, node.rettype
         )?;
+        if let Some(doc) = &node.doc {
+            self.shift()?;
+            writeln!(self.writer, "- Doc: {:?}", doc)?;
+        }
         self.shift()?;
         self.ident += 1;
         writeln!(self.writer, "- Args:")?;
@@ -114,6 +140,10 @@ impl<T: Write> Visitor for ASTPrinter<'_, T> {
             self.shift()?;
             writeln!(self.writer, "{}: {}", arg.name, arg.tp)?;
         }
+        if node.is_vararg {
+            self.shift()?;
+            writeln!(self.writer, "...")?;
+        }
         self.ident -= 3;
 
         Ok(())
@@ -123,9 +153,15 @@ impl<T: Write> Visitor for ASTPrinter<'_, T> {
         self.shift()?;
         writeln!(
             self.writer,
-            "FuncImpl of fn {} -> {}",
-            node.name, node.rettype
+            "FuncImpl of {}fn {} -> {}",
+            visibility(node.is_pub),
+            node.name,
+            node.rettype
         )?;
+        if let Some(doc) = &node.doc {
+            self.shift()?;
+            writeln!(self.writer, "- Doc: {:?}", doc)?;
+        }
         self.shift()?;
         self.ident += 1;
         writeln!(self.writer, "- Args:")?;
@@ -141,9 +177,69 @@ impl<T: Write> Visitor for ASTPrinter<'_, T> {
         Ok(())
     }
 
+    fn visit_global(&mut self, node: &super::statement::Global) -> anyhow::Result<()> {
+        self.shift()?;
+        let kind = if node.is_const { "Const" } else { "Static" };
+        writeln!(
+            self.writer,
+            "{}{} {}: {}",
+            visibility(node.is_pub),
+            kind,
+            node.name,
+            node.tp
+        )?;
+        if let Some(doc) = &node.doc {
+            self.shift()?;
+            writeln!(self.writer, "- Doc: {:?}", doc)?;
+        }
+        print_subtree!(self, "Value", node.expr);
+        Ok(())
+    }
+
+    fn visit_typealias(&mut self, node: &super::statement::TypeAlias) -> anyhow::Result<()> {
+        self.shift()?;
+        let kind = if node.is_newtype {
+            "Newtype"
+        } else {
+            "Type alias"
+        };
+        writeln!(
+            self.writer,
+            "{}{} {} = {}",
+            visibility(node.is_pub),
+            kind,
+            node.name,
+            node.target
+        )?;
+        if let Some(doc) = &node.doc {
+            self.shift()?;
+            writeln!(self.writer, "- Doc: {:?}", doc)?;
+        }
+        Ok(())
+    }
+
+    fn visit_impl(&mut self, node: &super::statement::Impl) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "Impl for type {}", node.type_name)?;
+        if let Some(doc) = &node.doc {
+            self.shift()?;
+            writeln!(self.writer, "- Doc: {:?}", doc)?;
+        }
+        print_body!(self, "Methods", node.methods);
+        Ok(())
+    }
+
     fn visit_functioncall(&mut self, node: &super::expression::FunctionCall) -> anyhow::Result<()> {
         self.shift()?;
-        writeln!(self.writer, "Calling function {}", node.name)?;
+        if let Some(callee) = &node.callee {
+            writeln!(self.writer, "Calling function pointer")?;
+            print_subtree!(self, "Callee", callee);
+        } else if let Some(receiver) = &node.receiver {
+            writeln!(self.writer, "Calling method {}", node.name)?;
+            print_subtree!(self, "Receiver", receiver);
+        } else {
+            writeln!(self.writer, "Calling function {}", node.name)?;
+        }
         print_body!(self, "Args", node.args);
         Ok(())
     }
@@ -159,9 +255,55 @@ impl<T: Write> Visitor for ASTPrinter<'_, T> {
         Ok(())
     }
 
+    fn visit_switch(&mut self, node: &super::statement::Switch) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "Switch")?;
+        print_subtree!(self, "Value", node.value);
+        for (i, case) in node.cases.iter().enumerate() {
+            print_body!(self, format!("Case #{} values", i), case.values);
+            print_body!(self, format!("Case #{} body", i), case.body);
+        }
+        if let Some(body) = &node.default {
+            print_body!(self, "Default", *body);
+        }
+        Ok(())
+    }
+
+    fn visit_match(&mut self, node: &super::statement::Match) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "Match")?;
+        print_subtree!(self, "Value", node.value);
+        for arm in &node.arms {
+            let pattern = match &arm.pattern {
+                Some(p) if p.bindings.is_empty() => format!("{}::{}", p.enum_name, p.variant),
+                Some(p) => format!("{}::{}({})", p.enum_name, p.variant, p.bindings.join(", ")),
+                None => "_".into(),
+            };
+            print_body!(self, format!("Arm {}", pattern), arm.body);
+        }
+        Ok(())
+    }
+
     fn visit_let(&mut self, node: &super::statement::Let) -> anyhow::Result<()> {
         self.shift()?;
-        writeln!(self.writer, "Let to var {} of type {:?}", node.var, node.tp)?;
+        writeln!(
+            self.writer,
+            "Let to var {} of type {:?}",
+            node.var,
+            node.tp.as_ref().map(|tp| tp.to_string())
+        )?;
+        print_subtree!(self, "Value", node.expr);
+        Ok(())
+    }
+
+    fn visit_lettuple(&mut self, node: &super::statement::LetTuple) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(
+            self.writer,
+            "Let to vars ({}) of type {:?}",
+            node.vars.join(", "),
+            node.tp.as_ref().map(|tp| tp.to_string())
+        )?;
         print_subtree!(self, "Value", node.expr);
         Ok(())
     }
@@ -180,9 +322,35 @@ impl<T: Write> Visitor for ASTPrinter<'_, T> {
         Ok(())
     }
 
+    fn visit_import(&mut self, node: &super::statement::Import) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "Import {:?}", node.path)?;
+        if let Some(doc) = &node.doc {
+            self.shift()?;
+            writeln!(self.writer, "- Doc: {:?}", doc)?;
+        }
+        Ok(())
+    }
+
+    fn visit_module(&mut self, node: &super::statement::Module) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "Module {}", node.name)?;
+        print_body!(self, "Blocks", node.blocks);
+        Ok(())
+    }
+
     fn visit_structdef(&mut self, node: &super::statement::StructDef) -> anyhow::Result<()> {
         self.shift()?;
-        writeln!(self.writer, "StructDef of type {}", node.name)?;
+        writeln!(
+            self.writer,
+            "StructDef of {}type {}",
+            visibility(node.is_pub),
+            node.name
+        )?;
+        if let Some(doc) = &node.doc {
+            self.shift()?;
+            writeln!(self.writer, "- Doc: {:?}", doc)?;
+        }
         self.shift()?;
         self.ident += 1;
         writeln!(self.writer, "- Fields:")?;
@@ -196,6 +364,36 @@ impl<T: Write> Visitor for ASTPrinter<'_, T> {
         Ok(())
     }
 
+    fn visit_enumdef(&mut self, node: &super::statement::EnumDef) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(
+            self.writer,
+            "EnumDef of {}type {}",
+            visibility(node.is_pub),
+            node.name
+        )?;
+        if let Some(doc) = &node.doc {
+            self.shift()?;
+            writeln!(self.writer, "- Doc: {:?}", doc)?;
+        }
+        self.shift()?;
+        self.ident += 1;
+        writeln!(self.writer, "- Variants:")?;
+        self.ident += 2;
+        for variant in &node.variants {
+            self.shift()?;
+            let fields: Vec<_> = variant.fields.iter().map(|f| f.to_string()).collect();
+            write!(self.writer, "{}({})", variant.name, fields.join(", "))?;
+            match variant.value {
+                Some(value) => writeln!(self.writer, " = {}", value)?,
+                None => writeln!(self.writer)?,
+            }
+        }
+        self.ident -= 3;
+
+        Ok(())
+    }
+
     fn visit_unaryminus(&mut self, node: &super::expression::UnaryMinus) -> anyhow::Result<()> {
         self.shift()?;
         writeln!(self.writer, "UnaryMinus")?;
@@ -206,20 +404,75 @@ impl<T: Write> Visitor for ASTPrinter<'_, T> {
     fn visit_variable(&mut self, node: &super::expression::Variable) -> anyhow::Result<()> {
         self.shift()?;
         writeln!(self.writer, "Variable {}", node)?;
+        for (i, field) in node.fields.iter().enumerate() {
+            if let super::expression::Accessor::Index(index) = field {
+                print_subtree!(self, format!("Index #{}", i), index);
+            }
+        }
         Ok(())
     }
 
     fn visit_while(&mut self, node: &super::statement::While) -> anyhow::Result<()> {
         self.shift()?;
         writeln!(self.writer, "While Loop")?;
+        if let Some(label) = &node.label {
+            self.shift()?;
+            writeln!(self.writer, "- Label: '{}", label)?;
+        }
         print_subtree!(self, "Condition", node.cond);
         print_body!(self, "Body", node.body);
         Ok(())
     }
 
+    fn visit_infiniteloop(&mut self, node: &super::statement::InfiniteLoop) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "Infinite Loop")?;
+        if let Some(label) = &node.label {
+            self.shift()?;
+            writeln!(self.writer, "- Label: '{}", label)?;
+        }
+        print_body!(self, "Body", node.body);
+        Ok(())
+    }
+
+    fn visit_dowhile(&mut self, node: &super::statement::DoWhile) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "Do While Loop")?;
+        if let Some(label) = &node.label {
+            self.shift()?;
+            writeln!(self.writer, "- Label: '{}", label)?;
+        }
+        print_body!(self, "Body", node.body);
+        print_subtree!(self, "Condition", node.cond);
+        Ok(())
+    }
+
     fn visit_structctor(&mut self, node: &super::expression::StructCtor) -> anyhow::Result<()> {
         self.shift()?;
         writeln!(self.writer, "Struct Ctor of type {}", node.name)?;
+        for field in &node.fields {
+            print_subtree!(self, format!("Field {}", field.name), field.value);
+        }
+        Ok(())
+    }
+
+    fn visit_enumctor(&mut self, node: &super::expression::EnumCtor) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(
+            self.writer,
+            "Enum Ctor {}::{}",
+            node.enum_name, node.variant
+        )?;
+        print_body!(self, "Values", node.args);
+        Ok(())
+    }
+
+    fn visit_itempath(&mut self, node: &super::expression::ItemPath) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "Item Path {}::{}", node.prefix, node.name)?;
+        if let Some(args) = &node.args {
+            print_body!(self, "Values", *args);
+        }
         Ok(())
     }
 
@@ -234,6 +487,124 @@ impl<T: Write> Visitor for ASTPrinter<'_, T> {
 
         Ok(())
     }
+
+    fn visit_break(&mut self, node: &super::statement::Break) -> anyhow::Result<()> {
+        self.shift()?;
+        match &node.label {
+            Some(label) => writeln!(self.writer, "Break '{}", label)?,
+            None => writeln!(self.writer, "Break")?,
+        }
+        Ok(())
+    }
+
+    fn visit_continue(&mut self, node: &super::statement::Continue) -> anyhow::Result<()> {
+        self.shift()?;
+        match &node.label {
+            Some(label) => writeln!(self.writer, "Continue '{}", label)?,
+            None => writeln!(self.writer, "Continue")?,
+        }
+        Ok(())
+    }
+
+    fn visit_deref(&mut self, node: &super::expression::Deref) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "Deref")?;
+        print_subtree!(self, "Pointer", node.expr);
+        Ok(())
+    }
+
+    fn visit_addressof(&mut self, node: &super::expression::AddressOf) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "AddressOf")?;
+        print_subtree!(self, "Value", node.expr);
+        Ok(())
+    }
+
+    fn visit_null(&mut self, _: &super::expression::Null) -> anyhow::Result<()> {
+        self.shift()?;
+        Ok(writeln!(self.writer, "Null")?)
+    }
+
+    fn visit_bool(&mut self, node: &super::expression::Bool) -> anyhow::Result<()> {
+        self.shift()?;
+        Ok(writeln!(self.writer, "Bool {}", node.val)?)
+    }
+
+    fn visit_logical(&mut self, node: &super::expression::Logical) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "Logical node (op: {})", node.op)?;
+        print_subtree!(self, "LHS", node.lhs);
+        print_subtree!(self, "RHS", node.rhs);
+        Ok(())
+    }
+
+    fn visit_bitnot(&mut self, node: &super::expression::BitNot) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "Bitwise Not")?;
+        print_subtree!(self, "Value", node.expr);
+        Ok(())
+    }
+
+    fn visit_as(&mut self, node: &super::expression::As) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "As {}", node.tp)?;
+        print_subtree!(self, "Value", node.expr);
+        Ok(())
+    }
+
+    fn visit_str(&mut self, node: &super::expression::Str) -> anyhow::Result<()> {
+        self.shift()?;
+        Ok(writeln!(self.writer, "Str {:?}", node.val)?)
+    }
+
+    fn visit_arrayctor(&mut self, node: &super::expression::ArrayCtor) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "Array Ctor")?;
+        print_body!(self, "Elements", node.elements);
+        Ok(())
+    }
+
+    fn visit_arrayrepeat(&mut self, node: &super::expression::ArrayRepeat) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "Array Repeat (len: {})", node.len)?;
+        print_subtree!(self, "Value", node.value);
+        Ok(())
+    }
+
+    fn visit_newobject(&mut self, node: &super::expression::NewObject) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "New Object")?;
+        print_subtree!(self, "Value", node.ctor);
+        Ok(())
+    }
+
+    fn visit_newarray(&mut self, node: &super::expression::NewArray) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "New Array of type {}", node.elem)?;
+        print_subtree!(self, "Len", node.len);
+        Ok(())
+    }
+
+    fn visit_delete(&mut self, node: &super::statement::Delete) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "Delete")?;
+        print_subtree!(self, "Pointer", node.expr);
+        Ok(())
+    }
+
+    fn visit_defer(&mut self, node: &super::statement::Defer) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "Defer")?;
+        print_subtree!(self, "Statement", node.stmt);
+        Ok(())
+    }
+
+    fn visit_tuplector(&mut self, node: &super::expression::TupleCtor) -> anyhow::Result<()> {
+        self.shift()?;
+        writeln!(self.writer, "Tuple Ctor")?;
+        print_body!(self, "Elements", node.elements);
+        Ok(())
+    }
 }
 
 pub fn print_ast<T: Write>(writer: &mut T, program: &Program) -> anyhow::Result<()> {
@@ -1,5 +1,5 @@
 mod function_call;
-pub use function_call::FunctionCall;
+pub use function_call::{FunctionCall, call_function};
 
 mod unary_minus;
 pub use unary_minus::UnaryMinus;
@@ -11,13 +11,43 @@ mod compare;
 pub use compare::Compare;
 
 mod arithmetic;
-pub use arithmetic::Arithmetic;
+pub use arithmetic::{Arithmetic, build_arithmetic};
 
 mod variable;
-pub use variable::Variable;
+pub use variable::{Accessor, Variable};
 
 mod constant;
-pub use constant::{Float, Int};
+pub use constant::{Bool, Float, Int, Null, Str};
 
 mod struct_ctor;
-pub use struct_ctor::StructCtor;
+pub use struct_ctor::{FieldInit, StructCtor};
+
+mod deref;
+pub use deref::Deref;
+
+mod address_of;
+pub use address_of::AddressOf;
+
+mod array_ctor;
+pub use array_ctor::{ArrayCtor, ArrayRepeat};
+
+mod logical;
+pub use logical::Logical;
+
+mod bit_not;
+pub use bit_not::BitNot;
+
+mod as_cast;
+pub use as_cast::As;
+
+mod enum_ctor;
+pub use enum_ctor::{EnumCtor, build_enum_value};
+
+mod item_path;
+pub use item_path::ItemPath;
+
+mod tuple_ctor;
+pub use tuple_ctor::TupleCtor;
+
+mod new;
+pub use new::{NewArray, NewObject};
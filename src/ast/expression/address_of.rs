@@ -0,0 +1,57 @@
+use std::rc::Rc;
+
+use crate::ast::Expression;
+use crate::codegen::{CodegenContext, Type, TypedValue};
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use nyacc_proc::Acceptor;
+
+#[derive(new, Acceptor, Debug)]
+pub struct AddressOf {
+    pub expr: Box<dyn Expression>,
+}
+
+impl Expression for AddressOf {
+    fn codegen(&self, cxt: &mut CodegenContext) -> anyhow::Result<TypedValue> {
+        let place = self.expr.codegen_ptr(cxt)?;
+
+        Ok(TypedValue {
+            value: place.value,
+            ty: Rc::new(Type::Pointer(place.ty)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn simple_expressions() {
+        check_ast!(
+            ExprParser,
+            "&a",
+            ast_node!(AddressOf, ast_node!(Variable, "a".into(), vec![]))
+        );
+
+        check_ast!(
+            ExprParser,
+            "&a.b.c",
+            ast_node!(
+                AddressOf,
+                ast_node!(Variable, "a".into(), vec!["b".into(), "c".into()])
+            )
+        );
+
+        check_ast!(
+            ExprParser,
+            "&*a",
+            ast_node!(
+                AddressOf,
+                ast_node!(Deref, ast_node!(Variable, "a".into(), vec![]))
+            )
+        );
+    }
+}
@@ -1,7 +1,12 @@
 use crate::ast::{Expression, OpType};
-use crate::codegen::{Type, TypedValue, ZERO_NAME, cast};
+use crate::codegen::{
+    CodegenContext, Type, TypedValue, ZERO_NAME, cast, common_operand_type, const_int_value,
+};
 use crate::visitor::{Acceptor, Visitor};
 use derive_new::new;
+use llvm_sys::core::{
+    LLVMBuildAShr, LLVMBuildAnd, LLVMBuildIntCast2, LLVMBuildLShr, LLVMBuildShl, LLVMConstInt,
+};
 use nyacc_proc::Acceptor;
 
 #[derive(new, Acceptor, Debug)]
@@ -19,49 +24,147 @@ impl Expression for Arithmetic {
         let lhs_tv = self.lhs.codegen(cxt)?;
         let rhs_tv = self.rhs.codegen(cxt)?;
 
-        if !lhs_tv.ty.arithmetic() || !rhs_tv.ty.arithmetic() {
-            anyhow::bail!("Arithmetic on incomptable types");
-        }
+        build_arithmetic(cxt, &self.op, lhs_tv, rhs_tv)
+    }
+}
 
-        let common_type = Type::common_type(&lhs_tv.ty, &rhs_tv.ty)?;
-
-        let lhs = cast(cxt, &lhs_tv.ty, &common_type, lhs_tv.value)?;
-        let rhs = cast(cxt, &rhs_tv.ty, &common_type, rhs_tv.value)?;
-
-        macro_rules! dispatch_binop {
-            ($([$op:tt, $float_func:tt, $int_func:tt ]),+) => {
-                match self.op {
-                $(
-                    OpType::$op => {
-                        let res = match common_type.as_ref() {
-                            Type::Float(_) => {
-                                unsafe {llvm_sys::core::$float_func(cxt.builder, lhs, rhs, ZERO_NAME)}
-                            },
-                            Type::Int(_) => {
-                                unsafe {llvm_sys::core::$int_func(cxt.builder, lhs, rhs, ZERO_NAME)}
-                            },
-                            _ => { panic!("This kind of errors should be catched during Type::common_type call") }
-                        };
-                        assert!(!res.is_null(), "Failed to build llvm arithmetic for optype {}, args types: {} {}", OpType::$op, lhs_tv.ty, rhs_tv.ty);
-                        res
-                    },
-                )+
-                }
-            };
-        }
+/// Applies `op` to computed operands, they are converted to their common type first
+pub fn build_arithmetic(
+    cxt: &mut CodegenContext,
+    op: &OpType,
+    lhs_tv: TypedValue,
+    rhs_tv: TypedValue,
+) -> anyhow::Result<TypedValue> {
+    if !lhs_tv.ty.arithmetic() || !rhs_tv.ty.arithmetic() {
+        anyhow::bail!("Arithmetic on incomptable types");
+    }
+    if let OpType::Shl | OpType::Shr = op {
+        return build_shift(cxt, op, lhs_tv, rhs_tv);
+    }
 
-        let value = dispatch_binop!(
-            [Mul, LLVMBuildFMul, LLVMBuildMul],
-            [Div, LLVMBuildFDiv, LLVMBuildSDiv],
-            [Add, LLVMBuildFAdd, LLVMBuildAdd],
-            [Sub, LLVMBuildFSub, LLVMBuildSub]
-        );
+    let common_type = common_operand_type(&lhs_tv, &rhs_tv)?;
+
+    let lhs = cast(cxt, &lhs_tv.ty, &common_type, lhs_tv.value)?;
+    let rhs = cast(cxt, &rhs_tv.ty, &common_type, rhs_tv.value)?;
+
+    if let (OpType::Div | OpType::Rem, Type::Int(int)) = (op, common_type.underlying())
+        && const_int_value(int, rhs) == Some(0)
+    {
+        anyhow::bail!("Division by zero");
+    }
+
+    macro_rules! dispatch_binop {
+        ($([$op:tt, $float_func:tt, $sint_func:tt, $uint_func:tt ]),+; $([$int_op:tt, $sint_only_func:tt, $uint_only_func:tt ]),+) => {
+            match op {
+            $(
+                OpType::$int_op => {
+                    let res = match common_type.underlying() {
+                        Type::Int(int) if int.signed => {
+                            unsafe {llvm_sys::core::$sint_only_func(cxt.builder, lhs, rhs, ZERO_NAME)}
+                        },
+                        Type::Int(_) => {
+                            unsafe {llvm_sys::core::$uint_only_func(cxt.builder, lhs, rhs, ZERO_NAME)}
+                        },
+                        _ => anyhow::bail!("Operator {} can be applied only to integers, not {}", OpType::$int_op, common_type),
+                    };
+                    assert!(!res.is_null(), "Failed to build llvm arithmetic for optype {}, args types: {} {}", OpType::$int_op, lhs_tv.ty, rhs_tv.ty);
+                    res
+                },
+            )+
+            $(
+                OpType::$op => {
+                    let res = match common_type.underlying() {
+                        Type::Float(_) => {
+                            unsafe {llvm_sys::core::$float_func(cxt.builder, lhs, rhs, ZERO_NAME)}
+                        },
+                        Type::Int(int) if int.signed => {
+                            unsafe {llvm_sys::core::$sint_func(cxt.builder, lhs, rhs, ZERO_NAME)}
+                        },
+                        Type::Int(_) => {
+                            unsafe {llvm_sys::core::$uint_func(cxt.builder, lhs, rhs, ZERO_NAME)}
+                        },
+                        _ => unreachable!("Common type of arithmetic types is int or float"),
+                    };
+                    assert!(!res.is_null(), "Failed to build llvm arithmetic for optype {}, args types: {} {}", OpType::$op, lhs_tv.ty, rhs_tv.ty);
+                    res
+                },
+            )+
+                OpType::Shl | OpType::Shr => unreachable!("Shifts are built by build_shift"),
+            }
+        };
+    }
+
+    let value = dispatch_binop!(
+        [Mul, LLVMBuildFMul, LLVMBuildMul, LLVMBuildMul],
+        [Div, LLVMBuildFDiv, LLVMBuildSDiv, LLVMBuildUDiv],
+        [Rem, LLVMBuildFRem, LLVMBuildSRem, LLVMBuildURem],
+        [Add, LLVMBuildFAdd, LLVMBuildAdd, LLVMBuildAdd],
+        [Sub, LLVMBuildFSub, LLVMBuildSub, LLVMBuildSub];
+        [BitAnd, LLVMBuildAnd, LLVMBuildAnd],
+        [BitXor, LLVMBuildXor, LLVMBuildXor],
+        [BitOr, LLVMBuildOr, LLVMBuildOr]
+    );
+
+    Ok(TypedValue {
+        value,
+        ty: common_type,
+    })
+}
+
+/// Shift has type of lhs, shift amount is converted to it.
+/// Constant amount should be less than bitwidth, other amounts are taken modulo bitwidth,
+/// because too large shift is poison in llvm
+fn build_shift(
+    cxt: &mut CodegenContext,
+    op: &OpType,
+    lhs_tv: TypedValue,
+    rhs_tv: TypedValue,
+) -> anyhow::Result<TypedValue> {
+    let (lhs_int, rhs_int) = match (lhs_tv.ty.underlying(), rhs_tv.ty.underlying()) {
+        (Type::Int(lhs_int), Type::Int(rhs_int)) => (lhs_int, rhs_int),
+        (Type::Int(_), _) => anyhow::bail!(
+            "Operator {} can be applied only to integers, not {}",
+            op,
+            rhs_tv.ty
+        ),
+        _ => anyhow::bail!(
+            "Operator {} can be applied only to integers, not {}",
+            op,
+            lhs_tv.ty
+        ),
+    };
 
-        Ok(TypedValue {
-            value,
-            ty: common_type,
-        })
+    if let Some(amount) = const_int_value(rhs_int, rhs_tv.value)
+        && !(0..lhs_int.bitwidth as i128).contains(&amount)
+    {
+        anyhow::bail!("Shift by {} is out of range of {}", amount, lhs_tv.ty);
     }
+
+    let llvm_type = lhs_int.llvm_type(cxt);
+    let value = unsafe {
+        let amount = LLVMBuildIntCast2(
+            cxt.builder,
+            rhs_tv.value,
+            llvm_type,
+            rhs_int.signed as i32,
+            ZERO_NAME,
+        );
+        // Bitwidth is power of two, so mask is the same as modulo
+        let mask = LLVMConstInt(llvm_type, lhs_int.bitwidth as u64 - 1, 0);
+        let amount = LLVMBuildAnd(cxt.builder, amount, mask, ZERO_NAME);
+
+        match (op, lhs_int.signed) {
+            (OpType::Shl, _) => LLVMBuildShl(cxt.builder, lhs_tv.value, amount, ZERO_NAME),
+            (_, true) => LLVMBuildAShr(cxt.builder, lhs_tv.value, amount, ZERO_NAME),
+            (_, false) => LLVMBuildLShr(cxt.builder, lhs_tv.value, amount, ZERO_NAME),
+        }
+    };
+    assert!(!value.is_null());
+
+    Ok(TypedValue {
+        value,
+        ty: lhs_tv.ty,
+    })
 }
 
 #[cfg(test)]
@@ -149,4 +252,60 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn bitwise_precedence() {
+        // Shifts < additive like in C, but bitwise ops are above comparisons like in Rust
+        check_ast!(
+            ExprParser,
+            "a | b ^ c & 1 << 2 + 3 % 4",
+            ast_node!(
+                Arithmetic,
+                ast_node!(Variable, "a".into(), vec![]),
+                OpType::BitOr,
+                ast_node!(
+                    Arithmetic,
+                    ast_node!(Variable, "b".into(), vec![]),
+                    OpType::BitXor,
+                    ast_node!(
+                        Arithmetic,
+                        ast_node!(Variable, "c".into(), vec![]),
+                        OpType::BitAnd,
+                        ast_node!(
+                            Arithmetic,
+                            ast_node!(Int, 1),
+                            OpType::Shl,
+                            ast_node!(
+                                Arithmetic,
+                                ast_node!(Int, 2),
+                                OpType::Add,
+                                ast_node!(
+                                    Arithmetic,
+                                    ast_node!(Int, 3),
+                                    OpType::Rem,
+                                    ast_node!(Int, 4)
+                                )
+                            )
+                        )
+                    )
+                )
+            )
+        );
+
+        check_ast!(
+            ExprParser,
+            "a & b == c",
+            ast_node!(
+                Compare,
+                ast_node!(
+                    Arithmetic,
+                    ast_node!(Variable, "a".into(), vec![]),
+                    OpType::BitAnd,
+                    ast_node!(Variable, "b".into(), vec![])
+                ),
+                crate::ast::Comparator::EQ,
+                ast_node!(Variable, "c".into(), vec![])
+            )
+        );
+    }
 }
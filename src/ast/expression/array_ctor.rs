@@ -0,0 +1,200 @@
+use std::rc::Rc;
+
+use crate::ast::Expression;
+use crate::codegen::macros::c_str;
+use crate::codegen::{
+    CodegenContext, Type, TypedValue, ZERO_NAME, cast, common_operand_type,
+    position_builer_at_begin,
+};
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use llvm_sys::LLVMIntPredicate;
+use llvm_sys::core::{
+    LLVMAddIncoming, LLVMAppendBasicBlockInContext, LLVMBuildAdd, LLVMBuildAlloca, LLVMBuildBr,
+    LLVMBuildCondBr, LLVMBuildGEP2, LLVMBuildICmp, LLVMBuildInsertValue, LLVMBuildLoad2,
+    LLVMBuildPhi, LLVMBuildStore, LLVMConstArray2, LLVMConstInt, LLVMGetEntryBasicBlock,
+    LLVMGetInsertBlock, LLVMGetUndef, LLVMIntTypeInContext, LLVMIsConstant,
+    LLVMPositionBuilderAtEnd,
+};
+use nyacc_proc::Acceptor;
+
+/// `[a, b, c]`
+#[derive(new, Acceptor, Debug)]
+pub struct ArrayCtor {
+    pub elements: Vec<Box<dyn Expression>>,
+}
+
+impl Expression for ArrayCtor {
+    fn codegen(&self, cxt: &mut CodegenContext) -> anyhow::Result<TypedValue> {
+        if self.elements.is_empty() {
+            anyhow::bail!("Can't infer element type of empty array");
+        }
+
+        let mut values = Vec::with_capacity(self.elements.len());
+        for elem in &self.elements {
+            values.push(elem.codegen(cxt)?);
+        }
+
+        /* Constant elements take type of others, if they fit, as operands of binary operators */
+        let mut elem_type = values[0].ty.clone();
+        let mut typed_by = 0;
+        for (i, val) in values.iter().enumerate().skip(1) {
+            if val.ty != elem_type {
+                elem_type = common_operand_type(&values[typed_by], val)?;
+                if !Rc::ptr_eq(&elem_type, &values[typed_by].ty) {
+                    typed_by = i;
+                }
+            }
+        }
+
+        let ty = Rc::new(Type::Array(elem_type.clone(), values.len() as u64));
+
+        let mut array = unsafe { LLVMGetUndef(ty.llvm_type(cxt)) };
+        for (i, val) in values.iter().enumerate() {
+            let val = cast(cxt, &val.ty, &elem_type, val.value)?;
+            array = unsafe { LLVMBuildInsertValue(cxt.builder, array, val, i as u32, ZERO_NAME) };
+        }
+        assert!(!array.is_null());
+
+        Ok(TypedValue { value: array, ty })
+    }
+}
+
+/// `[value; len]`
+#[derive(new, Acceptor, Debug)]
+pub struct ArrayRepeat {
+    pub value: Box<dyn Expression>,
+    pub len: u64,
+}
+
+impl Expression for ArrayRepeat {
+    fn codegen(&self, cxt: &mut CodegenContext) -> anyhow::Result<TypedValue> {
+        let cur_func = cxt.vislayers.cur_fun().unwrap().0;
+        let entry_block = unsafe { LLVMGetEntryBasicBlock(cur_func) };
+        assert!(!entry_block.is_null());
+
+        let value = self.value.codegen(cxt)?;
+        let current_block = unsafe { LLVMGetInsertBlock(cxt.builder) };
+        assert!(!current_block.is_null());
+        let ty = Rc::new(Type::Array(value.ty.clone(), self.len));
+        let llvm_ty = ty.llvm_type(cxt);
+
+        if self.len == 0 {
+            let value = unsafe { LLVMGetUndef(llvm_ty) };
+            return Ok(TypedValue { value, ty });
+        }
+
+        /* Constant value gives constant array, it's also required for global initializers */
+        if unsafe { LLVMIsConstant(value.value) } != 0 {
+            let mut elements = vec![value.value; self.len as usize];
+            let value = unsafe {
+                LLVMConstArray2(
+                    value.ty.llvm_type(cxt),
+                    elements.as_mut_ptr(),
+                    elements.len() as u64,
+                )
+            };
+            assert!(!value.is_null());
+            return Ok(TypedValue { value, ty });
+        }
+
+        /* Create alloca in entry block */
+        position_builer_at_begin(cxt, entry_block);
+        let alloca = unsafe { LLVMBuildAlloca(cxt.builder, llvm_ty, ZERO_NAME) };
+        assert!(!alloca.is_null());
+        unsafe { LLVMPositionBuilderAtEnd(cxt.builder, current_block) };
+
+        /* Fill it in loop, so code size doesn't depend on length */
+        let fill_block =
+            unsafe { LLVMAppendBasicBlockInContext(cxt.cxt, cur_func, c_str!(c"array_fill")) };
+        let cont_block =
+            unsafe { LLVMAppendBasicBlockInContext(cxt.cxt, cur_func, c_str!(c"array_filled")) };
+
+        unsafe {
+            LLVMBuildBr(cxt.builder, fill_block);
+            LLVMPositionBuilderAtEnd(cxt.builder, fill_block);
+        }
+
+        let i64_type = unsafe { LLVMIntTypeInContext(cxt.cxt, 64) };
+        unsafe {
+            let idx = LLVMBuildPhi(cxt.builder, i64_type, ZERO_NAME);
+            let mut indices = [LLVMConstInt(i64_type, 0, 0), idx];
+            let elem_ptr = LLVMBuildGEP2(
+                cxt.builder,
+                llvm_ty,
+                alloca,
+                indices.as_mut_ptr(),
+                indices.len() as u32,
+                ZERO_NAME,
+            );
+            LLVMBuildStore(cxt.builder, value.value, elem_ptr);
+
+            let next_idx = LLVMBuildAdd(cxt.builder, idx, LLVMConstInt(i64_type, 1, 0), ZERO_NAME);
+            let has_next = LLVMBuildICmp(
+                cxt.builder,
+                LLVMIntPredicate::LLVMIntULT,
+                next_idx,
+                LLVMConstInt(i64_type, self.len, 0),
+                ZERO_NAME,
+            );
+            LLVMBuildCondBr(cxt.builder, has_next, fill_block, cont_block);
+
+            let mut incoming_values = [LLVMConstInt(i64_type, 0, 0), next_idx];
+            let mut incoming_blocks = [current_block, fill_block];
+            LLVMAddIncoming(
+                idx,
+                incoming_values.as_mut_ptr(),
+                incoming_blocks.as_mut_ptr(),
+                2,
+            );
+
+            LLVMPositionBuilderAtEnd(cxt.builder, cont_block);
+        }
+
+        /* Load because of expression semantics */
+        let value = unsafe { LLVMBuildLoad2(cxt.builder, llvm_ty, alloca, ZERO_NAME) };
+        assert!(!value.is_null());
+
+        Ok(TypedValue { value, ty })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn simple_expressions() {
+        check_ast!(
+            ExprParser,
+            "[1, 2, 3]",
+            ast_node!(
+                ArrayCtor,
+                vec![ast_node!(Int, 1), ast_node!(Int, 2), ast_node!(Int, 3)]
+            )
+        );
+
+        check_ast!(
+            ExprParser,
+            "[a; 4]",
+            ast_node!(ArrayRepeat, ast_node!(Variable, "a".into(), vec![]), 4)
+        );
+    }
+
+    #[test]
+    fn nested() {
+        check_ast!(
+            ExprParser,
+            "[[0; 2], [1, 2]]",
+            ast_node!(
+                ArrayCtor,
+                vec![
+                    ast_node!(ArrayRepeat, ast_node!(Int, 0), 2),
+                    ast_node!(ArrayCtor, vec![ast_node!(Int, 1), ast_node!(Int, 2)])
+                ]
+            )
+        );
+    }
+}
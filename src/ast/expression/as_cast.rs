@@ -0,0 +1,82 @@
+use crate::ast::{Expression, TypeName};
+use crate::codegen::{TypedValue, cast_explicit};
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use nyacc_proc::Acceptor;
+
+/// Explicit conversion `expr as T`, allows narrowing, float <-> int, pointer <-> pointer
+/// and pointer <-> int conversions, which are forbidden implicitly
+#[derive(new, Acceptor, Debug)]
+pub struct As {
+    pub expr: Box<dyn Expression>,
+    pub tp: TypeName,
+}
+
+impl Expression for As {
+    fn codegen(
+        &self,
+        cxt: &mut crate::codegen::CodegenContext,
+    ) -> anyhow::Result<crate::codegen::TypedValue> {
+        let expr = self.expr.codegen(cxt)?;
+
+        let ty = cxt.resolve_type(&self.tp)?;
+        if ty.is_none() {
+            anyhow::bail!("Unknown type {} in `as` cast", self.tp);
+        }
+        let ty = ty.unwrap();
+
+        let value = cast_explicit(cxt, &expr.ty, &ty, expr.value)?;
+
+        Ok(TypedValue { value, ty })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::OpType;
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn simple_expressions() {
+        check_ast!(
+            ExprParser,
+            "a as i8",
+            ast_node!(As, ast_node!(Variable, "a".into(), vec![]), "i8".into())
+        );
+
+        check_ast!(
+            ExprParser,
+            "p as **u8",
+            ast_node!(
+                As,
+                ast_node!(Variable, "p".into(), vec![]),
+                TypeName::Pointer(Box::new(TypeName::Pointer(Box::new("u8".into()))))
+            )
+        );
+    }
+
+    #[test]
+    fn precedence() {
+        // `as` binds tighter than binary operators, but looser than unary ones
+        check_ast!(
+            ExprParser,
+            "-a as u8 + b as u8 as i32",
+            ast_node!(
+                Arithmetic,
+                ast_node!(
+                    As,
+                    ast_node!(UnaryMinus, ast_node!(Variable, "a".into(), vec![])),
+                    "u8".into()
+                ),
+                OpType::Add,
+                ast_node!(
+                    As,
+                    ast_node!(As, ast_node!(Variable, "b".into(), vec![]), "u8".into()),
+                    "i32".into()
+                )
+            )
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use crate::ast::Expression;
+use crate::codegen::ZERO_NAME;
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use llvm_sys::core::LLVMBuildNot;
+use nyacc_proc::Acceptor;
+
+/// Bitwise not `~`
+#[derive(new, Acceptor, Debug)]
+pub struct BitNot {
+    pub expr: Box<dyn Expression>,
+}
+
+impl Expression for BitNot {
+    fn codegen(
+        &self,
+        cxt: &mut crate::codegen::CodegenContext,
+    ) -> anyhow::Result<crate::codegen::TypedValue> {
+        let mut expr = self.expr.codegen(cxt)?;
+
+        expr.value = match expr.ty.underlying() {
+            crate::codegen::Type::Int(_) => unsafe {
+                LLVMBuildNot(cxt.builder, expr.value, ZERO_NAME)
+            },
+            _ => anyhow::bail!("Bitwise not on unsupported type {}", expr.ty),
+        };
+
+        assert!(!expr.value.is_null());
+
+        Ok(expr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::OpType;
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn simple_expressions() {
+        check_ast!(ExprParser, "~2", ast_node!(BitNot, ast_node!(Int, 2)));
+
+        check_ast!(
+            ExprParser,
+            "~a & b",
+            ast_node!(
+                Arithmetic,
+                ast_node!(BitNot, ast_node!(Variable, "a".into(), vec![])),
+                OpType::BitAnd,
+                ast_node!(Variable, "b".into(), vec![])
+            )
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use crate::ast::{Comparator, Expression};
-use crate::codegen::{Type, ZERO_NAME, bool_from_llvm, cast};
+use crate::codegen::{Type, ZERO_NAME, bool_from_llvm, cast, cast_explicit, common_operand_type};
 use crate::visitor::{Acceptor, Visitor};
 use derive_new::new;
 use nyacc_proc::Acceptor;
@@ -16,24 +16,55 @@ impl Expression for Compare {
         &self,
         cxt: &mut crate::codegen::CodegenContext,
     ) -> anyhow::Result<crate::codegen::TypedValue> {
-        let lhs_tv = self.lhs.codegen(cxt)?;
-        let rhs_tv = self.rhs.codegen(cxt)?;
+        let mut lhs_tv = self.lhs.codegen(cxt)?;
+        let mut rhs_tv = self.rhs.codegen(cxt)?;
 
-        let common_type = Type::common_type(&lhs_tv.ty, &rhs_tv.ty)?;
+        // C-like enums are compared by tags
+        if let Type::Enum(enum_type) = lhs_tv.ty.dealias() {
+            if lhs_tv.ty != rhs_tv.ty || !enum_type.is_c_like() {
+                anyhow::bail!("Can't compare {} with {}", lhs_tv.ty, rhs_tv.ty);
+            }
+            if !matches!(self.cmp, Comparator::EQ | Comparator::NE) {
+                anyhow::bail!("Enums can only be compared with == and !=");
+            }
+
+            let tag_ty = cxt.definitions.get_type("i64").unwrap();
+            for tv in [&mut lhs_tv, &mut rhs_tv] {
+                tv.value = cast_explicit(cxt, &tv.ty, &tag_ty, tv.value)?;
+                tv.ty = tag_ty.clone();
+            }
+        }
+
+        let common_type = common_operand_type(&lhs_tv, &rhs_tv)?;
 
         let lhs = cast(cxt, &lhs_tv.ty, &common_type, lhs_tv.value)?;
         let rhs = cast(cxt, &rhs_tv.ty, &common_type, rhs_tv.value)?;
 
+        if let Type::Pointer(_) | Type::Func(..) = common_type.underlying()
+            && !matches!(self.cmp, Comparator::EQ | Comparator::NE)
+        {
+            anyhow::bail!("Pointers can only be compared with == and !=");
+        }
+
         macro_rules! dispatch_binop {
-            ($([$op:tt, $float_pred:tt, $int_pred:tt ]),+) => {
+            ($([$op:tt, $float_pred:tt, $sint_pred:tt, $uint_pred:tt ]),+) => {
                 match self.cmp {
                 $(
                     Comparator::$op => {
-                        let res = match common_type.as_ref() {
-                            Type::Int(_) => {
+                        let res = match common_type.underlying() {
+                            Type::Int(int) if int.signed => {
+                                unsafe {llvm_sys::core::LLVMBuildICmp(
+                                    cxt.builder,
+                                    llvm_sys::LLVMIntPredicate::$sint_pred,
+                                    lhs,
+                                    rhs,
+                                    ZERO_NAME
+                                )}
+                            },
+                            Type::Int(_) | Type::Pointer(_) | Type::Func(..) => {
                                 unsafe {llvm_sys::core::LLVMBuildICmp(
                                     cxt.builder,
-                                    llvm_sys::LLVMIntPredicate::$int_pred,
+                                    llvm_sys::LLVMIntPredicate::$uint_pred,
                                     lhs,
                                     rhs,
                                     ZERO_NAME
@@ -59,12 +90,12 @@ impl Expression for Compare {
         }
 
         let cmp_res = dispatch_binop!(
-            [LE, LLVMRealOLE, LLVMIntSLE],
-            [GE, LLVMRealOGE, LLVMIntSGE],
-            [LT, LLVMRealOLT, LLVMIntSLT],
-            [GT, LLVMRealOGT, LLVMIntSGT],
-            [EQ, LLVMRealOEQ, LLVMIntEQ],
-            [NE, LLVMRealONE, LLVMIntNE]
+            [LE, LLVMRealOLE, LLVMIntSLE, LLVMIntULE],
+            [GE, LLVMRealOGE, LLVMIntSGE, LLVMIntUGE],
+            [LT, LLVMRealOLT, LLVMIntSLT, LLVMIntULT],
+            [GT, LLVMRealOGT, LLVMIntSGT, LLVMIntUGT],
+            [EQ, LLVMRealOEQ, LLVMIntEQ, LLVMIntEQ],
+            [NE, LLVMRealONE, LLVMIntNE, LLVMIntNE]
         );
 
         Ok(bool_from_llvm(cxt, cmp_res))
@@ -1,22 +1,34 @@
+use std::rc::Rc;
+
 use crate::ast::Expression;
-use crate::codegen::{CodegenContext, TypedValue};
+use crate::codegen::macros::c_str;
+use crate::codegen::{CodegenContext, Type, TypedValue};
 use crate::visitor::{Acceptor, Visitor};
 use derive_new::new;
-use llvm_sys::core::{LLVMConstInt, LLVMConstReal, LLVMDoubleTypeInContext, LLVMIntTypeInContext};
+use llvm_sys::LLVMLinkage;
+use llvm_sys::LLVMUnnamedAddr;
+use llvm_sys::core::{
+    LLVMAddGlobal, LLVMConstInt, LLVMConstPointerNull, LLVMConstReal, LLVMConstStringInContext2,
+    LLVMInt1TypeInContext, LLVMPointerTypeInContext, LLVMSetGlobalConstant, LLVMSetInitializer,
+    LLVMSetLinkage, LLVMSetUnnamedAddress, LLVMTypeOf,
+};
 use nyacc_proc::Acceptor;
 
+/// Float literal, `f64` unless it has type suffix (`2.0f32`)
 #[derive(new, Acceptor, Debug)]
 pub struct Float {
     pub val: f64,
+    #[new(default)]
+    pub ty: Option<String>,
 }
 
 impl Expression for Float {
     fn codegen(&self, cxt: &mut CodegenContext) -> anyhow::Result<TypedValue> {
-        let val;
-        unsafe {
-            let ty = LLVMDoubleTypeInContext(cxt.cxt);
-            val = LLVMConstReal(ty, self.val);
-        }
+        let ty = cxt
+            .definitions
+            .get_type(self.ty.as_deref().unwrap_or("f64"))
+            .unwrap();
+        let val = unsafe { LLVMConstReal(ty.llvm_type(cxt), self.val) };
 
         assert!(
             !val.is_null(),
@@ -24,31 +36,140 @@ impl Expression for Float {
             self.val
         );
 
-        Ok(TypedValue {
-            value: val,
-            ty: cxt.definitions.get_type("f64").unwrap(),
-        })
+        Ok(TypedValue { value: val, ty })
     }
 }
 
+/// Integer literal, `i64` unless it has type suffix (`10u8`).
+/// Unsuffixed literal which doesn't fit into `i64` (like `0xFFFF_FFFF_FFFF_FFFF`) is `u64`
 #[derive(new, Acceptor, Debug)]
 pub struct Int {
     pub val: u64,
+    #[new(default)]
+    pub ty: Option<String>,
 }
 
-impl Expression for Int {
-    fn codegen(&self, cxt: &mut CodegenContext) -> anyhow::Result<TypedValue> {
-        let val;
-        unsafe {
-            let ty = LLVMIntTypeInContext(cxt.cxt, 64);
-            val = LLVMConstInt(ty, self.val, 0);
+impl Int {
+    /// Literal with unary minus, `-val` is checked against range of signed type instead of `val`,
+    /// so minimal values like `-128i8` and `-9223372036854775808` can be written
+    pub fn codegen_negated(&self, cxt: &mut CodegenContext) -> anyhow::Result<TypedValue> {
+        self.build(cxt, true)
+    }
+
+    fn build(&self, cxt: &mut CodegenContext, negated: bool) -> anyhow::Result<TypedValue> {
+        let min_abs = i64::MIN.unsigned_abs();
+        let ty = match self.ty.as_deref() {
+            Some(ty) => ty,
+            None if self.val > i64::MAX as u64 && !(negated && self.val == min_abs) => "u64",
+            None => "i64",
+        };
+        let ty = cxt.definitions.get_type(ty).unwrap();
+
+        // Lexer lets signed literals be one bigger than maximum, it's valid only with minus
+        if let Type::Int(int) = ty.as_ref()
+            && int.signed
+            && self.val > (1 << (int.bitwidth - 1)) - !negated as u64
+        {
+            anyhow::bail!(
+                "Literal {}{} is out of range of {}",
+                self.val,
+                self.ty.as_deref().unwrap_or_default(),
+                ty
+            );
         }
 
+        let val = if negated {
+            self.val.wrapping_neg()
+        } else {
+            self.val
+        };
+        let val = unsafe { LLVMConstInt(ty.llvm_type(cxt), val, 0) };
+
         assert!(!val.is_null(), "Failed to construct const int {}", self.val);
 
+        Ok(TypedValue { value: val, ty })
+    }
+}
+
+impl Expression for Int {
+    fn codegen(&self, cxt: &mut CodegenContext) -> anyhow::Result<TypedValue> {
+        self.build(cxt, false)
+    }
+}
+
+/// `true` / `false` literals
+#[derive(new, Acceptor, Debug)]
+pub struct Bool {
+    pub val: bool,
+}
+
+impl Expression for Bool {
+    fn codegen(&self, cxt: &mut CodegenContext) -> anyhow::Result<TypedValue> {
+        let val = unsafe { LLVMConstInt(LLVMInt1TypeInContext(cxt.cxt), self.val as u64, 0) };
+        assert!(
+            !val.is_null(),
+            "Failed to construct const bool {}",
+            self.val
+        );
+
+        Ok(TypedValue {
+            value: val,
+            ty: cxt.definitions.get_type("bool").unwrap(),
+        })
+    }
+}
+
+/// `null` literal, has `*void` type so it's castable to any pointer
+#[derive(new, Acceptor, Debug)]
+pub struct Null {}
+
+impl Expression for Null {
+    fn codegen(&self, cxt: &mut CodegenContext) -> anyhow::Result<TypedValue> {
+        let val = unsafe { LLVMConstPointerNull(LLVMPointerTypeInContext(cxt.cxt, 0)) };
+        assert!(!val.is_null(), "Failed to construct null pointer");
+
         Ok(TypedValue {
             value: val,
-            ty: cxt.definitions.get_type("i64").unwrap(),
+            ty: Rc::new(Type::Pointer(cxt.definitions.get_type("void").unwrap())),
+        })
+    }
+}
+
+/// String literal, stored as private null terminated global and has `str` type
+#[derive(new, Acceptor, Debug)]
+pub struct Str {
+    pub val: String,
+}
+
+impl Expression for Str {
+    fn codegen(&self, cxt: &mut CodegenContext) -> anyhow::Result<TypedValue> {
+        let val = unsafe {
+            LLVMConstStringInContext2(
+                cxt.cxt,
+                self.val.as_ptr() as *const _,
+                self.val.len(),
+                false as i32,
+            )
+        };
+        assert!(
+            !val.is_null(),
+            "Failed to construct const string {:?}",
+            self.val
+        );
+
+        let global = unsafe {
+            let global = LLVMAddGlobal(cxt.module, LLVMTypeOf(val), c_str!(c".str"));
+            LLVMSetInitializer(global, val);
+            LLVMSetGlobalConstant(global, true as i32);
+            LLVMSetLinkage(global, LLVMLinkage::LLVMPrivateLinkage);
+            LLVMSetUnnamedAddress(global, LLVMUnnamedAddr::LLVMGlobalUnnamedAddr);
+            global
+        };
+        assert!(!global.is_null());
+
+        Ok(TypedValue {
+            value: global,
+            ty: cxt.definitions.get_type("str").unwrap(),
         })
     }
 }
@@ -56,10 +177,11 @@ impl Expression for Int {
 #[cfg(test)]
 mod tests {
     use crate::grammar;
+    use crate::lexer::Lexer;
 
     macro_rules! check_parser {
         ($parser:tt, $str:expr, $expect:expr) => {
-            let res = grammar::$parser::new().parse($str);
+            let res = grammar::$parser::new().parse(Lexer::new($str));
             if let Err(e) = &res {
                 panic!("Failed with err {:?}", e);
             }
@@ -73,8 +195,8 @@ mod tests {
         check_parser!(IDParser, "abc", "abc");
         check_parser!(IDParser, "s2", "s2");
         check_parser!(IDParser, "under_score", "under_score");
-        assert!(grammar::IDParser::new().parse("2ba").is_err());
-        assert!(grammar::IDParser::new().parse("@@a").is_err());
+        assert!(grammar::IDParser::new().parse(Lexer::new("2ba")).is_err());
+        assert!(grammar::IDParser::new().parse(Lexer::new("@@a")).is_err());
     }
 
     #[test]
@@ -85,5 +207,54 @@ mod tests {
         check_parser!(FloatParser, "3.0", 3.0);
         check_parser!(FloatParser, "1.99", 1.99);
         check_parser!(FloatParser, "12.", 12.);
+
+        check_parser!(IntParser, "0x1F", 31);
+        check_parser!(IntParser, "0b1010", 10);
+        check_parser!(IntParser, "1_000_000", 1000000);
+        check_parser!(FloatParser, "1e-3", 0.001);
+        check_parser!(FloatParser, "2.5E2", 250.0);
+        // Sizes and indices can't have type suffix
+        assert!(grammar::IntParser::new().parse(Lexer::new("10u8")).is_err());
+    }
+
+    #[test]
+    fn parse_typed_literals() {
+        let res = grammar::ExprParser::new().parse(Lexer::new("10u8"));
+        assert_eq!(
+            format!("{:?}", res.unwrap()),
+            r#"Int { val: 10, ty: Some("u8") }"#
+        );
+        let res = grammar::ExprParser::new().parse(Lexer::new("2.0f32"));
+        assert_eq!(
+            format!("{:?}", res.unwrap()),
+            r#"Float { val: 2.0, ty: Some("f32") }"#
+        );
+        let res = grammar::ExprParser::new().parse(Lexer::new("'a'"));
+        assert_eq!(
+            format!("{:?}", res.unwrap()),
+            r#"Int { val: 97, ty: Some("i8") }"#
+        );
+    }
+
+    #[test]
+    fn parse_bool() {
+        let res = grammar::ExprParser::new().parse(Lexer::new("true"));
+        assert_eq!(format!("{:?}", res.unwrap()), "Bool { val: true }");
+        let res = grammar::ExprParser::new().parse(Lexer::new("false"));
+        assert_eq!(format!("{:?}", res.unwrap()), "Bool { val: false }");
+    }
+
+    #[test]
+    fn parse_str() {
+        let res = grammar::ExprParser::new().parse(Lexer::new(r#""a\tb\n""#));
+        assert!(res.is_ok());
+        assert_eq!(format!("{:?}", res.unwrap()), r#"Str { val: "a\tb\n" }"#);
+    }
+
+    #[test]
+    fn parse_null() {
+        let res = grammar::ExprParser::new().parse(Lexer::new("null"));
+        assert!(res.is_ok());
+        assert_eq!(format!("{:?}", res.unwrap()), "Null");
     }
 }
@@ -0,0 +1,89 @@
+use crate::ast::Expression;
+use crate::codegen::{CodegenContext, Type, TypedValue, ZERO_NAME};
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use llvm_sys::core::LLVMBuildLoad2;
+use nyacc_proc::Acceptor;
+
+#[derive(new, Acceptor, Debug)]
+pub struct Deref {
+    pub expr: Box<dyn Expression>,
+}
+
+impl Expression for Deref {
+    fn codegen(&self, cxt: &mut CodegenContext) -> anyhow::Result<TypedValue> {
+        let ptr = self.codegen_ptr(cxt)?;
+
+        let value =
+            unsafe { LLVMBuildLoad2(cxt.builder, ptr.ty.llvm_type(cxt), ptr.value, ZERO_NAME) };
+        assert!(!value.is_null());
+
+        Ok(TypedValue { value, ty: ptr.ty })
+    }
+
+    fn codegen_ptr(&self, cxt: &mut CodegenContext) -> anyhow::Result<TypedValue> {
+        let ptr = self.expr.codegen(cxt)?;
+
+        let Type::Pointer(pointee) = ptr.ty.dealias() else {
+            anyhow::bail!("Dereference of non-pointer type {}", ptr.ty);
+        };
+        if **pointee == Type::Void() {
+            anyhow::bail!("Dereference of void pointer");
+        }
+
+        Ok(TypedValue {
+            value: ptr.value,
+            ty: pointee.clone(),
+        })
+    }
+
+    fn is_place(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::OpType;
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn simple_expressions() {
+        check_ast!(
+            ExprParser,
+            "*a",
+            ast_node!(Deref, ast_node!(Variable, "a".into(), vec![]))
+        );
+
+        check_ast!(
+            ExprParser,
+            "**a",
+            ast_node!(
+                Deref,
+                ast_node!(Deref, ast_node!(Variable, "a".into(), vec![]))
+            )
+        );
+    }
+
+    #[test]
+    fn eval_order() {
+        check_ast!(
+            ExprParser,
+            "*a.b",
+            ast_node!(Deref, ast_node!(Variable, "a".into(), vec!["b".into()]))
+        );
+
+        check_ast!(
+            ExprParser,
+            "*a * *b",
+            ast_node!(
+                Arithmetic,
+                ast_node!(Deref, ast_node!(Variable, "a".into(), vec![])),
+                OpType::Mul,
+                ast_node!(Deref, ast_node!(Variable, "b".into(), vec![]))
+            )
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::ast::Expression;
+use crate::codegen::{CodegenContext, Type, TypedValue, ZERO_NAME, cast, position_builer_at_begin};
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use llvm_sys::core::{
+    LLVMArrayType2, LLVMBuildAlloca, LLVMBuildLoad2, LLVMBuildStore, LLVMBuildStructGEP2,
+    LLVMConstInt, LLVMConstNamedStruct, LLVMConstNull, LLVMGetEntryBasicBlock, LLVMGetInsertBlock,
+    LLVMInt64TypeInContext, LLVMPositionBuilderAtEnd,
+};
+use nyacc_proc::Acceptor;
+
+/// `module::Enum::Variant` or `module::Enum::Variant(values...)` for variants with payload.
+/// Without module prefix it's parsed as `ItemPath`, because it looks like item of module
+#[derive(new, Acceptor, Debug)]
+pub struct EnumCtor {
+    pub enum_name: String,
+    pub variant: String,
+    pub args: Vec<Box<dyn Expression>>,
+}
+
+impl Expression for EnumCtor {
+    fn codegen(
+        &self,
+        cxt: &mut crate::codegen::CodegenContext,
+    ) -> anyhow::Result<crate::codegen::TypedValue> {
+        build_enum_value(cxt, &self.enum_name, &self.variant, &self.args)
+    }
+}
+
+/// Value of variant `variant_name` of enum `enum_name`, `args` are values of its payload
+pub fn build_enum_value(
+    cxt: &mut CodegenContext,
+    enum_name: &str,
+    variant_name: &str,
+    args: &[Box<dyn Expression>],
+) -> anyhow::Result<TypedValue> {
+    let ty = cxt
+        .definitions
+        .get_type(&cxt.definitions.type_name(enum_name)?);
+    let Some(ty) = ty else {
+        anyhow::bail!("Unknown enum {}", enum_name);
+    };
+    let Type::Enum(enum_type) = ty.dealias() else {
+        anyhow::bail!("Type {} is not enum", ty);
+    };
+    let Some(variant) = enum_type.variant(variant_name) else {
+        anyhow::bail!("Enum {} has no variant {}", ty, variant_name);
+    };
+    if variant.fields.len() != args.len() {
+        anyhow::bail!(
+            "Variant {}::{} expects {} values, got {}",
+            ty,
+            variant.name,
+            variant.fields.len(),
+            args.len()
+        );
+    }
+
+    let llvm_ty = enum_type.llvm_type(cxt);
+    let tag = unsafe { LLVMConstInt(LLVMInt64TypeInContext(cxt.cxt), variant.tag as u64, 1) };
+
+    /* Variant without payload is constant, so it can be used in `const` items */
+    if variant.fields.is_empty() {
+        let value = unsafe {
+            let payload_ty =
+                LLVMArrayType2(LLVMInt64TypeInContext(cxt.cxt), enum_type.payload_words());
+            let mut fields = [tag, LLVMConstNull(payload_ty)];
+            LLVMConstNamedStruct(llvm_ty, fields.as_mut_ptr(), fields.len() as u32)
+        };
+        assert!(!value.is_null());
+        return Ok(TypedValue {
+            value,
+            ty: ty.clone(),
+        });
+    }
+
+    let cur_func = cxt.vislayers.cur_fun().unwrap().0;
+    let entry_block = unsafe { LLVMGetEntryBasicBlock(cur_func) };
+    assert!(!entry_block.is_null());
+    let current_block = unsafe { LLVMGetInsertBlock(cxt.builder) };
+    assert!(!current_block.is_null());
+
+    /* Create alloca in entry block */
+    position_builer_at_begin(cxt, entry_block);
+    let alloca = unsafe { LLVMBuildAlloca(cxt.builder, llvm_ty, ZERO_NAME) };
+    assert!(!alloca.is_null());
+    unsafe { LLVMPositionBuilderAtEnd(cxt.builder, current_block) };
+
+    let variant_ty = enum_type.variant_llvm_type(cxt, variant);
+    let fields = variant.fields.clone();
+    unsafe {
+        let tag_ptr = LLVMBuildStructGEP2(cxt.builder, llvm_ty, alloca, 0, ZERO_NAME);
+        LLVMBuildStore(cxt.builder, tag, tag_ptr);
+    }
+    let payload_ptr = unsafe { LLVMBuildStructGEP2(cxt.builder, llvm_ty, alloca, 1, ZERO_NAME) };
+    assert!(!payload_ptr.is_null());
+
+    for (pos, (arg, field_ty)) in args.iter().zip(fields).enumerate() {
+        let value = arg.codegen(cxt)?;
+        let value = cast(cxt, &value.ty, &field_ty, value.value)?;
+
+        unsafe {
+            let field_ptr =
+                LLVMBuildStructGEP2(cxt.builder, variant_ty, payload_ptr, pos as u32, ZERO_NAME);
+            assert!(!field_ptr.is_null());
+            LLVMBuildStore(cxt.builder, value, field_ptr);
+        }
+    }
+
+    /* Load because of expression semantics */
+    let value = unsafe { LLVMBuildLoad2(cxt.builder, llvm_ty, alloca, ZERO_NAME) };
+    assert!(!value.is_null());
+
+    Ok(TypedValue { value, ty })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn module_path() {
+        check_ast!(
+            ExprParser,
+            "geometry::Shape::Empty()",
+            ast_node!(EnumCtor, "geometry::Shape".into(), "Empty".into(), vec![])
+        );
+
+        check_ast!(
+            ExprParser,
+            "geometry::Shape::Rect(1, a)",
+            ast_node!(
+                EnumCtor,
+                "geometry::Shape".into(),
+                "Rect".into(),
+                vec![ast_node!(Int, 1), ast_node!(Variable, "a".into(), vec![])]
+            )
+        );
+
+        check_ast!(
+            ExprParser,
+            "geometry::Point { x: 1 }",
+            ast_node!(
+                StructCtor,
+                "geometry::Point".into(),
+                vec![FieldInit::new("x".into(), ast_node!(Int, 1))]
+            )
+        );
+    }
+}
@@ -1,16 +1,44 @@
 use std::ffi::CString;
+use std::rc::Rc;
 
-use crate::ast::Expression;
-use crate::codegen::{TypedValue, ZERO_NAME, cast};
+use crate::ast::{Expression, TypeName};
+use crate::codegen::{
+    CodegenContext, Type, TypedValue, ZERO_NAME, cast, method_name, position_builer_at_begin,
+};
+use crate::utils::nodes::{Float, Int, UnaryMinus};
 use crate::visitor::{Acceptor, Visitor};
 use derive_new::new;
-use llvm_sys::core::{LLVMBuildCall2, LLVMGetNamedFunction};
+use llvm_sys::core::{
+    LLVMBuildAlloca, LLVMBuildCall2, LLVMBuildFPExt, LLVMBuildLoad2, LLVMBuildSExt, LLVMBuildStore,
+    LLVMBuildStructGEP2, LLVMBuildZExt, LLVMDoubleTypeInContext, LLVMGetEntryBasicBlock,
+    LLVMGetInsertBlock, LLVMGetNamedFunction, LLVMIntTypeInContext, LLVMPositionBuilderAtEnd,
+};
+use llvm_sys::prelude::{LLVMTypeRef, LLVMValueRef};
 use nyacc_proc::Acceptor;
 
 #[derive(new, Acceptor, Debug)]
 pub struct FunctionCall {
     pub name: String,
     pub args: Vec<Box<dyn Expression>>,
+    /// `receiver.name(args)` method call, receiver is passed by pointer as `self`
+    #[new(default)]
+    pub receiver: Option<Box<dyn Expression>>,
+    /// Explicit type args of generic function, `max::<i32>(a, b)`
+    #[new(default)]
+    pub type_args: Vec<TypeName>,
+    /// Function pointer to call instead of function `name`, `table[i](x)`
+    #[new(default)]
+    pub callee: Option<Box<dyn Expression>>,
+}
+
+/// Called function with its signature
+struct Callee {
+    name: String,
+    value: LLVMValueRef,
+    llvm_type: LLVMTypeRef,
+    args: Vec<Rc<Type>>,
+    ret: Rc<Type>,
+    is_vararg: bool,
 }
 
 impl Expression for FunctionCall {
@@ -18,45 +46,356 @@ impl Expression for FunctionCall {
         &self,
         cxt: &mut crate::codegen::CodegenContext,
     ) -> anyhow::Result<crate::codegen::TypedValue> {
-        let func_type = cxt.definitions.get_func(&self.name);
-        if func_type.is_none() {
-            anyhow::bail!("Calling unknown function {}", self.name);
+        let mut computed_arg = Vec::with_capacity(self.args.len() + 1);
+
+        // Variable with function pointer shadows function with the same name
+        let local = match (&self.callee, &self.receiver) {
+            (None, None) => self.local_callee(cxt)?,
+            _ => None,
+        };
+
+        let callee = match (&self.callee, &self.receiver, local) {
+            (Some(callee), _, _) => {
+                let value = callee.codegen(cxt)?;
+                self.pointer_callee(cxt, value)?
+            }
+            (None, Some(receiver), _) => {
+                let (callee, self_ptr) = self.codegen_receiver(cxt, receiver.as_ref())?;
+                computed_arg.extend(self_ptr);
+                callee
+            }
+            (None, None, Some(value)) => self.pointer_callee(cxt, value)?,
+            (None, None, None) => {
+                return call_function(cxt, &self.name, &self.type_args, &self.args);
+            }
+        };
+
+        emit_call(cxt, callee, computed_arg, &self.args, None)
+    }
+}
+
+/// Call of function `name` as seen from current module, `geometry::area(r)` included
+pub fn call_function(
+    cxt: &mut CodegenContext,
+    name: &str,
+    type_args: &[TypeName],
+    args: &[Box<dyn Expression>],
+) -> anyhow::Result<TypedValue> {
+    let symbol = cxt.definitions.func_name(name)?;
+    if cxt.definitions.is_generic_func(&symbol) {
+        // Args of generic call are computed before instantiation to infer type args
+        let values = args
+            .iter()
+            .map(|arg| arg.codegen(cxt))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let literals = args
+            .iter()
+            .map(|arg| is_numeric_literal(arg.as_ref()))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let instance = instantiate(cxt, &symbol, type_args, &values, &literals)?;
+        let callee = named_callee(cxt, &instance)?;
+        return emit_call(cxt, callee, vec![], args, Some(values));
+    }
+    if !type_args.is_empty() {
+        anyhow::bail!("Function {} is not generic", name)
+    }
+
+    let callee = named_callee(cxt, &symbol)?;
+    emit_call(cxt, callee, vec![], args, None)
+}
+
+/// Builds call of `callee`, `computed_arg` holds already computed leading args like `self`
+fn emit_call(
+    cxt: &mut CodegenContext,
+    callee: Callee,
+    mut computed_arg: Vec<LLVMValueRef>,
+    args: &[Box<dyn Expression>],
+    arg_values: Option<Vec<TypedValue>>,
+) -> anyhow::Result<TypedValue> {
+    // `self` is already computed
+    let arg_types = &callee.args[computed_arg.len()..];
+    if args.len() < arg_types.len() || (!callee.is_vararg && args.len() > arg_types.len()) {
+        anyhow::bail!(
+            "Function {} expects {}{} arguments, but {} were given",
+            callee.name,
+            if callee.is_vararg { "at least " } else { "" },
+            arg_types.len(),
+            args.len()
+        );
+    }
+
+    for (i, arg) in args.iter().enumerate() {
+        let argval = match &arg_values {
+            Some(values) => values[i].clone(),
+            None => arg.codegen(cxt)?,
+        };
+        let casted = match arg_types.get(i) {
+            Some(argtype) => cast(cxt, &argval.ty, argtype, argval.value)?,
+            None => promote_vararg(cxt, &argval)?,
+        };
+        computed_arg.push(casted);
+    }
+
+    let call = unsafe {
+        LLVMBuildCall2(
+            cxt.builder,
+            callee.llvm_type,
+            callee.value,
+            computed_arg.as_mut_ptr(),
+            computed_arg.len() as u32,
+            ZERO_NAME,
+        )
+    };
+    assert!(!call.is_null());
+
+    Ok(TypedValue {
+        value: call,
+        ty: callee.ret,
+    })
+}
+
+/// Instantiates generic function with explicit or inferred type args, returns mangled name
+fn instantiate(
+    cxt: &mut CodegenContext,
+    name: &str,
+    type_args: &[TypeName],
+    args: &[TypedValue],
+    literals: &[bool],
+) -> anyhow::Result<String> {
+    let type_args = if type_args.is_empty() {
+        cxt.definitions.infer_type_args(name, args, literals)?
+    } else {
+        let mut resolved = Vec::with_capacity(type_args.len());
+        for type_arg in type_args {
+            let ty = cxt.resolve_type(type_arg)?;
+            if ty.is_none() {
+                anyhow::bail!("Unknown type {} in call of {}", type_arg, name);
+            }
+            resolved.push(ty.unwrap());
         }
+        resolved
+    };
+
+    cxt.instantiate_func(name, &type_args)
+}
+
+/// Stores temporary value into stack, so pointer to it can be passed as `self`.
+/// Alloca is placed in entry block, so calls in loops don't grow the stack
+fn spill(cxt: &mut CodegenContext, value: TypedValue) -> TypedValue {
+    let cur_func = cxt.vislayers.cur_fun().unwrap().0;
+    let entry_block = unsafe { LLVMGetEntryBasicBlock(cur_func) };
+    let current_block = unsafe { LLVMGetInsertBlock(cxt.builder) };
+    position_builer_at_begin(cxt, entry_block);
+    let alloca = unsafe { LLVMBuildAlloca(cxt.builder, value.ty.llvm_type(cxt), ZERO_NAME) };
+    assert!(!alloca.is_null());
+    unsafe { LLVMPositionBuilderAtEnd(cxt.builder, current_block) };
+
+    unsafe { LLVMBuildStore(cxt.builder, value.value, alloca) };
 
-        let func_type = func_type.unwrap().clone();
+    TypedValue {
+        value: alloca,
+        ty: value.ty,
+    }
+}
+
+impl FunctionCall {
+    /// Value of variable, global or constant `name` to call through.
+    /// Variables of other types don't hide functions with the same name
+    fn local_callee(&self, cxt: &mut CodegenContext) -> anyhow::Result<Option<TypedValue>> {
+        let is_func = |value: &TypedValue| matches!(value.ty.dealias(), Type::Func(..));
+        let has_func = cxt.definitions.func_name(&self.name).is_ok_and(|name| {
+            cxt.definitions.get_func(&name).is_some() || cxt.definitions.is_generic_func(&name)
+        });
+        let name = cxt.variable_name(&self.name)?;
 
-        let func_name = CString::new(self.name.clone()).unwrap();
-        let func_object = unsafe { LLVMGetNamedFunction(cxt.module, func_name.as_ptr()) };
-        assert!(!func_object.is_null()); // It exists because we founded it in cxt.definitions
+        if let Some(constant) = cxt.vislayers.get_constant(&name) {
+            return Ok((is_func(&constant) || !has_func).then_some(constant));
+        }
 
-        let mut computed_arg = Vec::with_capacity(func_type.0.len());
-        for (i, arg) in self.args.iter().enumerate() {
-            let argval = arg.codegen(cxt)?;
-            let casted = cast(cxt, &argval.ty, &func_type.0[i], argval.value)?;
-            computed_arg.push(casted);
+        let Some(var) = cxt.vislayers.get_variable(&name) else {
+            return Ok(None);
+        };
+        if !is_func(&var) && has_func {
+            return Ok(None);
         }
 
-        let llvm_func_type = cxt.type_cache.get_func(&self.name).unwrap();
+        let value =
+            unsafe { LLVMBuildLoad2(cxt.builder, var.ty.llvm_type(cxt), var.value, ZERO_NAME) };
+        assert!(!value.is_null());
+
+        Ok(Some(TypedValue { value, ty: var.ty }))
+    }
+
+    /// Call through function pointer, signature is taken from pointer type
+    fn pointer_callee(&self, cxt: &mut CodegenContext, ptr: TypedValue) -> anyhow::Result<Callee> {
+        let Type::Func(args, ret) = ptr.ty.dealias() else {
+            anyhow::bail!(
+                "Can't call {} of type {}, it's not a function",
+                self.name,
+                ptr.ty
+            );
+        };
+
+        Ok(Callee {
+            name: self.name.clone(),
+            value: ptr.value,
+            llvm_type: cxt.func_llvm_type(args, ret, false),
+            args: args.clone(),
+            ret: ret.clone(),
+            is_vararg: false,
+        })
+    }
+
+    /// Returns method & pointer to receiver, which is passed as `self`.
+    /// Receiver can be struct or pointer to struct, like with field access.
+    /// If struct has no such method, function pointer in its field is called without `self`
+    fn codegen_receiver(
+        &self,
+        cxt: &mut CodegenContext,
+        receiver: &dyn Expression,
+    ) -> anyhow::Result<(Callee, Option<LLVMValueRef>)> {
+        let place = if receiver.is_place() {
+            receiver.codegen_ptr(cxt)?
+        } else {
+            let value = receiver.codegen(cxt)?;
+            spill(cxt, value)
+        };
+
+        let (custom, self_ptr) = match place.ty.dealias() {
+            Type::Custom(custom) => (custom, place.value),
+            Type::Pointer(pointee) if matches!(pointee.dealias(), Type::Custom(_)) => {
+                let Type::Custom(custom) = pointee.dealias() else {
+                    unreachable!()
+                };
+                let ptr = unsafe {
+                    LLVMBuildLoad2(cxt.builder, place.ty.llvm_type(cxt), place.value, ZERO_NAME)
+                };
+                assert!(!ptr.is_null());
+                (custom, ptr)
+            }
+            _ => anyhow::bail!(
+                "Method {} called on value of primitive type {}",
+                self.name,
+                place.ty
+            ),
+        };
 
-        let call = unsafe {
-            LLVMBuildCall2(
+        let symbol = method_name(&custom.name, &self.name);
+        if cxt.definitions.get_func(&symbol).is_some() {
+            cxt.definitions.check_method_access(&custom.name, &symbol)?;
+            return Ok((named_callee(cxt, &symbol)?, Some(self_ptr)));
+        }
+
+        let Some((index, field_type)) = custom.fields().get(&self.name).cloned() else {
+            anyhow::bail!("Type {} has no method {}", custom.name, self.name);
+        };
+        let field_ptr = unsafe {
+            LLVMBuildStructGEP2(
                 cxt.builder,
-                *llvm_func_type,
-                func_object,
-                computed_arg.as_mut_ptr(),
-                computed_arg.len() as u32,
+                custom.llvm_type(cxt),
+                self_ptr,
+                index as u32,
                 ZERO_NAME,
             )
         };
-        assert!(!call.is_null());
+        let value =
+            unsafe { LLVMBuildLoad2(cxt.builder, field_type.llvm_type(cxt), field_ptr, ZERO_NAME) };
+        assert!(!value.is_null());
 
-        Ok(TypedValue {
-            value: call,
-            ty: func_type.1.clone(),
-        })
+        let callee = self.pointer_callee(
+            cxt,
+            TypedValue {
+                value,
+                ty: field_type,
+            },
+        )?;
+        Ok((callee, None))
     }
 }
 
+/// Argument is number literal like `3`, `-1` or `0.5`, its type is bound only weakly
+/// during inference of type args
+fn is_numeric_literal(arg: &dyn Expression) -> anyhow::Result<bool> {
+    let mut checker = LiteralChecker::default();
+    arg.accept(&mut checker)?;
+    Ok(checker.is_literal)
+}
+
+#[derive(Default)]
+struct LiteralChecker {
+    is_literal: bool,
+}
+
+impl Visitor for LiteralChecker {
+    fn visit_int(&mut self, _: &Int) -> anyhow::Result<()> {
+        self.is_literal = true;
+        Ok(())
+    }
+
+    fn visit_float(&mut self, _: &Float) -> anyhow::Result<()> {
+        self.is_literal = true;
+        Ok(())
+    }
+
+    fn visit_unaryminus(&mut self, node: &UnaryMinus) -> anyhow::Result<()> {
+        node.expr.accept(self)
+    }
+}
+
+/// Function defined or declared in program
+fn named_callee(cxt: &mut CodegenContext, symbol: &str) -> anyhow::Result<Callee> {
+    let func_type = cxt.definitions.get_func(symbol);
+    if func_type.is_none() {
+        anyhow::bail!("Calling unknown function {}", symbol);
+    }
+    let (args, ret, is_vararg) = func_type.unwrap().as_ref().clone();
+
+    let func_name = CString::new(symbol).unwrap();
+    let value = unsafe { LLVMGetNamedFunction(cxt.module, func_name.as_ptr()) };
+    assert!(!value.is_null()); // It exists because we founded it in cxt.definitions
+
+    let llvm_type = *cxt.type_cache.get_func(symbol).unwrap();
+
+    Ok(Callee {
+        name: symbol.into(),
+        value,
+        llvm_type,
+        args,
+        ret,
+        is_vararg,
+    })
+}
+
+/// C default argument promotions for arguments passed via `...`
+fn promote_vararg(cxt: &mut CodegenContext, arg: &TypedValue) -> anyhow::Result<LLVMValueRef> {
+    let res = match arg.ty.underlying() {
+        Type::Int(int) if int.bitwidth < 32 => unsafe {
+            let int_type = LLVMIntTypeInContext(cxt.cxt, 32);
+            if int.signed {
+                LLVMBuildSExt(cxt.builder, arg.value, int_type, ZERO_NAME)
+            } else {
+                LLVMBuildZExt(cxt.builder, arg.value, int_type, ZERO_NAME)
+            }
+        },
+        Type::Float(float) if float.bitwidth < 64 => unsafe {
+            LLVMBuildFPExt(
+                cxt.builder,
+                arg.value,
+                LLVMDoubleTypeInContext(cxt.cxt),
+                ZERO_NAME,
+            )
+        },
+        Type::Int(_) | Type::Float(_) | Type::Pointer(_) | Type::Func(..) => arg.value,
+        _ => anyhow::bail!(
+            "Value of type {} can't be passed as variadic argument",
+            arg.ty
+        ),
+    };
+
+    assert!(!res.is_null());
+    Ok(res)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,4 +475,64 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn method_call() {
+        let mut expected = FunctionCall::new("len".into(), vec![ast_node!(Int, 1)]);
+        expected.receiver = Some(ast_node!(Variable, "a".into(), vec!["b".into()]));
+        let expected = Box::new(expected);
+        check_ast!(ExprParser, "a.b.len(1)", expected);
+
+        let mut expected = FunctionCall::new("len".into(), vec![]);
+        expected.receiver = Some(ast_node!(Variable, "self".into(), vec![]));
+        let expected = Box::new(expected);
+        check_ast!(ExprParser, "self.len()", expected);
+
+        let mut expected = FunctionCall::new("len".into(), vec![]);
+        let mut receiver = FunctionCall::new("shift".into(), vec![]);
+        receiver.receiver = Some(ast_node!(FunctionCall, "make".into(), vec![]));
+        expected.receiver = Some(Box::new(receiver));
+        let expected = Box::new(expected);
+        check_ast!(ExprParser, "make().shift().len()", expected);
+
+        let mut expected = FunctionCall::new("len".into(), vec![]);
+        expected.receiver = Some(ast_node!(StructCtor, "S".into(), vec![]));
+        let expected = Box::new(expected);
+        check_ast!(ExprParser, "S {}.len()", expected);
+    }
+
+    #[test]
+    fn pointer_call() {
+        let mut expected = FunctionCall::new("ops".into(), vec![ast_node!(Int, 1)]);
+        expected.callee = Some(ast_node!(
+            Variable,
+            "ops".into(),
+            vec!["list".into(), Accessor::Index(ast_node!(Int, 0))]
+        ));
+        let expected = Box::new(expected);
+        check_ast!(ExprParser, "ops.list[0](1)", expected);
+    }
+
+    #[test]
+    fn explicit_type_args() {
+        let mut expected = FunctionCall::new("max".into(), vec![ast_node!(Int, 1)]);
+        expected.type_args = vec![
+            "i32".into(),
+            TypeName::Generic("Pair".into(), vec!["u8".into()]),
+        ];
+        let expected = Box::new(expected);
+        check_ast!(ExprParser, "max::<i32, Pair<u8>>(1)", expected);
+
+        // Shifts are not confused with type args
+        check_ast!(
+            ExprParser,
+            "a >> b",
+            ast_node!(
+                Arithmetic,
+                ast_node!(Variable, "a".into(), vec![]),
+                OpType::Shr,
+                ast_node!(Variable, "b".into(), vec![])
+            )
+        );
+    }
 }
@@ -0,0 +1,64 @@
+use crate::ast::Expression;
+use crate::ast::expression::{Variable, build_enum_value, call_function};
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use nyacc_proc::Acceptor;
+
+/// `prefix::name` or `prefix::name(values...)`: item of module `prefix`
+/// (global or function call) or variant of enum `prefix`.
+/// Modules are known only after loading, so they are told apart at codegen
+#[derive(new, Acceptor, Debug)]
+pub struct ItemPath {
+    pub prefix: String,
+    pub name: String,
+    /// Values in parens, `None` if path is written without them
+    pub args: Option<Vec<Box<dyn Expression>>>,
+}
+
+impl Expression for ItemPath {
+    fn codegen(
+        &self,
+        cxt: &mut crate::codegen::CodegenContext,
+    ) -> anyhow::Result<crate::codegen::TypedValue> {
+        if !cxt.definitions.is_module(&self.prefix) {
+            let args = self.args.as_deref().unwrap_or_default();
+            return build_enum_value(cxt, &self.prefix, &self.name, args);
+        }
+
+        let path = format!("{}::{}", self.prefix, self.name);
+        match &self.args {
+            Some(args) => call_function(cxt, &path, &[], args),
+            None => Variable::new(path, vec![]).codegen(cxt),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::Expression;
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn without_args() {
+        check_ast!(
+            ExprParser,
+            "Color::Red",
+            ast_node!(ItemPath, "Color".into(), "Red".into(), None)
+        );
+    }
+
+    #[test]
+    fn with_args() {
+        let args: Vec<Box<dyn Expression>> =
+            vec![ast_node!(Int, 1), ast_node!(Variable, "a".into(), vec![])];
+        let expected = ast_node!(ItemPath, "geometry".into(), "area".into(), Some(args));
+        check_ast!(ExprParser, "geometry::area(1, a)", expected);
+
+        check_ast!(
+            ExprParser,
+            "Shape::Empty()",
+            ast_node!(ItemPath, "Shape".into(), "Empty".into(), Some(vec![]))
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use crate::ast::{Expression, LogicOp};
+use crate::codegen::macros::c_str;
+use crate::codegen::{ZERO_NAME, bool_from_llvm, bool_from_value};
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use llvm_sys::core::{
+    LLVMAddIncoming, LLVMAppendBasicBlockInContext, LLVMBuildBr, LLVMBuildCondBr, LLVMBuildPhi,
+    LLVMConstInt, LLVMConstIntGetZExtValue, LLVMGetInsertBlock, LLVMInt1TypeInContext,
+    LLVMIsAConstantInt, LLVMPositionBuilderAtEnd,
+};
+use nyacc_proc::Acceptor;
+
+/// Short-circuit `&&` and `||`, rhs is evaluated only if lhs doesn't determine the result
+#[derive(new, Acceptor, Debug)]
+pub struct Logical {
+    pub lhs: Box<dyn Expression>,
+    pub op: LogicOp,
+    pub rhs: Box<dyn Expression>,
+}
+
+impl Expression for Logical {
+    fn codegen(
+        &self,
+        cxt: &mut crate::codegen::CodegenContext,
+    ) -> anyhow::Result<crate::codegen::TypedValue> {
+        // Value of whole expression if rhs is skipped
+        let short_value = match self.op {
+            LogicOp::And => false,
+            LogicOp::Or => true,
+        };
+
+        let lhs = self.lhs.codegen(cxt)?;
+        let lhs = bool_from_value(cxt, &lhs)?;
+
+        /* Constant lhs is folded without control flow, so `true && false` is constant too */
+        if unsafe { !LLVMIsAConstantInt(lhs.value).is_null() } {
+            if unsafe { LLVMConstIntGetZExtValue(lhs.value) } == short_value as u64 {
+                return Ok(lhs);
+            }
+            let rhs = self.rhs.codegen(cxt)?;
+            return bool_from_value(cxt, &rhs);
+        }
+
+        let Some(&(cur_func, _)) = cxt.vislayers.cur_fun() else {
+            anyhow::bail!(
+                "Operator {} with non-constant operands outside of function",
+                self.op
+            );
+        };
+        // lhs codegen could introduce new blocks
+        let lhs_block = unsafe { LLVMGetInsertBlock(cxt.builder) };
+
+        let rhs_block =
+            unsafe { LLVMAppendBasicBlockInContext(cxt.cxt, cur_func, c_str!(c"logic_rhs")) };
+        let cont_block =
+            unsafe { LLVMAppendBasicBlockInContext(cxt.cxt, cur_func, c_str!(c"logic_cont")) };
+
+        unsafe {
+            match self.op {
+                LogicOp::And => LLVMBuildCondBr(cxt.builder, lhs.value, rhs_block, cont_block),
+                LogicOp::Or => LLVMBuildCondBr(cxt.builder, lhs.value, cont_block, rhs_block),
+            };
+            LLVMPositionBuilderAtEnd(cxt.builder, rhs_block);
+        }
+
+        let rhs = self.rhs.codegen(cxt)?;
+        let rhs = bool_from_value(cxt, &rhs)?;
+        let rhs_end_block = unsafe { LLVMGetInsertBlock(cxt.builder) };
+
+        unsafe {
+            LLVMBuildBr(cxt.builder, cont_block);
+            LLVMPositionBuilderAtEnd(cxt.builder, cont_block);
+        }
+
+        let phi = unsafe {
+            let bool_type = LLVMInt1TypeInContext(cxt.cxt);
+            let phi = LLVMBuildPhi(cxt.builder, bool_type, ZERO_NAME);
+
+            let mut incoming_values = [LLVMConstInt(bool_type, short_value as u64, 0), rhs.value];
+            let mut incoming_blocks = [lhs_block, rhs_end_block];
+            LLVMAddIncoming(
+                phi,
+                incoming_values.as_mut_ptr(),
+                incoming_blocks.as_mut_ptr(),
+                2,
+            );
+
+            phi
+        };
+        assert!(!phi.is_null());
+
+        Ok(bool_from_llvm(cxt, phi))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Comparator;
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn simple_expressions() {
+        check_ast!(
+            ExprParser,
+            "a && b",
+            ast_node!(
+                Logical,
+                ast_node!(Variable, "a".into(), vec![]),
+                LogicOp::And,
+                ast_node!(Variable, "b".into(), vec![])
+            )
+        );
+
+        check_ast!(
+            ExprParser,
+            "true || false",
+            ast_node!(
+                Logical,
+                ast_node!(Bool, true),
+                LogicOp::Or,
+                ast_node!(Bool, false)
+            )
+        );
+    }
+
+    #[test]
+    fn precedence() {
+        // && binds tighter than ||, comparison binds tighter than both
+        check_ast!(
+            ExprParser,
+            "a || b && c > 0",
+            ast_node!(
+                Logical,
+                ast_node!(Variable, "a".into(), vec![]),
+                LogicOp::Or,
+                ast_node!(
+                    Logical,
+                    ast_node!(Variable, "b".into(), vec![]),
+                    LogicOp::And,
+                    ast_node!(
+                        Compare,
+                        ast_node!(Variable, "c".into(), vec![]),
+                        Comparator::GT,
+                        ast_node!(Int, 0)
+                    )
+                )
+            )
+        );
+
+        check_ast!(
+            ExprParser,
+            "!a && b",
+            ast_node!(
+                Logical,
+                ast_node!(Not, ast_node!(Variable, "a".into(), vec![])),
+                LogicOp::And,
+                ast_node!(Variable, "b".into(), vec![])
+            )
+        );
+    }
+}
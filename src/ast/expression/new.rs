@@ -0,0 +1,183 @@
+use std::rc::Rc;
+
+use crate::ast::{Expression, TypeName};
+use crate::codegen::{CodegenContext, Type, TypedValue, ZERO_NAME, cast_explicit};
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use llvm_sys::LLVMIntPredicate;
+use llvm_sys::core::{
+    LLVMBuildCall2, LLVMBuildExtractValue, LLVMBuildICmp, LLVMBuildOr, LLVMBuildSelect,
+    LLVMBuildStore, LLVMConstInt, LLVMConstNull, LLVMGetIntrinsicDeclaration,
+    LLVMInt64TypeInContext, LLVMIntrinsicGetType, LLVMLookupIntrinsicID, LLVMSizeOf,
+};
+use llvm_sys::prelude::LLVMValueRef;
+use nyacc_proc::Acceptor;
+
+use super::StructCtor;
+
+/// `new T { ... }`, value is built as usual struct ctor and stored into heap block
+#[derive(new, Acceptor, Debug)]
+pub struct NewObject {
+    pub ctor: StructCtor,
+}
+
+impl Expression for NewObject {
+    fn codegen(&self, cxt: &mut CodegenContext) -> anyhow::Result<TypedValue> {
+        let value = self.ctor.codegen(cxt)?;
+
+        let size = unsafe { LLVMSizeOf(value.ty.llvm_type(cxt)) };
+        let ptr = build_alloc(cxt, size)?;
+        unsafe { LLVMBuildStore(cxt.builder, value.value, ptr) };
+
+        Ok(TypedValue {
+            value: ptr,
+            ty: Rc::new(Type::Pointer(value.ty)),
+        })
+    }
+}
+
+/// `new [T; n]`, pointer to `n` zero initialized elements, `n` is computed at runtime
+#[derive(new, Acceptor, Debug)]
+pub struct NewArray {
+    pub elem: TypeName,
+    pub len: Box<dyn Expression>,
+}
+
+impl Expression for NewArray {
+    fn codegen(&self, cxt: &mut CodegenContext) -> anyhow::Result<TypedValue> {
+        let elem = cxt.resolve_type(&self.elem)?;
+        if elem.is_none() {
+            anyhow::bail!("Allocation of unknown type {}", self.elem);
+        }
+        let elem = elem.unwrap();
+        if *elem == Type::Void() {
+            anyhow::bail!("Allocation of void array");
+        }
+
+        let len = self.len.codegen(cxt)?;
+        if !matches!(len.ty.underlying(), Type::Int(_)) {
+            anyhow::bail!(
+                "Length of allocated array should be integer, not {}",
+                len.ty
+            );
+        }
+        let i64_type = cxt.definitions.get_type("i64").unwrap();
+        let len = cast_explicit(cxt, &len.ty, &i64_type, len.value)?;
+
+        let elem_size = unsafe { LLVMSizeOf(elem.llvm_type(cxt)) };
+        let size = build_array_size(cxt, len, elem_size);
+        let ptr = build_alloc(cxt, size)?;
+
+        Ok(TypedValue {
+            value: ptr,
+            ty: Rc::new(Type::Pointer(elem)),
+        })
+    }
+}
+
+/// `len * elem_size`, negative length or overflow gives size `-1`, which is rejected by `nya_alloc`
+fn build_array_size(
+    cxt: &mut CodegenContext,
+    len: LLVMValueRef,
+    elem_size: LLVMValueRef,
+) -> LLVMValueRef {
+    let name = "llvm.umul.with.overflow";
+    unsafe {
+        let i64_type = LLVMInt64TypeInContext(cxt.cxt);
+        let mut param_types = [i64_type];
+        let id = LLVMLookupIntrinsicID(name.as_ptr() as *const _, name.len());
+        assert!(id != 0, "Unknown intrinsic {}", name);
+        let func = LLVMGetIntrinsicDeclaration(cxt.module, id, param_types.as_mut_ptr(), 1);
+        let func_type = LLVMIntrinsicGetType(cxt.cxt, id, param_types.as_mut_ptr(), 1);
+
+        let mut args = [len, elem_size];
+        let res = LLVMBuildCall2(
+            cxt.builder,
+            func_type,
+            func,
+            args.as_mut_ptr(),
+            args.len() as u32,
+            ZERO_NAME,
+        );
+        let size = LLVMBuildExtractValue(cxt.builder, res, 0, ZERO_NAME);
+        let overflow = LLVMBuildExtractValue(cxt.builder, res, 1, ZERO_NAME);
+
+        let negative = LLVMBuildICmp(
+            cxt.builder,
+            LLVMIntPredicate::LLVMIntSLT,
+            len,
+            LLVMConstNull(i64_type),
+            ZERO_NAME,
+        );
+        let invalid = LLVMBuildOr(cxt.builder, negative, overflow, ZERO_NAME);
+        let size = LLVMBuildSelect(
+            cxt.builder,
+            invalid,
+            LLVMConstInt(i64_type, u64::MAX, 1),
+            size,
+            ZERO_NAME,
+        );
+        assert!(!size.is_null());
+
+        size
+    }
+}
+
+/// Calls `nya_alloc` from nyastd, returned memory is zeroed
+fn build_alloc(cxt: &mut CodegenContext, size: LLVMValueRef) -> anyhow::Result<LLVMValueRef> {
+    let i64_type = cxt.definitions.get_type("i64").unwrap();
+    let void_ptr = Type::Pointer(cxt.definitions.get_type("void").unwrap());
+    let (func, func_type) = cxt.std_function("nya_alloc", &[i64_type], &void_ptr)?;
+
+    let mut args = [size];
+    let ptr = unsafe {
+        LLVMBuildCall2(
+            cxt.builder,
+            func_type,
+            func,
+            args.as_mut_ptr(),
+            args.len() as u32,
+            ZERO_NAME,
+        )
+    };
+    assert!(!ptr.is_null());
+
+    Ok(ptr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn simple_expressions() {
+        check_ast!(
+            ExprParser,
+            "new Point { x: 1 }",
+            ast_node!(
+                NewObject,
+                StructCtor::new(
+                    "Point".into(),
+                    vec![FieldInit::new("x".into(), ast_node!(Int, 1))]
+                )
+            )
+        );
+
+        check_ast!(
+            ExprParser,
+            "new [i64; n + 1]",
+            ast_node!(
+                NewArray,
+                "i64".into(),
+                ast_node!(
+                    Arithmetic,
+                    ast_node!(Variable, "n".into(), vec![]),
+                    crate::ast::OpType::Add,
+                    ast_node!(Int, 1)
+                )
+            )
+        );
+    }
+}
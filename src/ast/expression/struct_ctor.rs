@@ -1,16 +1,27 @@
-use crate::ast::Expression;
-use crate::codegen::{TypedValue, ZERO_NAME, position_builer_at_begin};
+use std::collections::HashSet;
+
+use crate::ast::{Expression, TypeName};
+use crate::codegen::{Type, TypedValue, ZERO_NAME, cast};
 use crate::visitor::{Acceptor, Visitor};
 use derive_new::new;
-use llvm_sys::core::{
-    LLVMBuildAlloca, LLVMBuildLoad2, LLVMBuildMemSet, LLVMConstInt, LLVMGetEntryBasicBlock,
-    LLVMGetInsertBlock, LLVMIntTypeInContext, LLVMPositionBuilderAtEnd, LLVMSizeOf,
-};
+use llvm_sys::core::{LLVMBuildInsertValue, LLVMConstNull};
 use nyacc_proc::Acceptor;
 
+/// `field: value` in struct ctor
+#[derive(new, Debug)]
+pub struct FieldInit {
+    pub name: String,
+    pub value: Box<dyn Expression>,
+}
+
+/// `Name { field: value, ... }`, fields which are not listed are zero initialized
 #[derive(new, Acceptor, Debug)]
 pub struct StructCtor {
     pub name: String,
+    pub fields: Vec<FieldInit>,
+    /// Type args of generic struct, `Pair::<i32> { ... }`
+    #[new(default)]
+    pub type_args: Vec<TypeName>,
 }
 
 impl Expression for StructCtor {
@@ -18,39 +29,47 @@ impl Expression for StructCtor {
         &self,
         cxt: &mut crate::codegen::CodegenContext,
     ) -> anyhow::Result<crate::codegen::TypedValue> {
-        let cur_func = cxt.vislayers.cur_fun().unwrap().0;
-        let entry_block = unsafe { LLVMGetEntryBasicBlock(cur_func) };
-        assert!(!entry_block.is_null());
-        let current_block = unsafe { LLVMGetInsertBlock(cxt.builder) };
-        assert!(!current_block.is_null());
-
-        let ty = cxt.definitions.get_type(&self.name);
+        let type_name = match self.type_args.is_empty() {
+            true => TypeName::Named(self.name.clone()),
+            false => TypeName::Generic(self.name.clone(), self.type_args.clone()),
+        };
+        let ty = cxt.resolve_type(&type_name)?;
         if ty.is_none() {
-            anyhow::bail!("Ctor for unknown type {}", self.name);
+            anyhow::bail!("Ctor for unknown type {}", type_name);
         }
         let ty = ty.unwrap();
         let llvm_ty = ty.llvm_type(cxt);
 
-        /* Create alloca in entry block */
-        position_builer_at_begin(cxt, entry_block);
-        let alloca = unsafe { LLVMBuildAlloca(cxt.builder, llvm_ty, ZERO_NAME) };
-        assert!(!alloca.is_null());
-        unsafe { LLVMPositionBuilderAtEnd(cxt.builder, current_block) };
+        /* Start from zero value, constant fields are folded by builder,
+         * so struct of constants is constant and can be used in `const` items */
+        let mut value = unsafe { LLVMConstNull(llvm_ty) };
 
-        /* Zero init variable */
-        unsafe {
-            let res = LLVMBuildMemSet(
-                cxt.builder,
-                alloca,
-                LLVMConstInt(LLVMIntTypeInContext(cxt.cxt, 64), 0, 0),
-                LLVMSizeOf(llvm_ty),
-                1,
-            );
-            assert!(!res.is_null());
-        }
+        /* Store explicitly initialized fields, in order of appearance */
+        let mut initialized = HashSet::new();
+        for FieldInit {
+            name: field_name,
+            value: field_value,
+        } in &self.fields
+        {
+            let Type::Custom(custom) = ty.dealias() else {
+                anyhow::bail!("Ctor of primitive type {} can't have fields", ty);
+            };
+            let field = custom.fields().get(field_name);
+            if field.is_none() {
+                anyhow::bail!("Type {} has no field {}", ty, field_name);
+            }
+            let (pos, field_ty) = field.unwrap().clone();
+            if !initialized.insert(field_name) {
+                anyhow::bail!("Field {} of {} is initialized twice", field_name, ty);
+            }
+
+            let field_value = field_value.codegen(cxt)?;
+            let field_value = cast(cxt, &field_value.ty, &field_ty, field_value.value)?;
 
-        /* Load because of expression semantics */
-        let value = unsafe { LLVMBuildLoad2(cxt.builder, llvm_ty, alloca, ZERO_NAME) };
+            value = unsafe {
+                LLVMBuildInsertValue(cxt.builder, value, field_value, pos as u32, ZERO_NAME)
+            };
+        }
         assert!(!value.is_null());
 
         Ok(TypedValue { value, ty })
@@ -61,14 +80,47 @@ impl Expression for StructCtor {
 mod tests {
     use super::*;
     use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
 
     #[test]
     fn simple_expressions() {
-        check_ast!(ExprParser, "S {}", ast_node!(StructCtor, "S".into()))
+        check_ast!(
+            ExprParser,
+            "S {}",
+            ast_node!(StructCtor, "S".into(), vec![])
+        )
     }
 
     #[test]
     fn complex_args() {
-        check_ast!(ExprParser, "S {}", ast_node!(StructCtor, "S".into()))
+        check_ast!(
+            ExprParser,
+            "Point { x: 1, y: 2.5, }",
+            ast_node!(
+                StructCtor,
+                "Point".into(),
+                vec![
+                    FieldInit::new("x".into(), ast_node!(Int, 1)),
+                    FieldInit::new("y".into(), ast_node!(Float, 2.5))
+                ]
+            )
+        );
+
+        check_ast!(
+            ExprParser,
+            "B { a: A { a: 1 } }",
+            ast_node!(
+                StructCtor,
+                "B".into(),
+                vec![FieldInit::new(
+                    "a".into(),
+                    ast_node!(
+                        StructCtor,
+                        "A".into(),
+                        vec![FieldInit::new("a".into(), ast_node!(Int, 1))]
+                    )
+                )]
+            )
+        );
     }
 }
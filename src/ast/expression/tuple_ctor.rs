@@ -0,0 +1,79 @@
+use std::rc::Rc;
+
+use crate::ast::Expression;
+use crate::codegen::{CodegenContext, Type, TypedValue, ZERO_NAME};
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use llvm_sys::core::{LLVMBuildInsertValue, LLVMGetUndef};
+use nyacc_proc::Acceptor;
+
+/// `(a, b, c)`, tuple has at least two elements, otherwise it's just parenthesized expression
+#[derive(new, Acceptor, Debug)]
+pub struct TupleCtor {
+    pub elements: Vec<Box<dyn Expression>>,
+}
+
+impl Expression for TupleCtor {
+    fn codegen(&self, cxt: &mut CodegenContext) -> anyhow::Result<TypedValue> {
+        let mut values = Vec::with_capacity(self.elements.len());
+        for elem in &self.elements {
+            values.push(elem.codegen(cxt)?);
+        }
+
+        let ty = Rc::new(Type::Tuple(
+            values.iter().map(|val| val.ty.clone()).collect(),
+        ));
+
+        // Constant elements are folded by builder, so tuple of constants is constant too
+        let mut tuple = unsafe { LLVMGetUndef(ty.llvm_type(cxt)) };
+        for (i, val) in values.iter().enumerate() {
+            tuple =
+                unsafe { LLVMBuildInsertValue(cxt.builder, tuple, val.value, i as u32, ZERO_NAME) };
+        }
+        assert!(!tuple.is_null());
+
+        Ok(TypedValue { value: tuple, ty })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn tuple() {
+        check_ast!(
+            ExprParser,
+            "(1, a.0, (2.5, b))",
+            ast_node!(
+                TupleCtor,
+                vec![
+                    ast_node!(Int, 1),
+                    ast_node!(Variable, "a".into(), vec!["0".into()]),
+                    ast_node!(
+                        TupleCtor,
+                        vec![
+                            ast_node!(Float, 2.5),
+                            ast_node!(Variable, "b".into(), vec![])
+                        ]
+                    )
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn nested_access() {
+        check_ast!(
+            ExprParser,
+            "t.1.0 + t.2",
+            ast_node!(
+                Arithmetic,
+                ast_node!(Variable, "t".into(), vec!["1".into(), "0".into()]),
+                crate::ast::OpType::Add,
+                ast_node!(Variable, "t".into(), vec!["2".into()])
+            )
+        );
+    }
+}
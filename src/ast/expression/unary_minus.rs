@@ -1,5 +1,6 @@
 use crate::ast::Expression;
-use crate::codegen::ZERO_NAME;
+use crate::codegen::{CodegenContext, TypedValue, ZERO_NAME};
+use crate::utils::nodes::Int;
 use crate::visitor::{Acceptor, Visitor};
 use derive_new::new;
 use llvm_sys::core::{LLVMBuildFNeg, LLVMBuildNeg};
@@ -15,9 +16,15 @@ impl Expression for UnaryMinus {
         &self,
         cxt: &mut crate::codegen::CodegenContext,
     ) -> anyhow::Result<crate::codegen::TypedValue> {
+        let mut literal = NegatedLiteral { cxt, value: None };
+        self.expr.accept(&mut literal)?;
+        if let Some(value) = literal.value {
+            return Ok(value);
+        }
+
         let mut expr = self.expr.codegen(cxt)?;
 
-        expr.value = match expr.ty.as_ref() {
+        expr.value = match expr.ty.underlying() {
             crate::codegen::Type::Float(_) => unsafe {
                 LLVMBuildFNeg(cxt.builder, expr.value, ZERO_NAME)
             },
@@ -33,6 +40,19 @@ impl Expression for UnaryMinus {
     }
 }
 
+/// Integer literal under minus is negated at once, see [`Int::codegen_negated`]
+struct NegatedLiteral<'a> {
+    cxt: &'a mut CodegenContext,
+    value: Option<TypedValue>,
+}
+
+impl Visitor for NegatedLiteral<'_> {
+    fn visit_int(&mut self, node: &Int) -> anyhow::Result<()> {
+        self.value = Some(node.codegen_negated(self.cxt)?);
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
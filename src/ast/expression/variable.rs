@@ -1,23 +1,46 @@
+use std::ffi::CString;
 use std::fmt::Display;
+use std::rc::Rc;
 
 use crate::ast::Expression;
-use crate::codegen::{CodegenContext, Type, TypedValue, ZERO_NAME};
+use crate::codegen::{CodegenContext, Type, TypedValue, ZERO_NAME, cast_explicit};
 use crate::visitor::{Acceptor, Visitor};
 use derive_new::new;
-use llvm_sys::core::{LLVMBuildGEP2, LLVMBuildLoad2, LLVMConstInt, LLVMIntTypeInContext};
+use llvm_sys::core::{
+    LLVMBuildGEP2, LLVMBuildLoad2, LLVMConstInt, LLVMConstIntGetZExtValue, LLVMGetNamedFunction,
+    LLVMIntTypeInContext, LLVMIsAConstantInt,
+};
+use llvm_sys::prelude::LLVMValueRef;
 use nyacc_proc::Acceptor;
 
+#[derive(Debug)]
+pub enum Accessor {
+    /// `.field`, or `.0` for tuple element
+    Field(String),
+    /// `[index]`
+    Index(Box<dyn Expression>),
+}
+
+impl From<&str> for Accessor {
+    fn from(value: &str) -> Self {
+        Accessor::Field(value.into())
+    }
+}
+
 #[derive(new, Acceptor, Debug)]
 pub struct Variable {
     pub name: String,
-    pub fields: Vec<String>,
+    pub fields: Vec<Accessor>,
 }
 
 impl Display for Variable {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.name)?;
         for field in &self.fields {
-            write!(f, ".{}", field)?;
+            match field {
+                Accessor::Field(name) => write!(f, ".{}", name)?,
+                Accessor::Index(_) => write!(f, "[..]")?,
+            }
         }
 
         Ok(())
@@ -26,63 +49,210 @@ impl Display for Variable {
 
 impl Variable {
     pub fn codegen_gep(&self, cxt: &mut CodegenContext) -> anyhow::Result<TypedValue> {
-        let var = cxt.vislayers.get_variable(&self.name);
+        let name = cxt.variable_name(&self.name)?;
+        let var = cxt.vislayers.get_variable(&name);
         if var.is_none() {
             anyhow::bail!("Unknown variable {}", self.name);
         }
         let mut var = var.unwrap();
         let mut indices = vec![unsafe { LLVMConstInt(LLVMIntTypeInContext(cxt.cxt, 32), 0, 0) }];
 
-        let orig_type = var.ty.clone();
-
-        for field_name in &self.fields {
-            if let Type::Custom(ty) = var.ty.as_ref() {
-                let field = ty.fields.get(field_name);
-                if field.is_none() {
-                    anyhow::bail!(
-                        "unknown field ({}) subscription of variable ({}) with type ({})",
-                        field_name,
-                        self.name,
-                        var.ty
-                    );
+        // Type of object GEP is computed from.
+        // Fields & elements of newtype are accessed as ones of wrapped type
+        let mut base_type = var.ty.clone();
+
+        for accessor in &self.fields {
+            match accessor {
+                Accessor::Field(field_name) => {
+                    /* Implicit dereference of pointer to struct: finish current GEP and continue from pointee */
+                    if let Type::Pointer(pointee) = var.ty.underlying() {
+                        let pointee = pointee.clone();
+                        var.value = load_ptr(cxt, &base_type, &var, &mut indices);
+                        var.ty = pointee;
+                        base_type = var.ty.clone();
+                        indices =
+                            vec![unsafe { LLVMConstInt(LLVMIntTypeInContext(cxt.cxt, 32), 0, 0) }];
+                    }
+
+                    if let Type::Custom(ty) = var.ty.underlying() {
+                        let field = ty.fields().get(field_name);
+                        if field.is_none() {
+                            anyhow::bail!(
+                                "unknown field ({}) subscription of variable ({}) with type ({})",
+                                field_name,
+                                self.name,
+                                var.ty
+                            );
+                        }
+                        let field = field.unwrap();
+                        indices.push(unsafe {
+                            LLVMConstInt(LLVMIntTypeInContext(cxt.cxt, 32), field.0 as u64, 0)
+                        });
+                        var.ty = field.1.clone();
+                    } else if let Type::Tuple(elems) = var.ty.underlying() {
+                        let index = field_name.parse::<usize>().ok();
+                        let Some(index) = index.filter(|index| *index < elems.len()) else {
+                            anyhow::bail!(
+                                "Tuple {} of variable ({}) has no element {}",
+                                var.ty,
+                                self.name,
+                                field_name
+                            );
+                        };
+                        let elem = elems[index].clone();
+                        indices.push(unsafe {
+                            LLVMConstInt(LLVMIntTypeInContext(cxt.cxt, 32), index as u64, 0)
+                        });
+                        var.ty = elem;
+                    } else {
+                        anyhow::bail!(
+                            "Field ({}) subscription of variable ({}) with primitive type ({})",
+                            field_name,
+                            self.name,
+                            var.ty
+                        );
+                    }
+                }
+                Accessor::Index(index) => {
+                    let index = index.codegen(cxt)?;
+                    if !matches!(index.ty.dealias(), Type::Int(_)) {
+                        anyhow::bail!(
+                            "Index of variable ({}) should be integer, not {}",
+                            self.name,
+                            index.ty
+                        );
+                    }
+                    let i64_type = cxt.definitions.get_type("i64").unwrap();
+                    let index = cast_explicit(cxt, &index.ty, &i64_type, index.value)?;
+
+                    match var.ty.clone().underlying() {
+                        Type::Array(elem, len) => {
+                            check_const_index(index, *len, &self.name)?;
+                            indices.push(index);
+                            var.ty = elem.clone();
+                        }
+                        /* Indexing through pointer: new GEP starts from pointed element */
+                        Type::Pointer(elem) if **elem != Type::Void() => {
+                            var.value = load_ptr(cxt, &base_type, &var, &mut indices);
+                            var.ty = elem.clone();
+                            base_type = var.ty.clone();
+                            indices = vec![index];
+                        }
+                        _ => anyhow::bail!(
+                            "Index subscription of variable ({}) with non-array type ({})",
+                            self.name,
+                            var.ty
+                        ),
+                    }
                 }
-                let field = field.unwrap();
-                indices.push(unsafe {
-                    LLVMConstInt(LLVMIntTypeInContext(cxt.cxt, 32), field.0 as u64, 0)
-                });
-                var.ty = field.1.clone();
-            } else {
-                anyhow::bail!(
-                    "Field ({}) subscription of variable ({}) with primitive type ({})",
-                    field_name,
-                    self.name,
-                    var.ty
-                );
             }
         }
 
         /* Get ptr of field */
-        let value = unsafe {
-            LLVMBuildGEP2(
-                cxt.builder,
-                orig_type.llvm_type(cxt),
-                var.value,
-                indices.as_mut_ptr(),
-                indices.len() as u32,
-                ZERO_NAME,
-            )
-        };
-        assert!(!value.is_null());
+        let value = build_gep(cxt, &base_type, var.value, &mut indices);
 
         Ok(TypedValue { value, ty: var.ty })
     }
 }
 
+fn build_gep(
+    cxt: &mut CodegenContext,
+    base_type: &Type,
+    ptr: LLVMValueRef,
+    indices: &mut [LLVMValueRef],
+) -> LLVMValueRef {
+    let value = unsafe {
+        LLVMBuildGEP2(
+            cxt.builder,
+            base_type.llvm_type(cxt),
+            ptr,
+            indices.as_mut_ptr(),
+            indices.len() as u32,
+            ZERO_NAME,
+        )
+    };
+    assert!(!value.is_null());
+    value
+}
+
+/// Finish current GEP chain and load pointer value stored there
+fn load_ptr(
+    cxt: &mut CodegenContext,
+    base_type: &Type,
+    var: &TypedValue,
+    indices: &mut [LLVMValueRef],
+) -> LLVMValueRef {
+    let ptr_addr = build_gep(cxt, base_type, var.value, indices);
+    let value = unsafe { LLVMBuildLoad2(cxt.builder, var.ty.llvm_type(cxt), ptr_addr, ZERO_NAME) };
+    assert!(!value.is_null());
+    value
+}
+
+/// Pointer to function `name`, `None` if there is no such function
+fn function_value(cxt: &mut CodegenContext, name: &str) -> anyhow::Result<Option<TypedValue>> {
+    let name = cxt.definitions.func_name(name)?;
+    let name = name.as_str();
+    if cxt.definitions.is_generic_func(name) {
+        anyhow::bail!("Generic function {} can't be used as value", name);
+    }
+    let Some(func_type) = cxt.definitions.get_func(name) else {
+        return Ok(None);
+    };
+
+    let (args, ret, is_vararg) = func_type.as_ref();
+    if *is_vararg {
+        anyhow::bail!("Variadic function {} can't be used as value", name);
+    }
+    let ty = Rc::new(Type::Func(args.clone(), ret.clone()));
+
+    let func_name = CString::new(name).unwrap();
+    let value = unsafe { LLVMGetNamedFunction(cxt.module, func_name.as_ptr()) };
+    assert!(!value.is_null());
+
+    Ok(Some(TypedValue { value, ty }))
+}
+
+/// Catch out of bounds access when index is known at compile time
+fn check_const_index(index: LLVMValueRef, len: u64, name: &str) -> anyhow::Result<()> {
+    if unsafe { LLVMIsAConstantInt(index) }.is_null() {
+        return Ok(());
+    }
+
+    let index = unsafe { LLVMConstIntGetZExtValue(index) };
+    if index >= len {
+        anyhow::bail!(
+            "Index {} is out of bounds of variable ({}) with length {}",
+            index as i64,
+            name,
+            len
+        );
+    }
+
+    Ok(())
+}
+
 impl Expression for Variable {
     fn codegen(
         &self,
         cxt: &mut crate::codegen::CodegenContext,
     ) -> anyhow::Result<crate::codegen::TypedValue> {
+        let name = cxt.variable_name(&self.name)?;
+
+        /* Constants are known at compile time, so there is no need to load them */
+        if self.fields.is_empty()
+            && let Some(value) = cxt.vislayers.get_constant(&name)
+        {
+            return Ok(value);
+        }
+
+        /* Name of function is a pointer to it, unless it's shadowed by variable */
+        if self.fields.is_empty()
+            && cxt.vislayers.get_variable(&name).is_none()
+            && let Some(value) = function_value(cxt, &self.name)?
+        {
+            return Ok(value);
+        }
+
         let var = self.codegen_gep(cxt)?;
 
         /* Load field */
@@ -91,12 +261,31 @@ impl Expression for Variable {
 
         Ok(TypedValue { value, ty: var.ty })
     }
+
+    fn codegen_ptr(&self, cxt: &mut CodegenContext) -> anyhow::Result<TypedValue> {
+        /* Constants live in read-only memory, so pointer to them can't leak */
+        let name = cxt.variable_name(&self.name)?;
+        if cxt.vislayers.get_constant(&name).is_some() {
+            anyhow::bail!(
+                "Constant {} is read-only, it can't be assigned or addressed",
+                self.name
+            );
+        }
+
+        self.codegen_gep(cxt)
+    }
+
+    fn is_place(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ast::OpType;
     use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::{Arithmetic, Int};
 
     #[test]
     fn simple() {
@@ -135,4 +324,49 @@ mod tests {
             ast_node!(Variable, "a12".into(), vec!["s5".into()])
         );
     }
+
+    #[test]
+    fn indices() {
+        check_ast!(
+            ExprParser,
+            "a[1]",
+            ast_node!(
+                Variable,
+                "a".into(),
+                vec![Accessor::Index(ast_node!(Int, 1))]
+            )
+        );
+
+        check_ast!(
+            ExprParser,
+            "p.items[i + 1].x",
+            ast_node!(
+                Variable,
+                "p".into(),
+                vec![
+                    "items".into(),
+                    Accessor::Index(ast_node!(
+                        Arithmetic,
+                        ast_node!(Variable, "i".into(), vec![]),
+                        OpType::Add,
+                        ast_node!(Int, 1)
+                    )),
+                    "x".into()
+                ]
+            )
+        );
+
+        check_ast!(
+            ExprParser,
+            "m[0][j]",
+            ast_node!(
+                Variable,
+                "m".into(),
+                vec![
+                    Accessor::Index(ast_node!(Int, 0)),
+                    Accessor::Index(ast_node!(Variable, "j".into(), vec![]))
+                ]
+            )
+        );
+    }
 }
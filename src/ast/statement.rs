@@ -2,11 +2,14 @@ mod assignment;
 pub use assignment::Assignment;
 
 mod let_st;
-pub use let_st::Let;
+pub use let_st::{Let, LetTuple};
 
 mod while_st;
 pub use while_st::While;
 
+mod loop_st;
+pub use loop_st::{DoWhile, InfiniteLoop};
+
 mod for_st;
 pub use for_st::For;
 
@@ -30,3 +33,33 @@ pub use expr_statement::ExprStatement;
 
 mod return_st;
 pub use return_st::Return;
+
+mod loop_control;
+pub use loop_control::{Break, Continue};
+
+mod switch_st;
+pub use switch_st::{Switch, SwitchCase};
+
+mod impl_block;
+pub use impl_block::Impl;
+
+mod global;
+pub use global::Global;
+
+mod type_alias;
+pub use type_alias::TypeAlias;
+
+mod enum_def;
+pub use enum_def::{EnumDef, EnumVariantDef};
+
+mod match_st;
+pub use match_st::{Match, MatchArm, VariantPattern};
+
+mod module;
+pub use module::{Import, Module, module_name};
+
+mod delete_st;
+pub use delete_st::Delete;
+
+mod defer_st;
+pub use defer_st::Defer;
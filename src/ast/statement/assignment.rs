@@ -1,21 +1,38 @@
-use crate::ast::{Expression, Statement, expression::Variable};
-use crate::codegen::cast;
+use crate::ast::expression::build_arithmetic;
+use crate::ast::{Expression, OpType, Statement};
+use crate::codegen::{TypedValue, ZERO_NAME, cast};
 use crate::visitor::{Acceptor, Visitor};
 use derive_new::new;
-use llvm_sys::core::LLVMBuildStore;
+use llvm_sys::core::{LLVMBuildLoad2, LLVMBuildStore};
 use nyacc_proc::Acceptor;
 
+/// `target = expr` or compound `target op= expr`, `a++` & `a--` are `a += 1` & `a -= 1`
 #[derive(new, Acceptor, Debug)]
 pub struct Assignment {
-    pub var: Variable,
+    pub target: Box<dyn Expression>,
     pub expr: Box<dyn Expression>,
+    /// Operator of compound assignment
+    #[new(default)]
+    pub op: Option<OpType>,
 }
 
 impl Statement for Assignment {
     fn codegen(&self, cxt: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
-        let var = self.var.codegen_gep(cxt)?;
+        let var = self.target.codegen_ptr(cxt)?;
 
-        let expr = self.expr.codegen(cxt)?;
+        let mut expr = self.expr.codegen(cxt)?;
+        if let Some(op) = &self.op {
+            /* Target is computed once, its pointer is used both for load and store */
+            let value =
+                unsafe { LLVMBuildLoad2(cxt.builder, var.ty.llvm_type(cxt), var.value, ZERO_NAME) };
+            assert!(!value.is_null());
+
+            let current = TypedValue {
+                value,
+                ty: var.ty.clone(),
+            };
+            expr = build_arithmetic(cxt, op, current, expr)?;
+        }
         let expr = cast(cxt, &expr.ty, &var.ty, expr.value)?;
 
         unsafe { LLVMBuildStore(cxt.builder, expr, var.value) };
@@ -26,9 +43,8 @@ impl Statement for Assignment {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-    use crate::ast::Comparator;
     use crate::ast::macros::{ast_node, check_ast};
+    use crate::ast::{Comparator, OpType};
     use crate::utils::nodes::*;
 
     #[test]
@@ -38,7 +54,7 @@ mod tests {
             "a = 12;",
             ast_node!(
                 Assignment,
-                Variable::new("a".into(), vec![]),
+                ast_node!(Variable, "a".into(), vec![]),
                 ast_node!(Int, 12)
             )
         );
@@ -48,7 +64,7 @@ mod tests {
             "a = b;",
             ast_node!(
                 Assignment,
-                Variable::new("a".into(), vec![]),
+                ast_node!(Variable, "a".into(), vec![]),
                 ast_node!(Variable, "b".into(), vec![])
             )
         );
@@ -61,7 +77,7 @@ mod tests {
             "a = b == c;",
             ast_node!(
                 Assignment,
-                Variable::new("a".into(), vec![]),
+                ast_node!(Variable, "a".into(), vec![]),
                 ast_node!(
                     Compare,
                     ast_node!(Variable, "b".into(), vec![]),
@@ -71,4 +87,43 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn through_pointer() {
+        check_ast!(
+            StatementParser,
+            "*a = 1;",
+            ast_node!(
+                Assignment,
+                ast_node!(Deref, ast_node!(Variable, "a".into(), vec![])),
+                ast_node!(Int, 1)
+            )
+        );
+
+        check_ast!(
+            StatementParser,
+            "a.b = &c;",
+            ast_node!(
+                Assignment,
+                ast_node!(Variable, "a".into(), vec!["b".into()]),
+                ast_node!(AddressOf, ast_node!(Variable, "c".into(), vec![]))
+            )
+        );
+    }
+    #[test]
+    fn compound() {
+        let mut expected = Assignment::new(
+            ast_node!(Variable, "a".into(), vec!["b".into()]),
+            ast_node!(Int, 2),
+        );
+        expected.op = Some(OpType::Shl);
+        let expected = Box::new(expected);
+        check_ast!(StatementParser, "a.b <<= 2;", expected);
+
+        let mut expected =
+            Assignment::new(ast_node!(Variable, "i".into(), vec![]), ast_node!(Int, 1));
+        expected.op = Some(OpType::Sub);
+        let expected = Box::new(expected);
+        check_ast!(StatementParser, "i--;", expected);
+    }
 }
@@ -0,0 +1,57 @@
+use std::rc::Rc;
+
+use crate::ast::Statement;
+use crate::codegen::CodegenContext;
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use nyacc_proc::Acceptor;
+
+/// `defer <statement>;`, statement is executed on exit of enclosing scope (in reverse order of defers).
+/// It's generated on every exit path, see [`CodegenContext::build_deferred`]
+#[derive(new, Acceptor, Debug)]
+pub struct Defer {
+    pub stmt: Rc<dyn Statement>,
+}
+
+impl Statement for Defer {
+    fn codegen(&self, cxt: &mut CodegenContext) -> anyhow::Result<()> {
+        if cxt.vislayers.deferred_depth().is_some() {
+            anyhow::bail!("`defer` can't be nested in deferred statement");
+        }
+        cxt.vislayers.add_deferred(self.stmt.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn simple() {
+        check_ast!(
+            StatementParser,
+            "defer close(f);",
+            ast_node!(
+                Defer,
+                Rc::new(ExprStatement::new(ast_node!(
+                    FunctionCall,
+                    "close".into(),
+                    vec![ast_node!(Variable, "f".into(), vec![])]
+                )))
+            )
+        );
+
+        check_ast!(
+            StatementParser,
+            "defer delete p;",
+            ast_node!(
+                Defer,
+                Rc::new(Delete::new(ast_node!(Variable, "p".into(), vec![])))
+            )
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use std::rc::Rc;
+
+use crate::ast::{Expression, Statement};
+use crate::codegen::{CodegenContext, Type, ZERO_NAME};
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use llvm_sys::core::LLVMBuildCall2;
+use nyacc_proc::Acceptor;
+
+/// `delete p;`, releases memory allocated by `new` via `nya_free` from nyastd
+#[derive(new, Acceptor, Debug)]
+pub struct Delete {
+    pub expr: Box<dyn Expression>,
+}
+
+impl Statement for Delete {
+    fn codegen(&self, cxt: &mut CodegenContext) -> anyhow::Result<()> {
+        let ptr = self.expr.codegen(cxt)?;
+        if !matches!(ptr.ty.dealias(), Type::Pointer(_)) {
+            anyhow::bail!("Delete of non-pointer type {}", ptr.ty);
+        }
+
+        let void_ptr = Rc::new(Type::Pointer(cxt.definitions.get_type("void").unwrap()));
+        let void = cxt.definitions.get_type("void").unwrap();
+        let (func, func_type) = cxt.std_function("nya_free", &[void_ptr], &void)?;
+
+        let mut args = [ptr.value];
+        let call = unsafe {
+            LLVMBuildCall2(
+                cxt.builder,
+                func_type,
+                func,
+                args.as_mut_ptr(),
+                args.len() as u32,
+                ZERO_NAME,
+            )
+        };
+        assert!(!call.is_null());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn simple() {
+        check_ast!(
+            StatementParser,
+            "delete p.next;",
+            ast_node!(Delete, ast_node!(Variable, "p".into(), vec!["next".into()]))
+        );
+    }
+}
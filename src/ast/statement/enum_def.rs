@@ -0,0 +1,88 @@
+use crate::ast::{Statement, TypeName};
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use nyacc_proc::Acceptor;
+
+/// `Name(T1, T2) = value` in enum definition, value can be set only explicitly
+#[derive(new, Debug)]
+pub struct EnumVariantDef {
+    pub name: String,
+    pub fields: Vec<TypeName>,
+    pub value: Option<i64>,
+}
+
+/// `enum Shape { Circle(f64), Rect(f64, f64), Empty }`, variants without explicit value
+/// are numbered from previous one as in C
+#[derive(new, Acceptor, Debug)]
+pub struct EnumDef {
+    pub name: String,
+    pub variants: Vec<EnumVariantDef>,
+    /// Doc comment (`///` lines) attached to definition
+    #[new(default)]
+    pub doc: Option<String>,
+    /// `pub` item is visible from other modules
+    #[new(default)]
+    pub is_pub: bool,
+}
+
+impl Statement for EnumDef {
+    fn codegen(&self, _: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
+        /* Everything done at context creation stage */
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn c_like() {
+        check_ast!(
+            ProgramBlockParser,
+            "enum Color { Red, Green = 5, Blue = -1, }",
+            ast_node!(
+                EnumDef,
+                "Color".into(),
+                vec![
+                    EnumVariantDef::new("Red".into(), vec![], None),
+                    EnumVariantDef::new("Green".into(), vec![], Some(5)),
+                    EnumVariantDef::new("Blue".into(), vec![], Some(-1)),
+                ]
+            )
+        )
+    }
+
+    #[test]
+    fn with_payload() {
+        check_ast!(
+            ProgramBlockParser,
+            "enum Shape { Circle(f64), Rect(f64, *i8), Empty }",
+            ast_node!(
+                EnumDef,
+                "Shape".into(),
+                vec![
+                    EnumVariantDef::new("Circle".into(), vec!["f64".into()], None),
+                    EnumVariantDef::new(
+                        "Rect".into(),
+                        vec![
+                            "f64".into(),
+                            crate::ast::TypeName::Pointer(Box::new("i8".into()))
+                        ],
+                        None
+                    ),
+                    EnumVariantDef::new("Empty".into(), vec![], None),
+                ]
+            )
+        )
+    }
+
+    #[test]
+    fn doc_comment() {
+        let mut expected = ast_node!(EnumDef, "E".into(), vec![]);
+        expected.doc = Some("Empty enum".into());
+
+        check_ast!(ProgramBlockParser, "/// Empty enum\nenum E {}", expected)
+    }
+}
@@ -1,6 +1,6 @@
 use crate::ast::{Expression, Statement};
 use crate::codegen::macros::c_str;
-use crate::codegen::{CodegenContext, bool_from_value};
+use crate::codegen::{CodegenContext, LoopTarget, bool_from_value};
 use crate::visitor::{Acceptor, Visitor};
 use derive_new::new;
 use llvm_sys::core::{
@@ -14,15 +14,20 @@ pub struct For {
     pub check: Box<dyn Expression>,
     pub step: Box<dyn Statement>,
     pub body: Vec<Box<dyn Statement>>,
+    /// Optional `'label:` for `break` & `continue` from nested loops
+    #[new(default)]
+    pub label: Option<String>,
 }
 
 impl Statement for For {
     fn codegen(&self, cxt: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
         let loopst = Loop {
             start: Some(self.start.as_ref()),
-            check: self.check.as_ref(),
+            check: Some(self.check.as_ref()),
+            check_after: false,
             step: Some(self.step.as_ref()),
             body: &self.body,
+            label: self.label.as_deref(),
         };
 
         codegen_loop(cxt, &loopst)
@@ -31,9 +36,13 @@ impl Statement for For {
 
 pub(super) struct Loop<'a> {
     pub start: Option<&'a dyn Statement>,
-    pub check: &'a dyn Expression,
+    /// `None` for infinite `loop`
+    pub check: Option<&'a dyn Expression>,
+    /// Condition is checked after each iteration, so body is executed at least once (`do ... while`)
+    pub check_after: bool,
     pub step: Option<&'a dyn Statement>,
     pub body: &'a Vec<Box<dyn Statement>>,
+    pub label: Option<&'a str>,
 }
 
 pub(super) fn codegen_loop(cxt: &mut CodegenContext, loopst: &Loop) -> anyhow::Result<()> {
@@ -42,28 +51,56 @@ pub(super) fn codegen_loop(cxt: &mut CodegenContext, loopst: &Loop) -> anyhow::R
 
     let check_block = unsafe { LLVMAppendBasicBlockInContext(cxt.cxt, cur_func, c_str!(c"check")) };
     let loop_block = unsafe { LLVMAppendBasicBlockInContext(cxt.cxt, cur_func, c_str!(c"loop")) };
+    // `continue` jumps here, so step is executed before next check
+    let step_block = unsafe { LLVMAppendBasicBlockInContext(cxt.cxt, cur_func, c_str!(c"step")) };
     let cont_block = unsafe { LLVMAppendBasicBlockInContext(cxt.cxt, cur_func, c_str!(c"cont")) };
 
     if let Some(start) = loopst.start {
         start.codegen(cxt)?;
     }
 
+    let first_block = if loopst.check_after {
+        loop_block
+    } else {
+        check_block
+    };
     unsafe {
-        LLVMBuildBr(cxt.builder, check_block);
+        LLVMBuildBr(cxt.builder, first_block);
         LLVMPositionBuilderAtEnd(cxt.builder, check_block);
     }
 
-    let check_expr = loopst.check.codegen(cxt)?;
-    let check_expr = bool_from_value(cxt, &check_expr)?;
-
-    unsafe {
-        LLVMBuildCondBr(cxt.builder, check_expr.value, loop_block, cont_block);
-        LLVMPositionBuilderAtEnd(cxt.builder, loop_block);
+    match loopst.check {
+        Some(check) => {
+            let check_expr = check.codegen(cxt)?;
+            let check_expr = bool_from_value(cxt, &check_expr)?;
+            unsafe { LLVMBuildCondBr(cxt.builder, check_expr.value, loop_block, cont_block) };
+        }
+        None => unsafe {
+            LLVMBuildBr(cxt.builder, loop_block);
+        },
     }
 
+    unsafe { LLVMPositionBuilderAtEnd(cxt.builder, loop_block) };
+
+    cxt.vislayers.enter_loop(LoopTarget {
+        label: loopst.label.map(str::to_string),
+        continue_block: step_block,
+        break_block: cont_block,
+        depth: cxt.vislayers.depth(),
+    })?;
+    // Body has own layer, so its deferred statements run on each iteration
+    cxt.vislayers.enter_layer();
     for st in loopst.body {
         st.codegen(cxt)?;
     }
+    cxt.exit_scope()?;
+    cxt.vislayers.exit_loop();
+
+    unsafe {
+        LLVMBuildBr(cxt.builder, step_block);
+        LLVMPositionBuilderAtEnd(cxt.builder, step_block);
+    }
+
     if let Some(step) = loopst.step {
         step.codegen(cxt)?;
     }
@@ -73,7 +110,7 @@ pub(super) fn codegen_loop(cxt: &mut CodegenContext, loopst: &Loop) -> anyhow::R
         LLVMPositionBuilderAtEnd(cxt.builder, cont_block);
     }
 
-    cxt.vislayers.exit_layer();
+    cxt.exit_scope()?;
 
     Ok(())
 }
@@ -94,7 +131,7 @@ mod tests {
                 For,
                 ast_node!(
                     Assignment,
-                    Variable::new("a".into(), vec![]),
+                    ast_node!(Variable, "a".into(), vec![]),
                     ast_node!(Int, 3)
                 ),
                 ast_node!(
@@ -105,7 +142,7 @@ mod tests {
                 ),
                 ast_node!(
                     Assignment,
-                    Variable::new("a".into(), vec![]),
+                    ast_node!(Variable, "a".into(), vec![]),
                     ast_node!(
                         Arithmetic,
                         ast_node!(Variable, "a".into(), vec![]),
@@ -127,7 +164,7 @@ mod tests {
                 For,
                 ast_node!(
                     Assignment,
-                    Variable::new("a".into(), vec![]),
+                    ast_node!(Variable, "a".into(), vec![]),
                     ast_node!(Int, 3)
                 ),
                 ast_node!(
@@ -138,7 +175,7 @@ mod tests {
                 ),
                 ast_node!(
                     Assignment,
-                    Variable::new("a".into(), vec![]),
+                    ast_node!(Variable, "a".into(), vec![]),
                     ast_node!(
                         Arithmetic,
                         ast_node!(Variable, "a".into(), vec![]),
@@ -149,12 +186,12 @@ mod tests {
                 vec![
                     ast_node!(
                         Assignment,
-                        Variable::new("a".into(), vec![]),
+                        ast_node!(Variable, "a".into(), vec![]),
                         ast_node!(Int, 3)
                     ),
                     ast_node!(
                         Assignment,
-                        Variable::new("a".into(), vec![]),
+                        ast_node!(Variable, "a".into(), vec![]),
                         ast_node!(Int, 7)
                     )
                 ]
@@ -178,7 +215,7 @@ mod tests {
                 ),
                 ast_node!(
                     Assignment,
-                    Variable::new("a".into(), vec![]),
+                    ast_node!(Variable, "a".into(), vec![]),
                     ast_node!(
                         Arithmetic,
                         ast_node!(Variable, "a".into(), vec![]),
@@ -189,12 +226,12 @@ mod tests {
                 vec![
                     ast_node!(
                         Assignment,
-                        Variable::new("a".into(), vec![]),
+                        ast_node!(Variable, "a".into(), vec![]),
                         ast_node!(Int, 3)
                     ),
                     ast_node!(
                         Assignment,
-                        Variable::new("a".into(), vec![]),
+                        ast_node!(Variable, "a".into(), vec![]),
                         ast_node!(Int, 7)
                     )
                 ]
@@ -1,4 +1,4 @@
-use crate::ast::{Statement, TypedArg};
+use crate::ast::{Statement, TypeName, TypedArg};
 use crate::visitor::{Acceptor, Visitor};
 use derive_new::new;
 use nyacc_proc::Acceptor;
@@ -7,7 +7,13 @@ use nyacc_proc::Acceptor;
 pub struct FuncDef {
     pub name: String,
     pub args: Vec<TypedArg>,
-    pub rettype: String,
+    pub rettype: TypeName,
+    /// C-style variadic function (`...` after named args)
+    #[new(default)]
+    pub is_vararg: bool,
+    /// Doc comment (`///` lines) attached to definition
+    #[new(default)]
+    pub doc: Option<String>,
 }
 
 impl Statement for FuncDef {
@@ -19,8 +25,8 @@ impl Statement for FuncDef {
 
 #[cfg(test)]
 mod tests {
-    use crate::ast::TypedArg;
     use crate::ast::macros::{ast_node, check_ast};
+    use crate::ast::{TypeName, TypedArg};
     use crate::utils::nodes::*;
 
     #[test]
@@ -57,4 +63,53 @@ mod tests {
             ast_node!(FuncDef, "foo".into(), vec![], "S".into())
         );
     }
+
+    #[test]
+    fn pointer_args() {
+        check_ast!(
+            ProgramBlockParser,
+            "fn foo(a: *S) -> **S;",
+            ast_node!(
+                FuncDef,
+                "foo".into(),
+                vec![TypedArg::new(
+                    "a".into(),
+                    TypeName::Pointer(Box::new("S".into()))
+                )],
+                TypeName::Pointer(Box::new(TypeName::Pointer(Box::new("S".into()))))
+            )
+        );
+    }
+
+    #[test]
+    fn vararg() {
+        let mut expected = ast_node!(
+            FuncDef,
+            "printf".into(),
+            vec![TypedArg::new(
+                "fmt".into(),
+                TypeName::Pointer(Box::new("i8".into()))
+            )],
+            "i32".into()
+        );
+        expected.is_vararg = true;
+
+        check_ast!(
+            ProgramBlockParser,
+            "fn printf(fmt: *i8, ...) -> i32;",
+            expected
+        );
+    }
+
+    #[test]
+    fn doc_comment() {
+        let mut expected = ast_node!(FuncDef, "foo".into(), vec![], "void".into());
+        expected.doc = Some("Line one\nLine two".into());
+
+        check_ast!(
+            ProgramBlockParser,
+            "/// Line one\n/// Line two\nfn foo();",
+            expected
+        );
+    }
 }
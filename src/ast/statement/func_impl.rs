@@ -1,8 +1,8 @@
 use std::ffi::CString;
 
-use crate::ast::{Statement, TypedArg};
+use crate::ast::{Statement, TypeName, TypedArg};
 use crate::codegen::macros::c_str;
-use crate::codegen::{TypedValue, ZERO_NAME};
+use crate::codegen::{Type, TypedValue, ZERO_NAME};
 use crate::visitor::{Acceptor, Visitor};
 use derive_new::new;
 use llvm_sys::core::{
@@ -15,22 +15,51 @@ use nyacc_proc::Acceptor;
 pub struct FuncImpl {
     pub name: String,
     pub args: Vec<TypedArg>,
-    pub rettype: String,
+    pub rettype: TypeName,
     pub body: Vec<Box<dyn Statement>>,
+    /// Set if `...` was used, only definitions can be variadic
+    #[new(default)]
+    pub is_vararg: bool,
+    /// Doc comment (`///` lines) attached to definition
+    #[new(default)]
+    pub doc: Option<String>,
+    /// `fn max<T>`, generic functions are generated only for used type args
+    #[new(default)]
+    pub type_params: Vec<String>,
+    /// `pub` item is visible from other modules
+    #[new(default)]
+    pub is_pub: bool,
 }
 
 impl Statement for FuncImpl {
     fn codegen(&self, cxt: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
-        let rettype = cxt.definitions.get_type(&self.rettype);
+        // Generic functions are generated per instance, see `Program::codegen`
+        if !self.type_params.is_empty() {
+            return Ok(());
+        }
+        let symbol = cxt.definitions.item_name(&self.name);
+        self.codegen_symbol(cxt, &symbol)
+    }
+}
+
+impl FuncImpl {
+    /// Codegen body into function `symbol`, which differs from name for methods
+    pub fn codegen_symbol(
+        &self,
+        cxt: &mut crate::codegen::CodegenContext,
+        symbol: &str,
+    ) -> anyhow::Result<()> {
+        let rettype = cxt.resolve_type(&self.rettype)?;
         if rettype.is_none() {
             anyhow::bail!("Unknown rettype {} in function {}", self.rettype, self.name);
         };
         let rettype = rettype.unwrap();
+        let is_void = *rettype == Type::Void();
 
         // -- Get function object
         // Note: Definitions should be generated before codegen by compile functions
 
-        let func_name = CString::new(self.name.clone()).unwrap();
+        let func_name = CString::new(symbol).unwrap();
         let func = unsafe { LLVMGetNamedFunction(cxt.module, func_name.as_ptr() as *const _) };
 
         assert!(
@@ -52,7 +81,7 @@ impl Statement for FuncImpl {
         // -- Allocate args
 
         for (i, arg) in self.args.iter().enumerate() {
-            let argtype = cxt.definitions.get_type(&arg.tp);
+            let argtype = cxt.resolve_type(&arg.tp)?;
             if argtype.is_none() {
                 anyhow::bail!(
                     "Unknown type {} in func {} argument {}",
@@ -83,14 +112,16 @@ impl Statement for FuncImpl {
             st.codegen(cxt)?;
         }
 
+        // Deferred statements of function scope
+        cxt.exit_scope()?;
+
         // Codegen ret / unreachable
-        if &self.rettype == "void" {
+        if is_void {
             unsafe { LLVMBuildRetVoid(cxt.builder) };
         } else {
             unsafe { LLVMBuildUnreachable(cxt.builder) };
         }
 
-        cxt.vislayers.exit_layer();
         cxt.vislayers.exit_function();
 
         Ok(())
@@ -141,4 +172,50 @@ mod tests {
             ast_node!(FuncImpl, "foo".into(), vec![], "S".into(), vec![])
         );
     }
+
+    #[test]
+    fn generic() {
+        let mut expected = ast_node!(
+            FuncImpl,
+            "max".into(),
+            vec![
+                TypedArg::new("a".into(), "T".into()),
+                TypedArg::new("b".into(), "T".into()),
+            ],
+            "T".into(),
+            vec![]
+        );
+        expected.type_params = vec!["T".into()];
+
+        check_ast!(
+            ProgramBlockParser,
+            "fn max<T>(a: T, b: T) -> T {}",
+            expected
+        );
+    }
+
+    #[test]
+    fn doc_comment() {
+        let mut expected = ast_node!(FuncImpl, "foo".into(), vec![], "void".into(), vec![]);
+        expected.doc = Some("Does nothing".into());
+
+        check_ast!(
+            ProgramBlockParser,
+            "/// Does nothing\nfn foo() { /* really */ }",
+            expected
+        );
+    }
+
+    #[test]
+    fn public() {
+        let mut expected = ast_node!(FuncImpl, "foo".into(), vec![], "void".into(), vec![]);
+        expected.is_pub = true;
+        expected.doc = Some("Visible from other modules".into());
+
+        check_ast!(
+            ProgramBlockParser,
+            "/// Visible from other modules\npub fn foo() {}",
+            expected
+        );
+    }
 }
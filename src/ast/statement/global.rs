@@ -0,0 +1,117 @@
+use std::ffi::CString;
+
+use crate::ast::{Expression, Statement, TypeName};
+use crate::codegen::{TypedValue, const_eval};
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use llvm_sys::LLVMLinkage;
+use llvm_sys::core::{LLVMAddGlobal, LLVMSetGlobalConstant, LLVMSetInitializer, LLVMSetLinkage};
+use nyacc_proc::Acceptor;
+
+/// Module level `const NAME: T = value;` or `static NAME: T = value;`.
+/// Both are llvm globals initialized with compile-time value,
+/// but constants are read-only and their value is used directly where possible
+#[derive(new, Acceptor, Debug)]
+pub struct Global {
+    pub name: String,
+    pub tp: TypeName,
+    pub expr: Box<dyn Expression>,
+    pub is_const: bool,
+    /// Doc comment (`///` lines) attached to definition
+    #[new(default)]
+    pub doc: Option<String>,
+    /// `pub` item is visible from other modules
+    #[new(default)]
+    pub is_pub: bool,
+}
+
+impl Global {
+    fn kind(&self) -> &'static str {
+        if self.is_const { "constant" } else { "static" }
+    }
+}
+
+impl Global {
+    /// Globals are defined before other items, so functions can use them regardless of order
+    pub fn define(&self, cxt: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
+        let ty = cxt.resolve_type(&self.tp)?;
+        if ty.is_none() {
+            anyhow::bail!("Unknown type {} of {} {}", self.tp, self.kind(), self.name);
+        }
+        let ty = ty.unwrap();
+
+        let init = const_eval(cxt, self.expr.as_ref(), &ty).map_err(|e| {
+            e.context(format!(
+                "Initializer of {} {} is not a compile-time constant",
+                self.kind(),
+                self.name
+            ))
+        })?;
+
+        let name = cxt.definitions.item_name(&self.name);
+        let name_c = CString::new(name.clone()).unwrap();
+        let global = unsafe { LLVMAddGlobal(cxt.module, ty.llvm_type(cxt), name_c.as_ptr()) };
+        assert!(!global.is_null());
+        unsafe {
+            LLVMSetInitializer(global, init);
+            LLVMSetGlobalConstant(global, self.is_const as i32);
+            LLVMSetLinkage(global, LLVMLinkage::LLVMInternalLinkage);
+        }
+
+        let const_value = self.is_const.then(|| TypedValue {
+            value: init,
+            ty: ty.clone(),
+        });
+        cxt.vislayers
+            .add_global(name, TypedValue { value: global, ty }, const_value)
+    }
+}
+
+impl Statement for Global {
+    fn codegen(&self, _: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
+        /* Everything done by Global::define before program codegen */
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::OpType;
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn constant() {
+        check_ast!(
+            GlobalDefParser,
+            "const MAX: i64 = 2 * 8;",
+            ast_node!(
+                Global,
+                "MAX".into(),
+                "i64".into(),
+                ast_node!(
+                    Arithmetic,
+                    ast_node!(Int, 2),
+                    OpType::Mul,
+                    ast_node!(Int, 8)
+                ),
+                true
+            )
+        );
+    }
+
+    #[test]
+    fn static_var() {
+        check_ast!(
+            GlobalDefParser,
+            "static counter: u32 = 0;",
+            ast_node!(
+                Global,
+                "counter".into(),
+                "u32".into(),
+                ast_node!(Int, 0),
+                false
+            )
+        );
+    }
+}
@@ -45,7 +45,7 @@ impl Statement for If {
         for st in &self.true_body {
             st.codegen(cxt)?;
         }
-        cxt.vislayers.exit_layer();
+        cxt.exit_scope()?;
 
         // -- Codegen false branch if it exists
         if let Some(false_body) = &self.else_body {
@@ -62,7 +62,7 @@ impl Statement for If {
             for st in false_body {
                 st.codegen(cxt)?;
             }
-            cxt.vislayers.exit_layer();
+            cxt.exit_scope()?;
 
             // -- Br false -> cont
             unsafe { LLVMBuildBr(cxt.builder, cont_block) };
@@ -125,4 +125,34 @@ mod tests {
             ast_node!(If, ast_node!(Int, 1), vec![], Some(vec![]))
         );
     }
+
+    #[test]
+    fn else_if() {
+        check_ast!(
+            StatementParser,
+            "if (1) {} else if (2) {} else if (3) {} else {}",
+            ast_node!(
+                If,
+                ast_node!(Int, 1),
+                vec![],
+                Some(vec![ast_node!(
+                    If,
+                    ast_node!(Int, 2),
+                    vec![],
+                    Some(vec![ast_node!(If, ast_node!(Int, 3), vec![], Some(vec![]))])
+                )])
+            )
+        );
+
+        check_ast!(
+            StatementParser,
+            "if (1) {} else if (2) {}",
+            ast_node!(
+                If,
+                ast_node!(Int, 1),
+                vec![],
+                Some(vec![ast_node!(If, ast_node!(Int, 2), vec![], None)])
+            )
+        );
+    }
 }
@@ -0,0 +1,101 @@
+use crate::ast::Statement;
+use crate::codegen::method_name;
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use nyacc_proc::Acceptor;
+
+use super::FuncImpl;
+
+/// `impl Point { fn len(self) -> f64 {...} }`, methods are usual functions with mangled names
+/// (see [`method_name`]) and `self: *Point` as first argument
+#[derive(new, Acceptor, Debug)]
+pub struct Impl {
+    pub type_name: String,
+    pub methods: Vec<FuncImpl>,
+    /// Doc comment (`///` lines) attached to block
+    #[new(default)]
+    pub doc: Option<String>,
+}
+
+impl Statement for Impl {
+    fn codegen(&self, cxt: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
+        let type_name = cxt.definitions.type_name(&self.type_name)?;
+        for method in &self.methods {
+            method.codegen_symbol(cxt, &method_name(&type_name, &method.name))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::ast::{TypeName, TypedArg};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn methods() {
+        let self_arg = TypedArg::new("self".into(), TypeName::Pointer(Box::new("Point".into())));
+
+        check_ast!(
+            ImplBlockParser,
+            "impl Point {
+                fn len(self) -> f64 { return self.x; }
+                fn scale(self, k: f64) {}
+            }",
+            ast_node!(
+                Impl,
+                "Point".into(),
+                vec![
+                    FuncImpl::new(
+                        "len".into(),
+                        vec![self_arg.clone()],
+                        "f64".into(),
+                        vec![ast_node!(
+                            Return,
+                            Some(ast_node!(Variable, "self".into(), vec!["x".into()]))
+                        )]
+                    ),
+                    FuncImpl::new(
+                        "scale".into(),
+                        vec![self_arg.clone(), TypedArg::new("k".into(), "f64".into())],
+                        "void".into(),
+                        vec![]
+                    )
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn public_method() {
+        let self_arg = TypedArg::new("self".into(), TypeName::Pointer(Box::new("Point".into())));
+        let mut method = FuncImpl::new("len".into(), vec![self_arg], "f64".into(), vec![]);
+        method.is_pub = true;
+        let expected = ast_node!(Impl, "Point".into(), vec![method]);
+
+        check_ast!(
+            ImplBlockParser,
+            "impl Point { pub fn len(self) -> f64 {} }",
+            expected
+        );
+    }
+
+    #[test]
+    fn doc_comment() {
+        let mut expected = ast_node!(Impl, "Point".into(), vec![]);
+        expected.doc = Some("Methods".into());
+
+        check_ast!(ImplBlockParser, "/// Methods\nimpl Point {}", expected)
+    }
+
+    #[test]
+    fn empty() {
+        check_ast!(
+            ImplBlockParser,
+            "impl Point {}",
+            ast_node!(Impl, "Point".into(), vec![])
+        );
+    }
+}
@@ -1,65 +1,123 @@
-use crate::ast::{Expression, Statement};
-use crate::codegen::{TypedValue, ZERO_NAME, cast, position_builer_at_begin};
+use crate::ast::{Expression, Statement, TypeName};
+use crate::codegen::{CodegenContext, Type, TypedValue, ZERO_NAME, cast, position_builer_at_begin};
 use crate::visitor::{Acceptor, Visitor};
 use derive_new::new;
 use llvm_sys::core::{
-    LLVMBuildAlloca, LLVMBuildStore, LLVMGetEntryBasicBlock, LLVMGetInsertBlock,
-    LLVMPositionBuilderAtEnd,
+    LLVMBuildAlloca, LLVMBuildExtractValue, LLVMBuildStore, LLVMGetEntryBasicBlock,
+    LLVMGetInsertBlock, LLVMPositionBuilderAtEnd,
 };
 use nyacc_proc::Acceptor;
 
 #[derive(new, Acceptor, Debug)]
 pub struct Let {
     pub var: String,
-    pub tp: Option<String>,
+    pub tp: Option<TypeName>,
     pub expr: Box<dyn Expression>,
 }
 
 impl Statement for Let {
     fn codegen(&self, cxt: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
-        let cur_func = cxt.vislayers.cur_fun().unwrap().0;
-        let entry_block = unsafe { LLVMGetEntryBasicBlock(cur_func) };
-        assert!(!entry_block.is_null());
-        let current_block = unsafe { LLVMGetInsertBlock(cxt.builder) };
-        assert!(!current_block.is_null());
-
-        // Codegen expr
-
-        let mut expr = self.expr.codegen(cxt)?;
-        if let Some(typename) = &self.tp {
-            let ty = cxt.definitions.get_type(typename);
-            if ty.is_none() {
-                anyhow::bail!("Unknown type {} in let statement", typename);
-            }
-            let ty = ty.unwrap();
-            expr.value = cast(cxt, &expr.ty, &ty, expr.value)?;
-            expr.ty = ty;
+        // Codegen expr, it may introduce new blocks
+        let expr = codegen_typed(cxt, self.expr.as_ref(), &self.tp)?;
+        declare_local(cxt, self.var.clone(), expr);
+
+        Ok(())
+    }
+}
+
+/// `let (a, b) = tuple;`, element bound to `_` is skipped
+#[derive(new, Acceptor, Debug)]
+pub struct LetTuple {
+    pub vars: Vec<String>,
+    pub tp: Option<TypeName>,
+    pub expr: Box<dyn Expression>,
+}
+
+impl Statement for LetTuple {
+    fn codegen(&self, cxt: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
+        let expr = codegen_typed(cxt, self.expr.as_ref(), &self.tp)?;
+        let Type::Tuple(elems) = expr.ty.dealias() else {
+            anyhow::bail!("Can't destructure value of non-tuple type {}", expr.ty);
+        };
+        if elems.len() != self.vars.len() {
+            anyhow::bail!(
+                "Can't destructure tuple {} into {} variables",
+                expr.ty,
+                self.vars.len()
+            );
         }
 
-        // Codegen alloca in entry block
-        position_builer_at_begin(cxt, entry_block);
-        let alloca = unsafe { LLVMBuildAlloca(cxt.builder, expr.ty.llvm_type(cxt), ZERO_NAME) };
-        assert!(!alloca.is_null());
-
-        // Return into normal block
-        unsafe { LLVMPositionBuilderAtEnd(cxt.builder, current_block) };
-        unsafe { LLVMBuildStore(cxt.builder, expr.value, alloca) };
-
-        // -- Remember var
-        cxt.vislayers.add_variable(
-            self.var.clone(),
-            TypedValue {
-                value: alloca,
-                ty: expr.ty,
-            },
-        );
+        for (i, (var, ty)) in self.vars.iter().zip(elems).enumerate() {
+            if var == "_" {
+                continue;
+            }
+            let value =
+                unsafe { LLVMBuildExtractValue(cxt.builder, expr.value, i as u32, ZERO_NAME) };
+            assert!(!value.is_null());
+            declare_local(
+                cxt,
+                var.clone(),
+                TypedValue {
+                    value,
+                    ty: ty.clone(),
+                },
+            );
+        }
 
         Ok(())
     }
 }
 
+/// Value of `expr` converted to type `tp` if it's given
+fn codegen_typed(
+    cxt: &mut CodegenContext,
+    expr: &dyn Expression,
+    tp: &Option<TypeName>,
+) -> anyhow::Result<TypedValue> {
+    let mut expr = expr.codegen(cxt)?;
+    if let Some(typename) = tp {
+        let ty = cxt.resolve_type(typename)?;
+        if ty.is_none() {
+            anyhow::bail!("Unknown type {} in let statement", typename);
+        }
+        let ty = ty.unwrap();
+        expr.value = cast(cxt, &expr.ty, &ty, expr.value)?;
+        expr.ty = ty;
+    }
+    Ok(expr)
+}
+
+/// Stores value into new local variable, its alloca is placed in entry block
+fn declare_local(cxt: &mut CodegenContext, name: String, value: TypedValue) {
+    let cur_func = cxt.vislayers.cur_fun().unwrap().0;
+    let entry_block = unsafe { LLVMGetEntryBasicBlock(cur_func) };
+    assert!(!entry_block.is_null());
+
+    let current_block = unsafe { LLVMGetInsertBlock(cxt.builder) };
+    assert!(!current_block.is_null());
+
+    // Codegen alloca in entry block
+    position_builer_at_begin(cxt, entry_block);
+    let alloca = unsafe { LLVMBuildAlloca(cxt.builder, value.ty.llvm_type(cxt), ZERO_NAME) };
+    assert!(!alloca.is_null());
+
+    // Return into normal block
+    unsafe { LLVMPositionBuilderAtEnd(cxt.builder, current_block) };
+    unsafe { LLVMBuildStore(cxt.builder, value.value, alloca) };
+
+    // -- Remember var
+    cxt.vislayers.add_variable(
+        name,
+        TypedValue {
+            value: alloca,
+            ty: value.ty,
+        },
+    );
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::ast::TypeName;
     use crate::ast::macros::{ast_node, check_ast};
     use crate::utils::nodes::*;
 
@@ -80,4 +138,75 @@ mod tests {
             ast_node!(Let, "a".into(), Some("u8".into()), ast_node!(Int, 1))
         );
     }
+
+    #[test]
+    fn pointer_type() {
+        check_ast!(
+            StatementParser,
+            "let a: **u8 = null;",
+            ast_node!(
+                Let,
+                "a".into(),
+                Some(TypeName::Pointer(Box::new(TypeName::Pointer(Box::new(
+                    "u8".into()
+                ))))),
+                Box::new(Null::new())
+            )
+        );
+    }
+
+    #[test]
+    fn array_type() {
+        check_ast!(
+            StatementParser,
+            "let a: [*i32; 4] = [null; 4];",
+            ast_node!(
+                Let,
+                "a".into(),
+                Some(TypeName::Array(
+                    Box::new(TypeName::Pointer(Box::new("i32".into()))),
+                    4
+                )),
+                ast_node!(ArrayRepeat, Box::new(Null::new()), 4)
+            )
+        );
+    }
+
+    #[test]
+    fn func_type() {
+        check_ast!(
+            StatementParser,
+            "let f: fn(i64, *u8) -> fn(i64) = null;",
+            ast_node!(
+                Let,
+                "f".into(),
+                Some(TypeName::Func(
+                    vec!["i64".into(), TypeName::Pointer(Box::new("u8".into()))],
+                    Box::new(TypeName::Func(vec!["i64".into()], Box::new("void".into())))
+                )),
+                Box::new(Null::new())
+            )
+        );
+    }
+
+    #[test]
+    fn destructuring() {
+        check_ast!(
+            StatementParser,
+            "let (q, _): (i64, f64) = divmod(a, b);",
+            ast_node!(
+                LetTuple,
+                vec!["q".into(), "_".into()],
+                Some(TypeName::Tuple(vec!["i64".into(), "f64".into()])),
+                ast_node!(
+                    FunctionCall,
+                    "divmod".into(),
+                    vec![
+                        ast_node!(Variable, "a".into(), vec![]),
+                        ast_node!(Variable, "b".into(), vec![])
+                    ]
+                )
+            )
+        );
+    }
 }
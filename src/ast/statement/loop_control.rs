@@ -0,0 +1,94 @@
+use crate::ast::Statement;
+use crate::codegen::CodegenContext;
+use crate::codegen::macros::c_str;
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use llvm_sys::core::{LLVMAppendBasicBlockInContext, LLVMBuildBr, LLVMPositionBuilderAtEnd};
+use llvm_sys::prelude::LLVMBasicBlockRef;
+use nyacc_proc::Acceptor;
+
+/// `break;` or `break 'label;`, leaves innermost (or labeled) loop, deferred statements of left scopes are run
+#[derive(new, Acceptor, Debug)]
+pub struct Break {
+    pub label: Option<String>,
+}
+
+impl Statement for Break {
+    fn codegen(&self, cxt: &mut CodegenContext) -> anyhow::Result<()> {
+        let target = find_target(cxt, "break", self.label.as_deref())?;
+        let (block, depth) = (target.break_block, target.depth);
+        cxt.build_deferred(depth)?;
+        jump_to(cxt, block);
+        Ok(())
+    }
+}
+
+/// `continue;` or `continue 'label;`, goes to the next iteration of innermost (or labeled) loop
+#[derive(new, Acceptor, Debug)]
+pub struct Continue {
+    pub label: Option<String>,
+}
+
+impl Statement for Continue {
+    fn codegen(&self, cxt: &mut CodegenContext) -> anyhow::Result<()> {
+        let target = find_target(cxt, "continue", self.label.as_deref())?;
+        let (block, depth) = (target.continue_block, target.depth);
+        cxt.build_deferred(depth)?;
+        jump_to(cxt, block);
+        Ok(())
+    }
+}
+
+fn find_target<'a>(
+    cxt: &'a CodegenContext,
+    stmt: &str,
+    label: Option<&str>,
+) -> anyhow::Result<&'a crate::codegen::LoopTarget> {
+    match (cxt.vislayers.find_loop(label), label) {
+        // Deferred statement is generated at exit points, so it can jump only within itself
+        (Some(target), _) if cxt.vislayers.deferred_depth() > Some(target.depth) => {
+            anyhow::bail!("`{}` can't leave deferred statement", stmt)
+        }
+        (Some(target), _) => Ok(target),
+        (None, Some(label)) => anyhow::bail!("Unknown loop label '{} in `{}`", label, stmt),
+        (None, None) => anyhow::bail!("`{}` outside of a loop", stmt),
+    }
+}
+
+fn jump_to(cxt: &mut CodegenContext, target: LLVMBasicBlockRef) {
+    let cur_func = cxt.vislayers.cur_fun().unwrap().0;
+
+    unsafe {
+        LLVMBuildBr(cxt.builder, target);
+        // Same trick as for return: following code is unreachable, but still must be emitted somewhere
+        let unreach_block =
+            LLVMAppendBasicBlockInContext(cxt.cxt, cur_func, c_str!(c"unreachable"));
+        LLVMPositionBuilderAtEnd(cxt.builder, unreach_block);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn plain() {
+        check_ast!(StatementParser, "break;", ast_node!(Break, None));
+        check_ast!(StatementParser, "continue;", ast_node!(Continue, None));
+    }
+
+    #[test]
+    fn labeled() {
+        check_ast!(
+            StatementParser,
+            "break 'outer;",
+            ast_node!(Break, Some("outer".into()))
+        );
+        check_ast!(
+            StatementParser,
+            "continue 'outer;",
+            ast_node!(Continue, Some("outer".into()))
+        );
+    }
+}
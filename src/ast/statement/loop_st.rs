@@ -0,0 +1,101 @@
+use crate::{
+    ast::{Expression, Statement},
+    visitor::{Acceptor, Visitor},
+};
+use derive_new::new;
+use nyacc_proc::Acceptor;
+
+use super::for_st::{Loop, codegen_loop};
+
+/// `loop { ... }`, infinite loop which is left via `break` or `return`
+#[derive(new, Acceptor, Debug)]
+pub struct InfiniteLoop {
+    pub body: Vec<Box<dyn Statement>>,
+    /// Optional `'label:` for `break` & `continue` from nested loops
+    #[new(default)]
+    pub label: Option<String>,
+}
+
+impl Statement for InfiniteLoop {
+    fn codegen(&self, cxt: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
+        let loopst = Loop {
+            start: None,
+            check: None,
+            check_after: false,
+            step: None,
+            body: &self.body,
+            label: self.label.as_deref(),
+        };
+
+        codegen_loop(cxt, &loopst)
+    }
+}
+
+/// `do { ... } while (cond);`, condition is checked after body, `continue` jumps to the check
+#[derive(new, Acceptor, Debug)]
+pub struct DoWhile {
+    pub body: Vec<Box<dyn Statement>>,
+    pub cond: Box<dyn Expression>,
+    /// Optional `'label:` for `break` & `continue` from nested loops
+    #[new(default)]
+    pub label: Option<String>,
+}
+
+impl Statement for DoWhile {
+    fn codegen(&self, cxt: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
+        let loopst = Loop {
+            start: None,
+            check: Some(self.cond.as_ref()),
+            check_after: true,
+            step: None,
+            body: &self.body,
+            label: self.label.as_deref(),
+        };
+
+        codegen_loop(cxt, &loopst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::Comparator;
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn infinite() {
+        check_ast!(
+            StatementParser,
+            "loop { break; }",
+            ast_node!(InfiniteLoop, vec![ast_node!(Break, None)])
+        );
+
+        let mut expected = InfiniteLoop::new(vec![ast_node!(Continue, Some("outer".into()))]);
+        expected.label = Some("outer".into());
+        let expected = Box::new(expected);
+
+        check_ast!(
+            StatementParser,
+            "'outer: loop { continue 'outer; }",
+            expected
+        )
+    }
+
+    #[test]
+    fn do_while() {
+        check_ast!(
+            StatementParser,
+            "do { 1; } while (a < 3);",
+            ast_node!(
+                DoWhile,
+                vec![ast_node!(ExprStatement, ast_node!(Int, 1))],
+                ast_node!(
+                    Compare,
+                    ast_node!(Variable, "a".into(), vec![]),
+                    Comparator::LT,
+                    ast_node!(Int, 3)
+                )
+            )
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use crate::ast::{Expression, Statement};
+use crate::codegen::macros::c_str;
+use crate::codegen::{Type, TypedValue, ZERO_NAME, position_builer_at_begin};
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use llvm_sys::core::{
+    LLVMAddCase, LLVMAppendBasicBlockInContext, LLVMBuildAlloca, LLVMBuildBr, LLVMBuildLoad2,
+    LLVMBuildStore, LLVMBuildStructGEP2, LLVMBuildSwitch, LLVMBuildUnreachable, LLVMConstInt,
+    LLVMGetEntryBasicBlock, LLVMGetInsertBlock, LLVMInt64TypeInContext, LLVMPositionBuilderAtEnd,
+};
+use nyacc_proc::Acceptor;
+
+/// `Enum::Variant(a, b)` pattern, `_` bindings are ignored
+#[derive(new, Debug)]
+pub struct VariantPattern {
+    pub enum_name: String,
+    pub variant: String,
+    pub bindings: Vec<String>,
+}
+
+/// `pattern => { ... }` branch of [`Match`], pattern is `None` for `_` arm
+#[derive(new, Debug)]
+pub struct MatchArm {
+    pub pattern: Option<VariantPattern>,
+    pub body: Vec<Box<dyn Statement>>,
+}
+
+/// `match (expr) { Shape::Circle(r) => {...} _ => {...} }` on enums, lowered to llvm `switch` on tag.
+/// Arms are checked to be exhaustive by definitions pass, bindings refer to copy of matched value
+#[derive(new, Acceptor, Debug)]
+pub struct Match {
+    pub value: Box<dyn Expression>,
+    pub arms: Vec<MatchArm>,
+}
+
+impl Statement for Match {
+    fn codegen(&self, cxt: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
+        let cur_func = cxt.vislayers.cur_fun().unwrap().0;
+
+        let value = self.value.codegen(cxt)?;
+
+        // Only `_` arm, nothing to dispatch
+        let Some(pattern) = self.arms.iter().find_map(|arm| arm.pattern.as_ref()) else {
+            for arm in &self.arms {
+                codegen_arm(cxt, arm, vec![])?;
+            }
+            return Ok(());
+        };
+
+        let enum_name = cxt.definitions.type_name(&pattern.enum_name)?;
+        let enum_ty = cxt.definitions.get_type(&enum_name).unwrap();
+        if enum_ty != value.ty {
+            anyhow::bail!(
+                "Match on value of type {}, but patterns are of enum {}",
+                value.ty,
+                enum_ty
+            );
+        }
+        let Type::Enum(enum_type) = enum_ty.dealias() else {
+            unreachable!("patterns are checked by definitions pass");
+        };
+        let llvm_ty = enum_type.llvm_type(cxt);
+
+        /* Matched value is copied, so bindings can point into it */
+        let entry_block = unsafe { LLVMGetEntryBasicBlock(cur_func) };
+        let current_block = unsafe { LLVMGetInsertBlock(cxt.builder) };
+        position_builer_at_begin(cxt, entry_block);
+        let alloca = unsafe { LLVMBuildAlloca(cxt.builder, llvm_ty, ZERO_NAME) };
+        assert!(!alloca.is_null());
+        unsafe { LLVMPositionBuilderAtEnd(cxt.builder, current_block) };
+
+        let (tag, payload_ptr) = unsafe {
+            LLVMBuildStore(cxt.builder, value.value, alloca);
+            let i64_type = LLVMInt64TypeInContext(cxt.cxt);
+            let tag_ptr = LLVMBuildStructGEP2(cxt.builder, llvm_ty, alloca, 0, ZERO_NAME);
+            let tag = LLVMBuildLoad2(cxt.builder, i64_type, tag_ptr, ZERO_NAME);
+            let payload_ptr = LLVMBuildStructGEP2(cxt.builder, llvm_ty, alloca, 1, ZERO_NAME);
+            (tag, payload_ptr)
+        };
+
+        let cont_block =
+            unsafe { LLVMAppendBasicBlockInContext(cxt.cxt, cur_func, c_str!(c"match_cont")) };
+        // Without `_` arm default is unreachable, since match is exhaustive
+        let default_block =
+            unsafe { LLVMAppendBasicBlockInContext(cxt.cxt, cur_func, c_str!(c"match_default")) };
+
+        let patterns = self.arms.iter().filter(|arm| arm.pattern.is_some()).count();
+        let switch = unsafe { LLVMBuildSwitch(cxt.builder, tag, default_block, patterns as u32) };
+        assert!(!switch.is_null());
+
+        let mut has_default = false;
+        for arm in &self.arms {
+            let Some(pattern) = &arm.pattern else {
+                unsafe { LLVMPositionBuilderAtEnd(cxt.builder, default_block) };
+                codegen_arm(cxt, arm, vec![])?;
+                unsafe { LLVMBuildBr(cxt.builder, cont_block) };
+                has_default = true;
+                continue;
+            };
+
+            let variant = enum_type.variant(&pattern.variant).unwrap();
+            let variant_ty = enum_type.variant_llvm_type(cxt, variant);
+
+            let arm_block =
+                unsafe { LLVMAppendBasicBlockInContext(cxt.cxt, cur_func, c_str!(c"match_arm")) };
+            unsafe {
+                let tag = LLVMConstInt(LLVMInt64TypeInContext(cxt.cxt), variant.tag as u64, 1);
+                LLVMAddCase(switch, tag, arm_block);
+                LLVMPositionBuilderAtEnd(cxt.builder, arm_block);
+            }
+
+            let mut bindings = vec![];
+            for (pos, (name, field_ty)) in pattern.bindings.iter().zip(&variant.fields).enumerate()
+            {
+                if name == "_" {
+                    continue;
+                }
+                let field_ptr = unsafe {
+                    LLVMBuildStructGEP2(cxt.builder, variant_ty, payload_ptr, pos as u32, ZERO_NAME)
+                };
+                assert!(!field_ptr.is_null());
+                bindings.push((
+                    name.clone(),
+                    TypedValue {
+                        value: field_ptr,
+                        ty: field_ty.clone(),
+                    },
+                ));
+            }
+
+            codegen_arm(cxt, arm, bindings)?;
+            unsafe { LLVMBuildBr(cxt.builder, cont_block) };
+        }
+
+        if !has_default {
+            unsafe {
+                LLVMPositionBuilderAtEnd(cxt.builder, default_block);
+                LLVMBuildUnreachable(cxt.builder);
+            }
+        }
+
+        unsafe { LLVMPositionBuilderAtEnd(cxt.builder, cont_block) };
+
+        Ok(())
+    }
+}
+
+fn codegen_arm(
+    cxt: &mut crate::codegen::CodegenContext,
+    arm: &MatchArm,
+    bindings: Vec<(String, TypedValue)>,
+) -> anyhow::Result<()> {
+    cxt.vislayers.enter_layer();
+    for (name, value) in bindings {
+        cxt.vislayers.add_variable(name, value);
+    }
+    for st in &arm.body {
+        st.codegen(cxt)?;
+    }
+    cxt.exit_scope()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn arms() {
+        check_ast!(
+            StatementParser,
+            "match (s) { Shape::Rect(w, _) => { w; } Shape::Empty => {} _ => {} }",
+            ast_node!(
+                Match,
+                ast_node!(Variable, "s".into(), vec![]),
+                vec![
+                    MatchArm::new(
+                        Some(VariantPattern::new(
+                            "Shape".into(),
+                            "Rect".into(),
+                            vec!["w".into(), "_".into()]
+                        )),
+                        vec![ast_node!(
+                            ExprStatement,
+                            ast_node!(Variable, "w".into(), vec![])
+                        )]
+                    ),
+                    MatchArm::new(
+                        Some(VariantPattern::new("Shape".into(), "Empty".into(), vec![])),
+                        vec![]
+                    ),
+                    MatchArm::new(None, vec![])
+                ]
+            )
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use std::path::Path;
+
+use crate::ast::Statement;
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use nyacc_proc::Acceptor;
+
+/// `import "geometry.nya";` or `mod geometry;`, path is relative to importing file.
+/// Imported files are loaded before codegen (see [`crate::loader`]), so there is nothing to generate
+#[derive(new, Acceptor, Debug)]
+pub struct Import {
+    pub path: String,
+    /// Doc comment (`///` lines) attached to import
+    #[new(default)]
+    pub doc: Option<String>,
+}
+
+impl Import {
+    /// Namespace of imported items, file name without extension
+    pub fn module_name(&self) -> String {
+        module_name(Path::new(&self.path))
+    }
+}
+
+impl Statement for Import {
+    fn codegen(&self, _: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Module name of file, `geometry` for `shapes/geometry.nya`
+pub fn module_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Items of imported file, they are defined as `name::item`.
+/// Modules aren't parsed, they are created by loader for each imported file
+#[derive(new, Acceptor, Debug)]
+pub struct Module {
+    pub name: String,
+    pub blocks: Vec<Box<dyn Statement>>,
+}
+
+impl Statement for Module {
+    fn codegen(&self, cxt: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
+        let prev = cxt.definitions.enter_module(Some(self.name.clone()));
+        for block in &self.blocks {
+            block.codegen(cxt)?;
+        }
+        cxt.definitions.enter_module(prev);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn import() {
+        check_ast!(
+            ProgramBlockParser,
+            "import \"shapes/geometry.nya\";",
+            ast_node!(Import, "shapes/geometry.nya".into())
+        );
+
+        check_ast!(
+            ProgramBlockParser,
+            "mod geometry;",
+            ast_node!(Import, "geometry.nya".into())
+        );
+    }
+
+    #[test]
+    fn module_name() {
+        assert_eq!(
+            Import::new("shapes/geometry.nya".into()).module_name(),
+            "geometry"
+        );
+        assert_eq!(Import::new("io".into()).module_name(), "io");
+    }
+}
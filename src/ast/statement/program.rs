@@ -1,4 +1,5 @@
 use crate::ast::Statement;
+use crate::codegen::{CodegenContext, PendingInstance};
 use crate::visitor::{Acceptor, Visitor};
 use derive_new::new;
 use nyacc_proc::Acceptor;
@@ -10,10 +11,76 @@ pub struct Program {
 
 impl Statement for Program {
     fn codegen(&self, cxt: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
+        let mut definer = GlobalDefiner { cxt };
+        for block in &self.blocks {
+            block.accept(&mut definer)?;
+        }
+
         for block in &self.blocks {
             block.codegen(cxt)?;
         }
 
+        /* Generate used instances of generic functions, they can use new instances too */
+        while let Some(instance) = cxt.pending_instances.pop() {
+            let prev = cxt
+                .definitions
+                .set_type_params(instance.type_params.clone());
+            let mut generator = InstanceGenerator {
+                cxt,
+                instance: &instance,
+            };
+            for block in &self.blocks {
+                block.accept(&mut generator)?;
+            }
+            cxt.definitions.set_type_params(prev);
+        }
+
+        Ok(())
+    }
+}
+
+/// Defines all module level constants and statics
+struct GlobalDefiner<'a> {
+    cxt: &'a mut CodegenContext,
+}
+
+impl Visitor for GlobalDefiner<'_> {
+    fn visit_module(&mut self, node: &crate::utils::nodes::Module) -> anyhow::Result<()> {
+        let prev = self.cxt.definitions.enter_module(Some(node.name.clone()));
+        for block in &node.blocks {
+            block.accept(self)?;
+        }
+        self.cxt.definitions.enter_module(prev);
+        Ok(())
+    }
+
+    fn visit_global(&mut self, node: &crate::utils::nodes::Global) -> anyhow::Result<()> {
+        node.define(self.cxt)
+    }
+}
+
+/// Generates body of generic function instance
+struct InstanceGenerator<'a> {
+    cxt: &'a mut CodegenContext,
+    instance: &'a PendingInstance,
+}
+
+impl Visitor for InstanceGenerator<'_> {
+    fn visit_module(&mut self, node: &crate::utils::nodes::Module) -> anyhow::Result<()> {
+        let prev = self.cxt.definitions.enter_module(Some(node.name.clone()));
+        for block in &node.blocks {
+            block.accept(self)?;
+        }
+        self.cxt.definitions.enter_module(prev);
+        Ok(())
+    }
+
+    fn visit_funcimpl(&mut self, node: &crate::utils::nodes::FuncImpl) -> anyhow::Result<()> {
+        if !node.type_params.is_empty()
+            && self.cxt.definitions.item_name(&node.name) == self.instance.name
+        {
+            node.codegen_symbol(self.cxt, &self.instance.symbol)?;
+        }
         Ok(())
     }
 }
@@ -15,18 +15,26 @@ pub struct Return {
 
 impl Statement for Return {
     fn codegen(&self, cxt: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
-        let cur_func = cxt.vislayers.cur_fun().unwrap().0;
-
-        if self.expr.is_none() {
-            unsafe { LLVMBuildRetVoid(cxt.builder) };
-            return Ok(());
+        if cxt.vislayers.deferred_depth().is_some() {
+            anyhow::bail!("`return` can't be used in deferred statement");
         }
+        let cur_func = cxt.vislayers.cur_fun().unwrap().0;
 
-        let rettype = cxt.vislayers.cur_fun().unwrap().1.clone();
-        let expr = self.expr.as_ref().unwrap().codegen(cxt)?;
-        let expr = cast(cxt, &expr.ty, &rettype, expr.value)?;
+        // Value is computed before deferred statements of all function scopes are run
+        match &self.expr {
+            None => {
+                cxt.build_deferred(0)?;
+                unsafe { LLVMBuildRetVoid(cxt.builder) };
+            }
+            Some(expr) => {
+                let rettype = cxt.vislayers.cur_fun().unwrap().1.clone();
+                let expr = expr.codegen(cxt)?;
+                let expr = cast(cxt, &expr.ty, &rettype, expr.value)?;
 
-        unsafe { LLVMBuildRet(cxt.builder, expr) };
+                cxt.build_deferred(0)?;
+                unsafe { LLVMBuildRet(cxt.builder, expr) };
+            }
+        }
 
         unsafe {
             let unreach_block =
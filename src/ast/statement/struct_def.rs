@@ -7,6 +7,15 @@ use nyacc_proc::Acceptor;
 pub struct StructDef {
     pub name: String,
     pub fields: Vec<TypedArg>,
+    /// Doc comment (`///` lines) attached to definition
+    #[new(default)]
+    pub doc: Option<String>,
+    /// `struct Pair<T>`, generic structs are instantiated on use
+    #[new(default)]
+    pub type_params: Vec<String>,
+    /// `pub` item is visible from other modules
+    #[new(default)]
+    pub is_pub: bool,
 }
 
 impl Statement for StructDef {
@@ -18,8 +27,8 @@ impl Statement for StructDef {
 
 #[cfg(test)]
 mod tests {
-    use crate::ast::TypedArg;
     use crate::ast::macros::{ast_node, check_ast};
+    use crate::ast::{TypeName, TypedArg};
     use crate::utils::nodes::*;
 
     #[test]
@@ -62,4 +71,48 @@ mod tests {
             )
         )
     }
+
+    #[test]
+    fn generic() {
+        let mut expected = ast_node!(
+            StructDef,
+            "Pair".into(),
+            vec![
+                TypedArg::new("a".into(), "T".into()),
+                TypedArg::new(
+                    "b".into(),
+                    TypeName::Generic(
+                        "Map".into(),
+                        vec![
+                            "K".into(),
+                            TypeName::Generic("Vec".into(), vec!["T".into()])
+                        ]
+                    )
+                )
+            ]
+        );
+        expected.type_params = vec!["T".into(), "K".into()];
+
+        check_ast!(
+            ProgramBlockParser,
+            "struct Pair<T, K> {a : T, b : Map<K, Vec<T>>}",
+            expected
+        )
+    }
+
+    #[test]
+    fn doc_comment() {
+        let mut expected = ast_node!(
+            StructDef,
+            "S".into(),
+            vec![TypedArg::new("a".into(), "t1".into())]
+        );
+        expected.doc = Some("Some struct".into());
+
+        check_ast!(
+            ProgramBlockParser,
+            "/// Some struct\nstruct S {\n a : t1, // field comment\n}",
+            expected
+        )
+    }
 }
@@ -0,0 +1,165 @@
+use std::collections::HashSet;
+
+use crate::ast::{Expression, Statement};
+use crate::codegen::macros::c_str;
+use crate::codegen::{Type, cast};
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use llvm_sys::core::{
+    LLVMAddCase, LLVMAppendBasicBlockInContext, LLVMBuildBr, LLVMBuildSwitch,
+    LLVMConstIntGetSExtValue, LLVMConstIntGetZExtValue, LLVMIsAConstantInt,
+    LLVMPositionBuilderAtEnd,
+};
+use nyacc_proc::Acceptor;
+
+/// `case 1, 2: { ... }` branch of [`Switch`]
+#[derive(new, Debug)]
+pub struct SwitchCase {
+    pub values: Vec<Box<dyn Expression>>,
+    pub body: Vec<Box<dyn Statement>>,
+}
+
+/// `switch (expr) { case 1, 2: {...} default: {...} }` on integers, lowered to llvm `switch`.
+/// Branches don't fall through, so `break` inside refers to enclosing loop
+#[derive(new, Acceptor, Debug)]
+pub struct Switch {
+    pub value: Box<dyn Expression>,
+    pub cases: Vec<SwitchCase>,
+    pub default: Option<Vec<Box<dyn Statement>>>,
+}
+
+impl Statement for Switch {
+    fn codegen(&self, cxt: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
+        let cur_func = cxt.vislayers.cur_fun().unwrap().0;
+
+        let value = self.value.codegen(cxt)?;
+        if !matches!(value.ty.underlying(), Type::Int(_)) {
+            anyhow::bail!("Switch on non-integer type {}", value.ty);
+        }
+
+        let cont_block =
+            unsafe { LLVMAppendBasicBlockInContext(cxt.cxt, cur_func, c_str!(c"switch_cont")) };
+        let default_block = match self.default {
+            Some(_) => unsafe {
+                LLVMAppendBasicBlockInContext(cxt.cxt, cur_func, c_str!(c"switch_default"))
+            },
+            None => cont_block,
+        };
+
+        // Case values are constants, so they are codegened before switch itself
+        let mut case_values = Vec::with_capacity(self.cases.len());
+        let mut seen = HashSet::new();
+        for case in &self.cases {
+            let mut values = Vec::with_capacity(case.values.len());
+            for case_value in &case.values {
+                let case_value = case_value.codegen(cxt)?;
+                let case_value = cast(cxt, &case_value.ty, &value.ty, case_value.value)?;
+                if unsafe { LLVMIsAConstantInt(case_value) }.is_null() {
+                    anyhow::bail!("Case value must be an integer constant");
+                }
+
+                let raw = match value.ty.underlying() {
+                    Type::Int(int) if int.signed => unsafe {
+                        LLVMConstIntGetSExtValue(case_value) as i128
+                    },
+                    _ => unsafe { LLVMConstIntGetZExtValue(case_value) as i128 },
+                };
+                if !seen.insert(raw) {
+                    anyhow::bail!("Duplicate case value {} in switch", raw);
+                }
+                values.push(case_value);
+            }
+            case_values.push(values);
+        }
+
+        let total_cases = case_values.iter().map(Vec::len).sum::<usize>();
+        let switch =
+            unsafe { LLVMBuildSwitch(cxt.builder, value.value, default_block, total_cases as u32) };
+        assert!(!switch.is_null());
+
+        for (case, values) in self.cases.iter().zip(case_values) {
+            let case_block =
+                unsafe { LLVMAppendBasicBlockInContext(cxt.cxt, cur_func, c_str!(c"switch_case")) };
+            for case_value in values {
+                unsafe { LLVMAddCase(switch, case_value, case_block) };
+            }
+
+            unsafe { LLVMPositionBuilderAtEnd(cxt.builder, case_block) };
+            codegen_branch(cxt, &case.body)?;
+            unsafe { LLVMBuildBr(cxt.builder, cont_block) };
+        }
+
+        if let Some(default) = &self.default {
+            unsafe { LLVMPositionBuilderAtEnd(cxt.builder, default_block) };
+            codegen_branch(cxt, default)?;
+            unsafe { LLVMBuildBr(cxt.builder, cont_block) };
+        }
+
+        unsafe { LLVMPositionBuilderAtEnd(cxt.builder, cont_block) };
+
+        Ok(())
+    }
+}
+
+fn codegen_branch(
+    cxt: &mut crate::codegen::CodegenContext,
+    body: &Vec<Box<dyn Statement>>,
+) -> anyhow::Result<()> {
+    cxt.vislayers.enter_layer();
+    for st in body {
+        st.codegen(cxt)?;
+    }
+    cxt.exit_scope()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn cases() {
+        check_ast!(
+            StatementParser,
+            "switch (a) { case 1, 2: { 1; } case 3: {} }",
+            ast_node!(
+                Switch,
+                ast_node!(Variable, "a".into(), vec![]),
+                vec![
+                    SwitchCase::new(
+                        vec![ast_node!(Int, 1), ast_node!(Int, 2)],
+                        vec![ast_node!(ExprStatement, ast_node!(Int, 1))]
+                    ),
+                    SwitchCase::new(vec![ast_node!(Int, 3)], vec![])
+                ],
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn default() {
+        check_ast!(
+            StatementParser,
+            "switch (a) { case 1: {} default: { 2; } }",
+            ast_node!(
+                Switch,
+                ast_node!(Variable, "a".into(), vec![]),
+                vec![SwitchCase::new(vec![ast_node!(Int, 1)], vec![])],
+                Some(vec![ast_node!(ExprStatement, ast_node!(Int, 2))])
+            )
+        );
+
+        check_ast!(
+            StatementParser,
+            "switch (a) { default: {} }",
+            ast_node!(
+                Switch,
+                ast_node!(Variable, "a".into(), vec![]),
+                vec![],
+                Some(vec![])
+            )
+        );
+    }
+}
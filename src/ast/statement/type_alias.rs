@@ -0,0 +1,67 @@
+use crate::ast::{Statement, TypeName};
+use crate::visitor::{Acceptor, Visitor};
+use derive_new::new;
+use nyacc_proc::Acceptor;
+
+/// `type Meters = f64;` is another name of the same type.
+/// `newtype Meters = f64;` has the same llvm layout, but it's distinct type:
+/// only constants are converted to it implicitly, other values need `as`
+#[derive(new, Acceptor, Debug)]
+pub struct TypeAlias {
+    pub name: String,
+    pub target: TypeName,
+    pub is_newtype: bool,
+    /// Doc comment (`///` lines) attached to definition
+    #[new(default)]
+    pub doc: Option<String>,
+    /// `pub` item is visible from other modules
+    #[new(default)]
+    pub is_pub: bool,
+}
+
+impl Statement for TypeAlias {
+    fn codegen(&self, _: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
+        /* Everything done at context creation stage */
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::TypeName;
+    use crate::ast::macros::{ast_node, check_ast};
+    use crate::utils::nodes::*;
+
+    #[test]
+    fn alias() {
+        check_ast!(
+            ProgramBlockParser,
+            "type Meters = f64;",
+            ast_node!(TypeAlias, "Meters".into(), "f64".into(), false)
+        );
+
+        check_ast!(
+            ProgramBlockParser,
+            "type IntPair = geometry::Pair<i64>;",
+            ast_node!(
+                TypeAlias,
+                "IntPair".into(),
+                TypeName::Generic("geometry::Pair".into(), vec!["i64".into()]),
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn newtype() {
+        let mut expected = ast_node!(TypeAlias, "Id".into(), "u32".into(), true);
+        expected.is_pub = true;
+        expected.doc = Some("Index in table".into());
+
+        check_ast!(
+            ProgramBlockParser,
+            "/// Index in table\npub newtype Id = u32;",
+            expected
+        );
+    }
+}
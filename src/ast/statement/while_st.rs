@@ -11,15 +11,20 @@ use super::for_st::{Loop, codegen_loop};
 pub struct While {
     pub cond: Box<dyn Expression>,
     pub body: Vec<Box<dyn Statement>>,
+    /// Optional `'label:` for `break` & `continue` from nested loops
+    #[new(default)]
+    pub label: Option<String>,
 }
 
 impl Statement for While {
     fn codegen(&self, cxt: &mut crate::codegen::CodegenContext) -> anyhow::Result<()> {
         let loopst = Loop {
             start: None,
-            check: self.cond.as_ref(),
+            check: Some(self.cond.as_ref()),
+            check_after: false,
             step: None,
             body: &self.body,
+            label: self.label.as_deref(),
         };
 
         codegen_loop(cxt, &loopst)
@@ -54,4 +59,20 @@ mod tests {
             )
         )
     }
+
+    #[test]
+    fn labeled() {
+        let mut expected = While::new(
+            ast_node!(Bool, true),
+            vec![ast_node!(Break, Some("outer".into()))],
+        );
+        expected.label = Some("outer".into());
+        let expected = Box::new(expected);
+
+        check_ast!(
+            StatementParser,
+            "'outer: while (true) { break 'outer; }",
+            expected
+        )
+    }
 }
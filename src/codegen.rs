@@ -6,10 +6,17 @@ use std::{
 };
 
 use llvm_sys::{
-    LLVMModule, LLVMOpcode, LLVMValue,
+    LLVMIntPredicate, LLVMModule, LLVMOpcode, LLVMRealPredicate, LLVMValue,
     core::{
-        LLVMBuildCast, LLVMBuildFPCast, LLVMBuildIntCast, LLVMDisposeMessage,
-        LLVMGetFirstInstruction, LLVMPositionBuilder, LLVMPrintModuleToFile,
+        LLVMAddFunction, LLVMAppendBasicBlockInContext, LLVMBuildCall2, LLVMBuildCast,
+        LLVMBuildExtractValue, LLVMBuildFCmp, LLVMBuildFPCast, LLVMBuildICmp, LLVMBuildInsertValue,
+        LLVMBuildIntCast2, LLVMBuildIntToPtr, LLVMBuildPtrToInt, LLVMClearInsertionPosition,
+        LLVMConstIntGetSExtValue, LLVMConstIntGetZExtValue, LLVMConstNull, LLVMConstRealGetDouble,
+        LLVMDeleteFunction, LLVMDisposeMessage, LLVMFunctionType, LLVMGetEntryBasicBlock,
+        LLVMGetFirstInstruction, LLVMGetNamedFunction, LLVMGetNumOperands, LLVMGetOperand,
+        LLVMGetUndef, LLVMIsAConstantArray, LLVMIsAConstantFP, LLVMIsAConstantInt,
+        LLVMIsAConstantStruct, LLVMIsConstant, LLVMIsUndef, LLVMPositionBuilder,
+        LLVMPositionBuilderAtEnd, LLVMPrintModuleToFile, LLVMVoidTypeInContext,
     },
     prelude::LLVMBasicBlockRef,
     target::LLVM_InitializeNativeTarget,
@@ -25,7 +32,10 @@ use llvm_sys::{
 };
 use macros::c_str;
 
-use crate::{ast::Statement, utils::nodes::Program};
+use crate::{
+    ast::{Expression, Statement},
+    utils::nodes::Program,
+};
 
 mod context;
 mod definitions;
@@ -33,8 +43,8 @@ mod definitions;
 #[cfg(test)]
 mod tests;
 
-pub use context::{CodegenContext, JitEngine};
-pub use definitions::Type;
+pub use context::{CodegenContext, JitEngine, LoopTarget, PendingInstance};
+pub use definitions::{IntType, Type, method_name};
 
 #[derive(Debug, Clone)]
 pub struct TypedValue {
@@ -42,9 +52,17 @@ pub struct TypedValue {
     pub ty: Rc<Type>,
 }
 
-pub fn ir_target(prog: &Program, output: &Path, no_optimize: bool) -> anyhow::Result<()> {
+pub fn ir_target(
+    prog: &Program,
+    output: &Path,
+    no_optimize: bool,
+    leak_report: bool,
+) -> anyhow::Result<()> {
     let mut cxt = CodegenContext::prepare(prog)?;
     prog.codegen(&mut cxt)?;
+    if leak_report {
+        register_leak_report(&mut cxt)?;
+    }
 
     let filename_c = CString::new(output.to_str().unwrap()).unwrap();
     let mut errors: *mut c_char = null_mut();
@@ -69,7 +87,7 @@ pub fn ir_target(prog: &Program, output: &Path, no_optimize: bool) -> anyhow::Re
     Ok(())
 }
 
-pub fn jit_target(prog: &Program) -> anyhow::Result<()> {
+pub fn jit_target(prog: &Program, leak_report: bool) -> anyhow::Result<()> {
     let mut cxt = CodegenContext::prepare(prog)?;
     prog.codegen(&mut cxt)?;
 
@@ -89,6 +107,42 @@ pub fn jit_target(prog: &Program) -> anyhow::Result<()> {
 
     func_ptr();
 
+    if leak_report {
+        nyastd::nya_leak_report();
+    }
+
+    Ok(())
+}
+
+/// Registers `nya_leak_report` via `atexit` at the beginning of `main`,
+/// so linked program reports leaks on exit like JIT does
+fn register_leak_report(cxt: &mut CodegenContext) -> anyhow::Result<()> {
+    let main = unsafe { LLVMGetNamedFunction(cxt.module, c_str!(c"main")) };
+    if main.is_null() {
+        anyhow::bail!("Leak report requires main function");
+    }
+
+    let void = cxt.definitions.get_type("void").unwrap();
+    let void_ptr = Rc::new(Type::Pointer(void.clone()));
+    let i32_type = cxt.definitions.get_type("i32").unwrap();
+    let (report, _) = cxt.std_function("nya_leak_report", &[], &void)?;
+    let (atexit, atexit_type) = cxt.std_function("atexit", &[void_ptr], &i32_type)?;
+
+    position_builer_at_begin(cxt, unsafe { LLVMGetEntryBasicBlock(main) });
+    let mut args = [report];
+    let call = unsafe {
+        LLVMBuildCall2(
+            cxt.builder,
+            atexit_type,
+            atexit,
+            args.as_mut_ptr(),
+            args.len() as u32,
+            ZERO_NAME,
+        )
+    };
+    assert!(!call.is_null());
+    unsafe { LLVMClearInsertionPosition(cxt.builder) };
+
     Ok(())
 }
 
@@ -164,49 +218,309 @@ pub mod macros {
     pub(crate) use c_str;
 }
 
+/// Implicit conversion (`let`, assignment, arguments, return, operands of binary operators).
+///
+/// Conversion policy: widening conversions, which can't lose data, are implicit.
+/// Integers of the same signedness are also truncated implicitly as in C.
+/// Float narrowing, float to int, sign changing and int to float conversions, where float
+/// can't represent every integer exactly, require explicit `as`.
+/// Constants (e.g. unsuffixed literals) take target type, if their value fits into it.
 pub fn cast(
     cxt: &mut CodegenContext,
     from: &Type,
     to: &Type,
     val: *mut LLVMValue,
+) -> anyhow::Result<*mut LLVMValue> {
+    build_cast(cxt, from, to, val, false)
+}
+
+/// Explicit conversion via `as`
+pub fn cast_explicit(
+    cxt: &mut CodegenContext,
+    from: &Type,
+    to: &Type,
+    val: *mut LLVMValue,
+) -> anyhow::Result<*mut LLVMValue> {
+    build_cast(cxt, from, to, val, true)
+}
+
+/// Checks conversion policy for implicit casts, see [`cast`]
+fn implicit_cast_allowed(from: &Type, to: &Type, val: *mut LLVMValue) -> bool {
+    if from.widens_to(to) {
+        return true;
+    }
+
+    match (from.dealias(), to.dealias()) {
+        (Type::Int(from_int), Type::Int(to_int)) => match const_int_value(from_int, val) {
+            Some(_) => const_int_fits(from_int, to_int, val),
+            // Bool is a check for non zero value, not truncation
+            None => from_int.signed == to_int.signed && to_int.bitwidth > 1,
+        },
+        (Type::Int(from_int), Type::Float(to_fp)) => const_int_value(from_int, val)
+            .is_some_and(|value| value.unsigned_abs() <= 1 << to_fp.mantissa_bits()),
+        (Type::Float(_), Type::Float(to_fp)) => const_float_fits(to_fp, val),
+        (Type::Float(_), Type::Int(_)) => false,
+        // Elements are checked one by one in build_cast
+        (Type::Array(..), Type::Array(..)) => true,
+        (Type::Tuple(_), Type::Tuple(_)) => true,
+        (Type::Pointer(_), Type::Pointer(_)) => from.is_void_ptr() || to.is_void_ptr(),
+        (Type::Func(..), Type::Pointer(_)) => to.is_void_ptr(),
+        (Type::Pointer(_), Type::Func(..)) => from.is_void_ptr(),
+        _ => false,
+    }
+}
+
+/// Like [`Type::common_type`], but integer constant operand takes type of other operand
+/// if it fits, so `x + 1` has type of `x` and `f * 2` has type of `f`
+pub fn common_operand_type(lhs: &TypedValue, rhs: &TypedValue) -> anyhow::Result<Rc<Type>> {
+    if let Type::Int(_) = rhs.ty.dealias()
+        && let Type::Int(_) | Type::Float(_) = lhs.ty.dealias()
+    {
+        let lhs_const = unsafe { !LLVMIsAConstantInt(lhs.value).is_null() };
+        let rhs_const = unsafe { !LLVMIsAConstantInt(rhs.value).is_null() };
+
+        if !lhs_const && rhs_const && implicit_cast_allowed(&rhs.ty, &lhs.ty, rhs.value) {
+            return Ok(lhs.ty.clone());
+        }
+    }
+    if let Type::Int(_) = lhs.ty.dealias()
+        && let Type::Int(_) | Type::Float(_) = rhs.ty.dealias()
+    {
+        let lhs_const = unsafe { !LLVMIsAConstantInt(lhs.value).is_null() };
+        let rhs_const = unsafe { !LLVMIsAConstantInt(rhs.value).is_null() };
+
+        if lhs_const && !rhs_const && implicit_cast_allowed(&lhs.ty, &rhs.ty, lhs.value) {
+            return Ok(rhs.ty.clone());
+        }
+    }
+
+    // Constant operand takes newtype of other one, so `len * 2.0` is still in `Meters`
+    if let Type::Newtype(_, inner) = lhs.ty.dealias()
+        && rhs.ty.arithmetic()
+        && is_const_convertible(rhs, inner)
+    {
+        return Ok(lhs.ty.clone());
+    }
+    if let Type::Newtype(_, inner) = rhs.ty.dealias()
+        && lhs.ty.arithmetic()
+        && is_const_convertible(lhs, inner)
+    {
+        return Ok(rhs.ty.clone());
+    }
+
+    Type::common_type(&lhs.ty, &rhs.ty)
+}
+
+/// Value is constant of other type, which can be implicitly casted to `to`
+fn is_const_convertible(value: &TypedValue, to: &Type) -> bool {
+    let is_const = unsafe { LLVMIsConstant(value.value) } != 0;
+    is_const
+        && !matches!(value.ty.dealias(), Type::Newtype(..))
+        && implicit_cast_allowed(&value.ty, to.underlying(), value.value)
+}
+
+/// Value of integer constant, `None` if value is not a constant
+pub fn const_int_value(ty: &IntType, val: *mut LLVMValue) -> Option<i128> {
+    if unsafe { LLVMIsAConstantInt(val).is_null() } {
+        return None;
+    }
+
+    let value = unsafe {
+        if ty.signed {
+            LLVMConstIntGetSExtValue(val) as i128
+        } else {
+            LLVMConstIntGetZExtValue(val) as i128
+        }
+    };
+    Some(value)
+}
+
+/// Integer constant can be represented by target type without loss
+fn const_int_fits(from: &IntType, to: &IntType, val: *mut LLVMValue) -> bool {
+    let Some(value) = const_int_value(from, val) else {
+        return false;
+    };
+
+    let (min, max) = if to.signed {
+        (
+            -(1i128 << (to.bitwidth - 1)),
+            (1i128 << (to.bitwidth - 1)) - 1,
+        )
+    } else {
+        (0, (1i128 << to.bitwidth) - 1)
+    };
+
+    min <= value && value <= max
+}
+
+/// Float constant stays finite in type `to`, only f64 -> f32 can overflow
+fn const_float_fits(to: &definitions::FloatType, val: *mut LLVMValue) -> bool {
+    if unsafe { LLVMIsAConstantFP(val).is_null() } {
+        return false;
+    }
+
+    let mut loses_info = 0;
+    let value = unsafe { LLVMConstRealGetDouble(val, &mut loses_info) };
+    to.bitwidth == 64 || value.is_nan() || (value as f32).is_finite()
+}
+
+fn build_cast(
+    cxt: &mut CodegenContext,
+    from: &Type,
+    to: &Type,
+    val: *mut LLVMValue,
+    explicit: bool,
 ) -> anyhow::Result<*mut LLVMValue> {
     // Quickpath
     if from == to {
         return Ok(val);
     }
 
+    // Aliases are the same types, their names are used only in messages
+    let (from_name, to_name) = (from, to);
+    let (from, to) = (from.dealias(), to.dealias());
+
+    // Newtypes have layout of wrapped type, but only constants are converted to them implicitly.
+    // Check for non zero value is allowed too, so they can be used in conditions
+    if let Type::Newtype(..) = from {
+        let to_bool = matches!(to, Type::Int(int) if int.bitwidth == 1 && !int.signed);
+        if !explicit && !to_bool {
+            anyhow::bail!(
+                "Implicit conversion from {} to {} is forbidden, use explicit `as` cast",
+                from_name,
+                to_name
+            );
+        }
+        return build_cast(cxt, from.underlying(), to, val, explicit);
+    }
+    if let Type::Newtype(..) = to {
+        if !explicit && unsafe { LLVMIsConstant(val) } == 0 {
+            anyhow::bail!(
+                "Implicit conversion from {} to {} is forbidden, use explicit `as` cast",
+                from_name,
+                to_name
+            );
+        }
+        return build_cast(cxt, from, to.underlying(), val, explicit);
+    }
+
+    let allowed = explicit || implicit_cast_allowed(from, to, val);
+    let numeric = |ty: &Type| matches!(ty, Type::Int(_) | Type::Float(_));
+    if !allowed && numeric(from) && numeric(to) {
+        anyhow::bail!(
+            "Implicit conversion from {} to {} can lose data, use explicit `as` cast",
+            from_name,
+            to_name
+        );
+    }
+
     let res = match to {
         Type::Float(to_fp) => match from {
             Type::Float(_) => unsafe {
                 LLVMBuildFPCast(cxt.builder, val, to_fp.llvm_type(cxt), ZERO_NAME)
             },
-            Type::Int(_) => unsafe {
+            Type::Int(from_int) => unsafe {
                 LLVMBuildCast(
                     cxt.builder,
-                    LLVMOpcode::LLVMSIToFP,
+                    if from_int.signed {
+                        LLVMOpcode::LLVMSIToFP
+                    } else {
+                        LLVMOpcode::LLVMUIToFP
+                    },
                     val,
                     to_fp.llvm_type(cxt),
                     ZERO_NAME,
                 )
             },
-            _ => anyhow::bail!("Cast from incompatable type"),
+            _ => anyhow::bail!("Cast from {} to {} is forbidden", from_name, to_name),
         },
+        // Conversion to bool is a check for non zero value as in C
+        Type::Int(to_int) if to_int.bitwidth == 1 && !to_int.signed && allowed => {
+            build_nonzero_check(cxt, from, val)?
+        }
         Type::Int(to_int) => match from {
             Type::Float(_) => unsafe {
                 LLVMBuildCast(
                     cxt.builder,
-                    LLVMOpcode::LLVMFPToSI,
+                    if to_int.signed {
+                        LLVMOpcode::LLVMFPToSI
+                    } else {
+                        LLVMOpcode::LLVMFPToUI
+                    },
+                    val,
+                    to_int.llvm_type(cxt),
+                    ZERO_NAME,
+                )
+            },
+            // Source signedness defines how value is extended
+            Type::Int(from_int) => unsafe {
+                LLVMBuildIntCast2(
+                    cxt.builder,
                     val,
                     to_int.llvm_type(cxt),
+                    from_int.signed as i32,
                     ZERO_NAME,
                 )
             },
-            Type::Int(_) => unsafe {
-                LLVMBuildIntCast(cxt.builder, val, to_int.llvm_type(cxt), ZERO_NAME)
+            Type::Pointer(_) if allowed => unsafe {
+                LLVMBuildPtrToInt(cxt.builder, val, to_int.llvm_type(cxt), ZERO_NAME)
             },
-            _ => anyhow::bail!("Cast from incompatable type"),
+            // Value of C-like enum is its tag
+            Type::Enum(enum_type) if allowed && enum_type.is_c_like() => unsafe {
+                let tag = LLVMBuildExtractValue(cxt.builder, val, 0, ZERO_NAME);
+                LLVMBuildIntCast2(cxt.builder, tag, to_int.llvm_type(cxt), 1, ZERO_NAME)
+            },
+            _ => anyhow::bail!("Cast from {} to {} is forbidden", from_name, to_name),
+        },
+        // Arrays are casted elementwise
+        Type::Array(to_elem, to_len) => match from {
+            Type::Array(from_elem, from_len) if from_len == to_len => {
+                let mut res = unsafe { LLVMGetUndef(to.llvm_type(cxt)) };
+                for i in 0..*to_len as u32 {
+                    let elem = unsafe { LLVMBuildExtractValue(cxt.builder, val, i, ZERO_NAME) };
+                    let elem = build_cast(cxt, from_elem, to_elem, elem, explicit)?;
+                    res = unsafe { LLVMBuildInsertValue(cxt.builder, res, elem, i, ZERO_NAME) };
+                }
+                res
+            }
+            _ => anyhow::bail!("Cast from {} to {} is forbidden", from_name, to_name),
         },
-        _ => anyhow::bail!("Cast to incompatable type"),
+        // Tuples too
+        Type::Tuple(to_elems) => match from {
+            Type::Tuple(from_elems) if from_elems.len() == to_elems.len() => {
+                let mut res = unsafe { LLVMGetUndef(to.llvm_type(cxt)) };
+                for (i, (from_elem, to_elem)) in from_elems.iter().zip(to_elems).enumerate() {
+                    let i = i as u32;
+                    let elem = unsafe { LLVMBuildExtractValue(cxt.builder, val, i, ZERO_NAME) };
+                    let elem = build_cast(cxt, from_elem, to_elem, elem, explicit)?;
+                    res = unsafe { LLVMBuildInsertValue(cxt.builder, res, elem, i, ZERO_NAME) };
+                }
+                res
+            }
+            _ => anyhow::bail!("Cast from {} to {} is forbidden", from_name, to_name),
+        },
+        // Pointers are opaque in llvm, so only typecheck is needed
+        Type::Pointer(_) => match from {
+            Type::Pointer(_) | Type::Func(..) if allowed => val,
+            Type::Int(_) if allowed => unsafe {
+                LLVMBuildIntToPtr(cxt.builder, val, to.llvm_type(cxt), ZERO_NAME)
+            },
+            _ => anyhow::bail!(
+                "Cast from {} to {} pointer is forbidden",
+                from_name,
+                to_name
+            ),
+        },
+        // Function pointers are usual pointers in llvm, signature is only checked here
+        Type::Func(..) => match from {
+            Type::Func(..) | Type::Pointer(_) if allowed => val,
+            _ => anyhow::bail!(
+                "Cast from {} to function pointer {} is forbidden",
+                from_name,
+                to_name
+            ),
+        },
+        _ => anyhow::bail!("Cast from {} to {} is forbidden", from_name, to_name),
     };
 
     assert!(!res.is_null());
@@ -222,7 +536,7 @@ pub fn bool_from_llvm(cxt: &mut CodegenContext, val: *mut LLVMValue) -> TypedVal
 
 pub fn bool_from_value(cxt: &mut CodegenContext, val: &TypedValue) -> anyhow::Result<TypedValue> {
     let target_type = cxt.definitions.get_type("bool").unwrap();
-    let value = cast(cxt, &val.ty, target_type.as_ref(), val.value)?;
+    let value = cast_explicit(cxt, &val.ty, target_type.as_ref(), val.value)?;
 
     Ok(TypedValue {
         value,
@@ -230,6 +544,95 @@ pub fn bool_from_value(cxt: &mut CodegenContext, val: &TypedValue) -> anyhow::Re
     })
 }
 
+/// Converts value to `bool` as C does: any non-zero value is true
+fn build_nonzero_check(
+    cxt: &mut CodegenContext,
+    from: &Type,
+    val: *mut LLVMValue,
+) -> anyhow::Result<*mut LLVMValue> {
+    let zero = unsafe { LLVMConstNull(from.llvm_type(cxt)) };
+    let res = match from.dealias() {
+        Type::Int(_) | Type::Pointer(_) | Type::Func(..) => unsafe {
+            LLVMBuildICmp(
+                cxt.builder,
+                LLVMIntPredicate::LLVMIntNE,
+                val,
+                zero,
+                ZERO_NAME,
+            )
+        },
+        Type::Float(_) => unsafe {
+            LLVMBuildFCmp(
+                cxt.builder,
+                LLVMRealPredicate::LLVMRealUNE,
+                val,
+                zero,
+                ZERO_NAME,
+            )
+        },
+        _ => anyhow::bail!("Can't use value of type {} as bool", from),
+    };
+
+    Ok(res)
+}
+
+/// Evaluates initializer of global item at compile time.
+/// Expression is generated into temporary function, so any expression can be used,
+/// but result is accepted only if llvm folded it into constant
+pub fn const_eval(
+    cxt: &mut CodegenContext,
+    expr: &dyn Expression,
+    ty: &Rc<Type>,
+) -> anyhow::Result<*mut LLVMValue> {
+    let func = unsafe {
+        let func_type = LLVMFunctionType(LLVMVoidTypeInContext(cxt.cxt), null_mut(), 0, 0);
+        LLVMAddFunction(cxt.module, c_str!(c"nyacc.const_eval"), func_type)
+    };
+    assert!(!func.is_null());
+
+    cxt.vislayers
+        .enter_function(func, cxt.definitions.get_type("void").unwrap());
+    cxt.vislayers.enter_layer();
+    unsafe {
+        let entry = LLVMAppendBasicBlockInContext(cxt.cxt, func, c_str!(c"entry"));
+        LLVMPositionBuilderAtEnd(cxt.builder, entry);
+    }
+
+    let res = expr
+        .codegen(cxt)
+        .and_then(|val| cast(cxt, &val.ty, ty, val.value));
+
+    cxt.vislayers.exit_layer();
+    cxt.vislayers.exit_function();
+    unsafe {
+        LLVMClearInsertionPosition(cxt.builder);
+        LLVMDeleteFunction(func);
+    }
+
+    let res = res?;
+    if unsafe { LLVMIsConstant(res) } == 0 {
+        anyhow::bail!("Value can't be computed at compile time");
+    }
+    if has_undef(res) {
+        anyhow::bail!("Value is undefined, e.g. shift is too large");
+    }
+
+    Ok(res)
+}
+
+/// Constant is or contains undef/poison value, llvm folds invalid operations to it
+fn has_undef(val: *mut LLVMValue) -> bool {
+    unsafe {
+        if LLVMIsUndef(val) != 0 {
+            return true;
+        }
+        if LLVMIsAConstantStruct(val).is_null() && LLVMIsAConstantArray(val).is_null() {
+            return false;
+        }
+        (0..LLVMGetNumOperands(val) as u32).any(|i| has_undef(LLVMGetOperand(val, i)))
+    }
+}
+
 pub fn position_builer_at_begin(cxt: &mut CodegenContext, block: LLVMBasicBlockRef) {
     let first_instr = unsafe { LLVMGetFirstInstruction(block) };
     // Note: first_instr can be NULL, but LLVMPositionBuilder can handle it
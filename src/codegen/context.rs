@@ -5,32 +5,66 @@ use std::{
     rc::Rc,
 };
 
-use crate::{utils::nodes::Program, visitor::Acceptor};
+use crate::{
+    ast::{Statement, TypeName},
+    utils::nodes::Program,
+    visitor::Acceptor,
+};
 use llvm_sys::{
     LLVMBuilder, LLVMContext, LLVMLinkage, LLVMModule, LLVMValue,
     core::{
-        LLVMAddFunction, LLVMContextCreate, LLVMContextDispose, LLVMCreateBuilderInContext,
-        LLVMDisposeBuilder, LLVMDisposeModule, LLVMFunctionType, LLVMGetNamedFunction,
+        LLVMAddFunction, LLVMArrayType2, LLVMContextCreate, LLVMContextDispose,
+        LLVMCreateBuilderInContext, LLVMDisposeBuilder, LLVMDisposeModule, LLVMFunctionType,
+        LLVMGetNamedFunction, LLVMGetTypeByName2, LLVMGlobalGetValueType, LLVMInt64TypeInContext,
         LLVMModuleCreateWithNameInContext, LLVMSetLinkage, LLVMStructCreateNamed,
-        LLVMStructSetBody,
+        LLVMStructSetBody, LLVMStructTypeInContext,
     },
     execution_engine::{
         LLVMAddGlobalMapping, LLVMCreateExecutionEngineForModule, LLVMDisposeExecutionEngine,
         LLVMGetFunctionAddress, LLVMLinkInMCJIT, LLVMOpaqueExecutionEngine,
     },
-    prelude::{LLVMTypeRef, LLVMValueRef},
+    prelude::{LLVMBasicBlockRef, LLVMTypeRef, LLVMValueRef},
     target::{LLVM_InitializeNativeAsmPrinter, LLVM_InitializeNativeTarget},
 };
 
 use super::{
     Type, TypedValue,
-    definitions::{CustomType, ProgramDefinitions},
+    definitions::{CustomType, EnumType, ProgramDefinitions},
 };
 
+/// Jump targets of enclosing loop for `break` & `continue`
+pub struct LoopTarget {
+    pub label: Option<String>,
+    pub continue_block: LLVMBasicBlockRef,
+    pub break_block: LLVMBasicBlockRef,
+    /// Number of layers outside of loop body, jumps run deferred statements of deeper ones
+    pub depth: usize,
+}
+
+/// Variables of one scope & statements deferred until its exit
+#[derive(Default)]
+struct Layer {
+    variables: HashMap<String, TypedValue>,
+    deferred: Vec<Deferred>,
+}
+
+/// Statement of `defer` with variables of its layer declared before it
+struct Deferred {
+    stmt: Rc<dyn Statement>,
+    variables: HashMap<String, TypedValue>,
+}
+
 pub struct VisibilityContext {
-    layers: Vec<HashMap<String, TypedValue>>,
+    layers: Vec<Layer>,
     // Currently means function rettype, but possibly can have other meanings like in rust
     cur_func: Option<(*mut LLVMValue, Rc<Type>)>,
+    loops: Vec<LoopTarget>,
+    /// Module level `static` & `const` items, visible when not shadowed by locals
+    globals: HashMap<String, TypedValue>,
+    /// Values of `const` items, they are known at compile time
+    constants: HashMap<String, TypedValue>,
+    /// Depth of layer of deferred statement being generated, jumps out of it are rejected
+    deferred_depth: Option<usize>,
 }
 
 impl VisibilityContext {
@@ -38,30 +72,88 @@ impl VisibilityContext {
         Self {
             layers: vec![],
             cur_func: None,
+            loops: vec![],
+            globals: HashMap::new(),
+            constants: HashMap::new(),
+            deferred_depth: None,
         }
     }
 
     pub fn enter_layer(&mut self) {
-        self.layers.push(HashMap::new());
+        self.layers.push(Layer::default());
     }
 
+    /// Leaves layer without running its deferred statements, see [`CodegenContext::exit_scope`]
     pub fn exit_layer(&mut self) {
         let res = self.layers.pop();
         debug_assert!(res.is_some(), "Exited more than entered");
     }
 
+    /// Number of entered layers
+    pub fn depth(&self) -> usize {
+        self.layers.len()
+    }
+
     pub fn add_variable(&mut self, name: String, val: TypedValue) {
-        self.layers.last_mut().unwrap().insert(name, val);
+        self.layers.last_mut().unwrap().variables.insert(name, val);
+    }
+
+    /// Statement is executed on exit of current layer, in reverse order of addition.
+    /// It sees only variables declared before it
+    pub fn add_deferred(&mut self, stmt: Rc<dyn Statement>) {
+        let layer = self.layers.last_mut().unwrap();
+        let variables = layer.variables.clone();
+        layer.deferred.push(Deferred { stmt, variables });
+    }
+
+    /// Depth of layer of deferred statement being generated, if any
+    pub fn deferred_depth(&self) -> Option<usize> {
+        self.deferred_depth
     }
 
     pub fn get_variable(&mut self, name: &str) -> Option<TypedValue> {
         for layer in self.layers.iter().rev() {
-            if let Some(var) = layer.get(name) {
+            if let Some(var) = layer.variables.get(name) {
                 return Some(var.clone());
             }
         }
 
-        None
+        self.globals.get(name).cloned()
+    }
+
+    /// Registers global variable, `const_value` is set for constants
+    pub fn add_global(
+        &mut self,
+        name: String,
+        val: TypedValue,
+        const_value: Option<TypedValue>,
+    ) -> anyhow::Result<()> {
+        if self.globals.contains_key(&name) {
+            anyhow::bail!("Redefinition of global {}", name);
+        }
+
+        if let Some(const_value) = const_value {
+            self.constants.insert(name.clone(), const_value);
+        }
+        self.globals.insert(name, val);
+
+        Ok(())
+    }
+
+    /// Name is a local variable or argument of current function
+    pub fn is_local(&self, name: &str) -> bool {
+        self.layers
+            .iter()
+            .any(|layer| layer.variables.contains_key(name))
+    }
+
+    /// Value of constant if name refers to it, i.e. it's not shadowed by local variable
+    pub fn get_constant(&self, name: &str) -> Option<TypedValue> {
+        if self.is_local(name) {
+            return None;
+        }
+
+        self.constants.get(name).cloned()
     }
 
     pub fn enter_function(&mut self, func: LLVMValueRef, rettype: Rc<Type>) {
@@ -77,6 +169,43 @@ impl VisibilityContext {
     pub fn exit_function(&mut self) {
         self.cur_func = None;
     }
+
+    pub fn enter_loop(&mut self, target: LoopTarget) -> anyhow::Result<()> {
+        if let Some(label) = &target.label
+            && self.find_loop(Some(label)).is_some()
+        {
+            anyhow::bail!("Loop label '{} is already used by enclosing loop", label);
+        }
+
+        self.loops.push(target);
+        Ok(())
+    }
+
+    pub fn exit_loop(&mut self) {
+        let res = self.loops.pop();
+        debug_assert!(res.is_some(), "Exited more loops than entered");
+    }
+
+    /// Innermost loop with given label or just innermost one if label is `None`
+    pub fn find_loop(&self, label: Option<&str>) -> Option<&LoopTarget> {
+        match label {
+            None => self.loops.last(),
+            Some(label) => self
+                .loops
+                .iter()
+                .rev()
+                .find(|target| target.label.as_deref() == Some(label)),
+        }
+    }
+}
+
+/// Instance of generic function, which body should be generated
+pub struct PendingInstance {
+    /// Name of generic function
+    pub name: String,
+    /// Mangled name of instance
+    pub symbol: String,
+    pub type_params: HashMap<String, Rc<Type>>,
 }
 
 pub struct TypeCache {
@@ -106,6 +235,7 @@ pub struct CodegenContext {
     pub definitions: ProgramDefinitions,
     pub vislayers: VisibilityContext,
     pub type_cache: TypeCache,
+    pub pending_instances: Vec<PendingInstance>,
 }
 
 impl CodegenContext {
@@ -131,10 +261,13 @@ impl CodegenContext {
             definitions,
             vislayers: VisibilityContext::new(),
             type_cache: TypeCache::new(),
+            pending_instances: vec![],
         };
 
         cxt.register_types()?;
         cxt.register_functions();
+        // Instances used in definitions are registered with other types
+        cxt.definitions.take_new_instances();
 
         Ok(cxt)
     }
@@ -152,18 +285,17 @@ impl CodegenContext {
                 return Ok(*prev);
             }
         }
+        if let Some(prev) = self.registered_type(&ty.name) {
+            return Ok(prev);
+        }
 
         /* insert stub */
         registered.insert(&ty.name, null_mut());
 
-        let mut llvm_types = vec![null_mut(); ty.fields.len()];
+        let mut llvm_types = vec![null_mut(); ty.fields().len()];
 
-        for (idx, field) in ty.fields.values() {
-            let llvm_ty = if let Type::Custom(ty) = field.as_ref() {
-                self.register_types_recursive(ty, registered)?
-            } else {
-                field.llvm_type(self)
-            };
+        for (idx, field) in ty.fields().values() {
+            let llvm_ty = self.register_field_type(field, registered)?;
             debug_assert!(!llvm_ty.is_null());
 
             llvm_types[*idx] = llvm_ty;
@@ -190,13 +322,121 @@ impl CodegenContext {
         Ok(new_llvm_type)
     }
 
+    /// Registers enum as `{ i64 tag, [N x i64] payload }` and struct type for payload of each variant
+    fn register_enum_recursive<'a>(
+        &self,
+        ty: &'a EnumType,
+        registered: &mut HashMap<&'a str, LLVMTypeRef>,
+    ) -> anyhow::Result<LLVMTypeRef> {
+        /* Try to retrieve cache */
+        if let Some(prev) = registered.get(ty.name.as_str()) {
+            if prev.is_null() {
+                anyhow::bail!("Recursive types, loop begins at {}", ty.name);
+            } else {
+                return Ok(*prev);
+            }
+        }
+        if let Some(prev) = self.registered_type(&ty.name) {
+            return Ok(prev);
+        }
+
+        /* insert stub */
+        registered.insert(&ty.name, null_mut());
+
+        let mut variant_types = Vec::with_capacity(ty.variants().len());
+        for variant in ty.variants() {
+            let mut llvm_types = Vec::with_capacity(variant.fields.len());
+            for field in &variant.fields {
+                llvm_types.push(self.register_field_type(field, registered)?);
+            }
+            variant_types.push((ty.variant_type_name(variant), llvm_types));
+        }
+
+        /* Register in llvm
+         * Should be after all checks, payload size is known only when field types are registered
+         */
+        for (name, mut llvm_types) in variant_types {
+            let name = CString::new(name).unwrap();
+            let variant_type = unsafe { LLVMStructCreateNamed(self.cxt, name.as_ptr()) };
+            assert!(!variant_type.is_null());
+            unsafe {
+                LLVMStructSetBody(
+                    variant_type,
+                    llvm_types.as_mut_ptr(),
+                    llvm_types.len() as u32,
+                    0,
+                )
+            };
+        }
+
+        let own_name = CString::new(ty.name.clone()).unwrap();
+        let new_llvm_type = unsafe { LLVMStructCreateNamed(self.cxt, own_name.as_ptr()) };
+        assert!(!new_llvm_type.is_null());
+        unsafe {
+            let i64_type = LLVMInt64TypeInContext(self.cxt);
+            let mut llvm_types = [i64_type, LLVMArrayType2(i64_type, ty.payload_words())];
+            LLVMStructSetBody(
+                new_llvm_type,
+                llvm_types.as_mut_ptr(),
+                llvm_types.len() as u32,
+                0,
+            )
+        };
+
+        /* Update cache */
+        registered.insert(&ty.name, new_llvm_type);
+
+        Ok(new_llvm_type)
+    }
+
+    /// Type registered by previous `register_types` calls
+    fn registered_type(&self, name: &str) -> Option<LLVMTypeRef> {
+        let name = CString::new(name).unwrap();
+        let res = unsafe { LLVMGetTypeByName2(self.cxt, name.as_ptr()) };
+        (!res.is_null()).then_some(res)
+    }
+
+    /// Field types can contain custom types (directly, as array or tuple elements), which should be registered first
+    fn register_field_type<'a>(
+        &self,
+        field: &'a Type,
+        registered: &mut HashMap<&'a str, LLVMTypeRef>,
+    ) -> anyhow::Result<LLVMTypeRef> {
+        let res = match field.dealias() {
+            Type::Custom(ty) => self.register_types_recursive(ty, registered)?,
+            Type::Enum(ty) => self.register_enum_recursive(ty, registered)?,
+            Type::Array(elem, len) => {
+                let elem = self.register_field_type(elem, registered)?;
+                unsafe { LLVMArrayType2(elem, *len) }
+            }
+            Type::Tuple(elems) => {
+                let mut elems = elems
+                    .iter()
+                    .map(|elem| self.register_field_type(elem, registered))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                unsafe {
+                    LLVMStructTypeInContext(self.cxt, elems.as_mut_ptr(), elems.len() as u32, 0)
+                }
+            }
+            _ => field.llvm_type(self),
+        };
+
+        Ok(res)
+    }
+
     // TODO: Rewrite it (and llvm_type) so it would use cxt.type_cache as in register_functions
     fn register_types(&mut self) -> anyhow::Result<()> {
         let mut registered = HashMap::new();
 
         for ty in self.definitions.types.values() {
-            if let Type::Custom(ty) = ty.as_ref() {
-                self.register_types_recursive(ty, &mut registered)?;
+            match ty.as_ref() {
+                Type::Custom(ty) => {
+                    self.register_types_recursive(ty, &mut registered)?;
+                }
+                Type::Enum(ty) => {
+                    self.register_enum_recursive(ty, &mut registered)?;
+                }
+                _ => {}
             }
         }
 
@@ -204,31 +444,172 @@ impl CodegenContext {
     }
 
     fn register_functions(&mut self) {
-        for funcname in self.definitions.function_names() {
-            let func_type = self.definitions.get_func(funcname).unwrap();
-
-            let mut llvm_arg_types: Vec<LLVMTypeRef> =
-                func_type.0.iter().map(|t| t.llvm_type(self)).collect();
-
-            let llvm_func_type = unsafe {
-                LLVMFunctionType(
-                    func_type.1.llvm_type(self),
-                    llvm_arg_types.as_mut_ptr(),
-                    llvm_arg_types.len() as u32,
-                    false as i32,
-                )
-            };
-            assert!(!llvm_func_type.is_null());
+        let names: Vec<_> = self
+            .definitions
+            .function_names()
+            .into_iter()
+            .cloned()
+            .collect();
+        for funcname in names {
+            self.declare_function(funcname);
+        }
+    }
+
+    fn declare_function(&mut self, funcname: String) {
+        let func_type = self.definitions.get_func(&funcname).unwrap().clone();
+        let llvm_func_type = self.func_llvm_type(&func_type.0, &func_type.1, func_type.2);
 
-            let func_name_c = CString::new(funcname.clone()).unwrap();
+        let func_name_c = CString::new(funcname.clone()).unwrap();
+
+        let func = unsafe { LLVMAddFunction(self.module, func_name_c.as_ptr(), llvm_func_type) };
+        assert!(!func.is_null());
+        // Set external linkage
+        unsafe { LLVMSetLinkage(func, LLVMLinkage::LLVMExternalLinkage) };
+        self.type_cache.store_func(funcname, llvm_func_type);
+    }
 
-            let func =
-                unsafe { LLVMAddFunction(self.module, func_name_c.as_ptr(), llvm_func_type) };
+    /// Declares function of nyastd, which is called implicitly by generated code (`nya_alloc` for `new`).
+    /// JIT maps it via `nyastd::register_functions` as other std functions.
+    /// Function declared by user is reused, if it has the same signature
+    pub fn std_function(
+        &mut self,
+        name: &str,
+        args: &[Rc<Type>],
+        ret: &Type,
+    ) -> anyhow::Result<(LLVMValueRef, LLVMTypeRef)> {
+        let func_type = self.func_llvm_type(args, ret, false);
+        let name_c = CString::new(name).unwrap();
+
+        let mut func = unsafe { LLVMGetNamedFunction(self.module, name_c.as_ptr()) };
+        if func.is_null() {
+            func = unsafe { LLVMAddFunction(self.module, name_c.as_ptr(), func_type) };
             assert!(!func.is_null());
-            // Set external linkage
             unsafe { LLVMSetLinkage(func, LLVMLinkage::LLVMExternalLinkage) };
-            self.type_cache.store_func(funcname.clone(), llvm_func_type);
+        } else if unsafe { LLVMGlobalGetValueType(func) } != func_type {
+            // Function types are uniqued by llvm, so pointers can be compared
+            anyhow::bail!("Conflicting declaration of {}", name);
         }
+
+        Ok((func, func_type))
+    }
+
+    /// Llvm type of function with given signature, also used for calls through function pointers
+    pub fn func_llvm_type(&self, args: &[Rc<Type>], ret: &Type, is_vararg: bool) -> LLVMTypeRef {
+        let mut llvm_arg_types: Vec<LLVMTypeRef> = args.iter().map(|t| t.llvm_type(self)).collect();
+
+        let llvm_func_type = unsafe {
+            LLVMFunctionType(
+                ret.llvm_type(self),
+                llvm_arg_types.as_mut_ptr(),
+                llvm_arg_types.len() as u32,
+                is_vararg as i32,
+            )
+        };
+        assert!(!llvm_func_type.is_null());
+
+        llvm_func_type
+    }
+
+    /// Registers generic struct instances, which were created after `register_types`
+    fn register_new_instances(&mut self) -> anyhow::Result<()> {
+        let instances = self.definitions.take_new_instances();
+        let mut registered = HashMap::new();
+
+        for ty in &instances {
+            if let Type::Custom(ty) = ty.as_ref() {
+                self.register_types_recursive(ty, &mut registered)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Leaves layer, its deferred statements are generated before
+    pub fn exit_scope(&mut self) -> anyhow::Result<()> {
+        self.build_deferred(self.vislayers.depth() - 1)?;
+        self.vislayers.exit_layer();
+        Ok(())
+    }
+
+    /// Generates deferred statements of layers deeper than `depth`, from the innermost one.
+    /// It's done on every exit from them: end of scope, `return`, `break` & `continue`.
+    /// Each statement sees only its own & outer layers, so it's not affected by shadowing.
+    /// It can't leave itself via `return`, `break` or `continue`, nor contain `defer`
+    pub fn build_deferred(&mut self, depth: usize) -> anyhow::Result<()> {
+        let mut hidden = vec![];
+
+        while self.vislayers.depth() > depth {
+            let deferred = std::mem::take(&mut self.vislayers.layers.last_mut().unwrap().deferred);
+            for st in deferred.iter().rev() {
+                // Variables declared after `defer` are hidden
+                let variables = std::mem::replace(
+                    &mut self.vislayers.layers.last_mut().unwrap().variables,
+                    st.variables.clone(),
+                );
+                self.vislayers.enter_layer();
+                let stmt_depth = self.vislayers.depth();
+                let outer_depth = self.vislayers.deferred_depth.replace(stmt_depth);
+                st.stmt.codegen(self)?;
+                self.exit_scope()?;
+                self.vislayers.deferred_depth = outer_depth;
+                self.vislayers.layers.last_mut().unwrap().variables = variables;
+            }
+
+            let mut layer = self.vislayers.layers.pop().unwrap();
+            layer.deferred = deferred;
+            hidden.push(layer);
+        }
+
+        while let Some(layer) = hidden.pop() {
+            self.vislayers.layers.push(layer);
+        }
+
+        Ok(())
+    }
+
+    /// Name under which variable is registered: locals are used as is,
+    /// globals are qualified with their module
+    pub fn variable_name(&self, name: &str) -> anyhow::Result<String> {
+        if self.vislayers.is_local(name) {
+            return Ok(name.into());
+        }
+        self.definitions.global_name(name)
+    }
+
+    /// Resolves type written in source, new instances of generic structs are registered in llvm
+    pub fn resolve_type(&mut self, name: &TypeName) -> anyhow::Result<Option<Rc<Type>>> {
+        let res = self.definitions.resolve_type(name)?;
+        self.register_new_instances()?;
+
+        Ok(res)
+    }
+
+    /// Declares instance of generic function, its body is generated after other functions
+    pub fn instantiate_func(
+        &mut self,
+        name: &str,
+        type_args: &[Rc<Type>],
+    ) -> anyhow::Result<String> {
+        let (symbol, is_new) = self.definitions.instantiate_func(name, type_args)?;
+
+        if is_new {
+            // Signature can contain new struct instances
+            self.register_new_instances()?;
+            self.declare_function(symbol.clone());
+
+            let params = self.definitions.generic_params(name);
+            self.pending_instances.push(PendingInstance {
+                name: name.into(),
+                symbol: symbol.clone(),
+                type_params: params
+                    .iter()
+                    .cloned()
+                    .zip(type_args.iter().cloned())
+                    .collect(),
+            });
+        }
+
+        Ok(symbol)
     }
 }
 
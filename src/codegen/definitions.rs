@@ -1,46 +1,65 @@
-use std::{collections::HashMap, ffi::CString, fmt::Display, rc::Rc};
+use std::{
+    cell::OnceCell,
+    collections::{HashMap, HashSet},
+    ffi::CString,
+    fmt::Display,
+    rc::Rc,
+};
 
 use anyhow::Context;
 use llvm_sys::{
     core::{
-        LLVMDoubleTypeInContext, LLVMFloatTypeInContext, LLVMGetTypeByName2, LLVMIntTypeInContext,
+        LLVMArrayType2, LLVMDoubleTypeInContext, LLVMFloatTypeInContext, LLVMGetTypeByName2,
+        LLVMIntTypeInContext, LLVMPointerTypeInContext, LLVMStructTypeInContext,
         LLVMVoidTypeInContext,
     },
     prelude::LLVMTypeRef,
 };
 
-use crate::{ast::TypedArg, utils::nodes::StructDef, visitor::Visitor};
+use crate::{
+    ast::{Statement, TypeName, TypedArg},
+    utils::nodes::{EnumDef, Match},
+    visitor::{Acceptor, Visitor},
+};
 
-use super::CodegenContext;
+use super::{CodegenContext, TypedValue};
 
-#[derive(PartialEq, Eq, Debug)]
 pub struct CustomType {
     pub name: String,
     // Field name -> (position, Type)
-    pub fields: HashMap<String, (usize, Rc<Type>)>,
+    // Set after type is declared, so fields can point to the type itself
+    fields: OnceCell<HashMap<String, (usize, Rc<Type>)>>,
 }
 
 impl CustomType {
-    fn from_def(structdef: &StructDef, types: &ProgramDefinitions) -> anyhow::Result<Self> {
+    fn declare(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            fields: OnceCell::new(),
+        }
+    }
+
+    fn define(&self, fields: &[TypedArg], types: &mut ProgramDefinitions) -> anyhow::Result<()> {
         let mut type_fields = HashMap::new();
 
-        for (pos, field) in structdef.fields.iter().enumerate() {
-            let field_type = types.get_type(&field.tp);
+        for (pos, field) in fields.iter().enumerate() {
+            let field_type = types.resolve_type(&field.tp)?;
             if let Some(field_type) = field_type {
                 type_fields.insert(field.name.clone(), (pos, field_type));
             } else {
-                anyhow::bail!(
-                    "Unknown type {} in definition of {}",
-                    field.tp,
-                    structdef.name
-                );
+                anyhow::bail!("Unknown type {} in definition of {}", field.tp, self.name);
             }
         }
 
-        Ok(Self {
-            name: structdef.name.clone(),
-            fields: type_fields,
-        })
+        let res = self.fields.set(type_fields);
+        debug_assert!(res.is_ok(), "type {} defined twice", self.name);
+        Ok(())
+    }
+
+    pub fn fields(&self) -> &HashMap<String, (usize, Rc<Type>)> {
+        self.fields
+            .get()
+            .unwrap_or_else(|| panic!("type {} is declared, but not defined", self.name))
     }
 
     pub fn llvm_type(&self, cxt: &CodegenContext) -> LLVMTypeRef {
@@ -52,14 +71,169 @@ impl CustomType {
 
     #[cfg(test)]
     fn test_sample() -> Self {
+        let res = Self::declare("test");
+        res.fields.set(HashMap::new()).unwrap();
+        res
+    }
+}
+
+/* Custom types are nominal, also comparing fields could loop on self-referencing types */
+impl PartialEq for CustomType {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for CustomType {}
+
+impl std::fmt::Debug for CustomType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomType")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Display for CustomType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Variant of [`EnumType`], tag is stored in first field of enum value
+#[derive(Debug)]
+pub struct EnumVariant {
+    pub name: String,
+    pub fields: Vec<Rc<Type>>,
+    pub tag: i64,
+}
+
+/// Tagged union: `{ i64 tag, [N x i64] payload }` in llvm,
+/// payload of each variant is accessed through its own struct type named `Enum.Variant`
+pub struct EnumType {
+    pub name: String,
+    // Set after type is declared, so payload can point to the type itself
+    variants: OnceCell<Vec<EnumVariant>>,
+}
+
+impl EnumType {
+    fn declare(name: &str) -> Self {
         Self {
-            name: "test".into(),
-            fields: HashMap::new(),
+            name: name.into(),
+            variants: OnceCell::new(),
         }
     }
+
+    fn define(&self, enumdef: &EnumDef, types: &mut ProgramDefinitions) -> anyhow::Result<()> {
+        let mut variants: Vec<EnumVariant> = Vec::with_capacity(enumdef.variants.len());
+        let mut next_tag = 0;
+
+        for variant in &enumdef.variants {
+            if variants.iter().any(|v| v.name == variant.name) {
+                anyhow::bail!("Variant {} of {} is defined twice", variant.name, self.name);
+            }
+
+            let tag = variant.value.unwrap_or(next_tag);
+            if let Some(other) = variants.iter().find(|v| v.tag == tag) {
+                anyhow::bail!(
+                    "Variants {} and {} of {} have the same value {}",
+                    other.name,
+                    variant.name,
+                    self.name,
+                    tag
+                );
+            }
+            next_tag = tag.wrapping_add(1);
+
+            let mut fields = Vec::with_capacity(variant.fields.len());
+            for field in &variant.fields {
+                let field_type = types.resolve_type(field)?.with_context(|| {
+                    format!(
+                        "Unknown type {} in variant {} of {}",
+                        field, variant.name, self.name
+                    )
+                })?;
+                fields.push(field_type);
+            }
+
+            variants.push(EnumVariant {
+                name: variant.name.clone(),
+                fields,
+                tag,
+            });
+        }
+
+        let res = self.variants.set(variants);
+        debug_assert!(res.is_ok(), "type {} defined twice", self.name);
+        Ok(())
+    }
+
+    pub fn variants(&self) -> &Vec<EnumVariant> {
+        self.variants
+            .get()
+            .unwrap_or_else(|| panic!("type {} is declared, but not defined", self.name))
+    }
+
+    pub fn variant(&self, name: &str) -> Option<&EnumVariant> {
+        self.variants().iter().find(|v| v.name == name)
+    }
+
+    /// No variant has payload, such enums can be compared and casted to integers
+    pub fn is_c_like(&self) -> bool {
+        self.variants().iter().all(|v| v.fields.is_empty())
+    }
+
+    /// Payload is stored as `[N x i64]`, so it's big and aligned enough for any variant
+    pub fn payload_words(&self) -> u64 {
+        self.variants()
+            .iter()
+            .map(|v| fields_size_bound(&v.fields) / 8)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Name of llvm struct type of variant payload
+    pub fn variant_type_name(&self, variant: &EnumVariant) -> String {
+        format!("{}.{}", self.name, variant.name)
+    }
+
+    pub fn llvm_type(&self, cxt: &CodegenContext) -> LLVMTypeRef {
+        let name = CString::new(self.name.clone()).unwrap();
+        let res = unsafe { LLVMGetTypeByName2(cxt.cxt, name.as_ptr()) };
+        assert!(!res.is_null(), "type {} is unknown to llvm", self);
+        res
+    }
+
+    pub fn variant_llvm_type(&self, cxt: &CodegenContext, variant: &EnumVariant) -> LLVMTypeRef {
+        let name = CString::new(self.variant_type_name(variant)).unwrap();
+        let res = unsafe { LLVMGetTypeByName2(cxt.cxt, name.as_ptr()) };
+        assert!(
+            !res.is_null(),
+            "variant {} is unknown to llvm",
+            variant.name
+        );
+        res
+    }
 }
 
-impl Display for CustomType {
+/* Enums are nominal as custom types */
+impl PartialEq for EnumType {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for EnumType {}
+
+impl std::fmt::Debug for EnumType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EnumType")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Display for EnumType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.name)
     }
@@ -67,7 +241,8 @@ impl Display for CustomType {
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub struct IntType {
-    bitwidth: u8,
+    pub bitwidth: u8,
+    pub signed: bool,
 }
 
 impl IntType {
@@ -78,7 +253,11 @@ impl IntType {
 
 impl Display for IntType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "i{}", self.bitwidth)
+        match (self.bitwidth, self.signed) {
+            (1, false) => write!(f, "bool"),
+            (bitwidth, true) => write!(f, "i{}", bitwidth),
+            (bitwidth, false) => write!(f, "u{}", bitwidth),
+        }
     }
 }
 
@@ -96,6 +275,11 @@ impl FloatType {
             unsafe { LLVMFloatTypeInContext(cxt.cxt) }
         }
     }
+
+    /// Bits of significand, integers up to `2^mantissa_bits` are represented exactly
+    pub fn mantissa_bits(&self) -> u8 {
+        if self.bitwidth == 64 { 53 } else { 24 }
+    }
 }
 
 impl Display for FloatType {
@@ -104,14 +288,52 @@ impl Display for FloatType {
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(Debug)]
 pub enum Type {
     Void(),
     Float(FloatType),
     Int(IntType),
     Custom(CustomType),
+    Enum(EnumType),
+    Pointer(Rc<Type>),
+    Array(Rc<Type>, u64),
+    /// Function pointer: args and return type
+    Func(Vec<Rc<Type>>, Rc<Type>),
+    /// `newtype Name = T;`: distinct type with layout of `T`, arithmetic only if `T` has it
+    Newtype(String, Rc<Type>),
+    /// Anonymous tuple, it's literal llvm struct, so it's passed & returned by value
+    Tuple(Vec<Rc<Type>>),
+    /// `type Name = T;`: the same type as `T`, name is kept for diagnostics
+    Alias(String, Rc<Type>),
+}
+
+/// Aliases are equal to their targets
+impl PartialEq for Type {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.dealias(), other.dealias()) {
+            (Type::Void(), Type::Void()) => true,
+            (Type::Float(lhs), Type::Float(rhs)) => lhs == rhs,
+            (Type::Int(lhs), Type::Int(rhs)) => lhs == rhs,
+            (Type::Custom(lhs), Type::Custom(rhs)) => lhs == rhs,
+            (Type::Enum(lhs), Type::Enum(rhs)) => lhs == rhs,
+            (Type::Pointer(lhs), Type::Pointer(rhs)) => lhs == rhs,
+            (Type::Array(lhs, lhs_len), Type::Array(rhs, rhs_len)) => {
+                lhs == rhs && lhs_len == rhs_len
+            }
+            (Type::Func(lhs_args, lhs_ret), Type::Func(rhs_args, rhs_ret)) => {
+                lhs_args == rhs_args && lhs_ret == rhs_ret
+            }
+            (Type::Newtype(lhs, lhs_inner), Type::Newtype(rhs, rhs_inner)) => {
+                lhs == rhs && lhs_inner == rhs_inner
+            }
+            (Type::Tuple(lhs), Type::Tuple(rhs)) => lhs == rhs,
+            _ => false,
+        }
+    }
 }
 
+impl Eq for Type {}
+
 impl Type {
     pub fn llvm_type(&self, cxt: &CodegenContext) -> LLVMTypeRef {
         let res = match self {
@@ -119,6 +341,17 @@ impl Type {
             Type::Float(float_type) => float_type.llvm_type(cxt),
             Type::Int(int_type) => int_type.llvm_type(cxt),
             Type::Custom(custom_type) => custom_type.llvm_type(cxt),
+            Type::Enum(enum_type) => enum_type.llvm_type(cxt),
+            // Pointers are opaque in llvm
+            Type::Pointer(_) | Type::Func(..) => unsafe { LLVMPointerTypeInContext(cxt.cxt, 0) },
+            Type::Array(elem, len) => unsafe { LLVMArrayType2(elem.llvm_type(cxt), *len) },
+            Type::Newtype(_, inner) | Type::Alias(_, inner) => inner.llvm_type(cxt),
+            Type::Tuple(elems) => {
+                let mut elems: Vec<_> = elems.iter().map(|elem| elem.llvm_type(cxt)).collect();
+                unsafe {
+                    LLVMStructTypeInContext(cxt.cxt, elems.as_mut_ptr(), elems.len() as u32, 0)
+                }
+            }
         };
 
         assert!(!res.is_null());
@@ -129,32 +362,132 @@ impl Type {
     /// This type can perform arithmetic
     pub fn arithmetic(&self) -> bool {
         match self {
-            Type::Void() | Type::Custom(_) => false,
+            Type::Void()
+            | Type::Custom(_)
+            | Type::Enum(_)
+            | Type::Pointer(_)
+            | Type::Array(..)
+            | Type::Func(..)
+            | Type::Tuple(_) => false,
             Type::Float(_) | Type::Int(_) => true,
+            Type::Newtype(_, inner) | Type::Alias(_, inner) => inner.arithmetic(),
+        }
+    }
+
+    /// Type, which is used to build operations on values of this type: wrapped type for newtypes
+    pub fn underlying(&self) -> &Type {
+        match self.dealias() {
+            Type::Newtype(_, inner) => inner.underlying(),
+            ty => ty,
+        }
+    }
+
+    /// Target of type alias, other types are returned as is
+    pub fn dealias(&self) -> &Type {
+        match self {
+            Type::Alias(_, target) => target.dealias(),
+            _ => self,
+        }
+    }
+
+    /// `*void`, which can be implicitly casted to any other pointer
+    pub fn is_void_ptr(&self) -> bool {
+        matches!(self.dealias(), Type::Pointer(pointee) if **pointee == Type::Void())
+    }
+
+    /// Upper bound of type size in bytes, used to size enum payloads.
+    /// Every struct field is rounded up to 8 bytes, so alignment padding is covered
+    pub fn size_bound(&self) -> u64 {
+        match self {
+            Type::Void() => 0,
+            Type::Float(float_type) => float_type.bitwidth as u64 / 8,
+            Type::Int(int_type) => (int_type.bitwidth as u64).div_ceil(8),
+            Type::Pointer(_) | Type::Func(..) => 8,
+            Type::Array(elem, len) => elem.size_bound() * len,
+            Type::Custom(custom_type) => {
+                fields_size_bound(custom_type.fields().values().map(|f| &f.1))
+            }
+            Type::Enum(enum_type) => 8 + enum_type.payload_words() * 8,
+            Type::Newtype(_, inner) | Type::Alias(_, inner) => inner.size_bound(),
+            Type::Tuple(elems) => fields_size_bound(elems),
         }
     }
 }
 
+/// Size bound of struct with given fields, see [`Type::size_bound`]
+fn fields_size_bound<'a>(fields: impl IntoIterator<Item = &'a Rc<Type>>) -> u64 {
+    fields
+        .into_iter()
+        .map(|field| field.size_bound().next_multiple_of(8))
+        .sum()
+}
+
+/// Aliases are printed by their names, alternate form (`{:#}`) prints their targets instead
 impl Display for Type {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        /// Writes types separated by comma, keeping format flags
+        fn write_list(f: &mut std::fmt::Formatter<'_>, types: &[Rc<Type>]) -> std::fmt::Result {
+            for (i, ty) in types.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                ty.fmt(f)?;
+            }
+            Ok(())
+        }
+
         match self {
             Type::Void() => write!(f, "void"),
             Type::Float(float_type) => float_type.fmt(f),
             Type::Int(int_type) => int_type.fmt(f),
             Type::Custom(custom_type) => custom_type.fmt(f),
+            Type::Enum(enum_type) => enum_type.fmt(f),
+            Type::Pointer(pointee) => {
+                write!(f, "*")?;
+                pointee.fmt(f)
+            }
+            Type::Array(elem, len) => {
+                write!(f, "[")?;
+                elem.fmt(f)?;
+                write!(f, "; {}]", len)
+            }
+            Type::Func(args, ret) => {
+                write!(f, "fn(")?;
+                write_list(f, args)?;
+                write!(f, ")")?;
+                if **ret != Type::Void() {
+                    write!(f, " -> ")?;
+                    ret.fmt(f)?;
+                }
+                Ok(())
+            }
+            Type::Newtype(name, _) => write!(f, "{}", name),
+            Type::Tuple(elems) => {
+                write!(f, "(")?;
+                write_list(f, elems)?;
+                write!(f, ")")
+            }
+            Type::Alias(_, target) if f.alternate() => target.fmt(f),
+            Type::Alias(name, _) => write!(f, "{}", name),
         }
     }
 }
 
 macro_rules! bad_type {
     (1, $ty:tt, $name:ident) => {{
-        if let Type::$ty(_) = $name.as_ref() {
+        if let Type::$ty(_) = $name.dealias() {
             anyhow::bail!("Can't find common type when type is {}", $name);
         }
     }};
 
     (0, $ty:tt, $name:ident) => {{
-        if let Type::$ty() = $name.as_ref() {
+        if let Type::$ty() = $name.dealias() {
+            anyhow::bail!("Can't find common type when type is {}", $name);
+        }
+    }};
+
+    (2, $ty:tt, $name:ident) => {{
+        if let Type::$ty(_, _) = $name.dealias() {
             anyhow::bail!("Can't find common type when type is {}", $name);
         }
     }};
@@ -162,48 +495,154 @@ macro_rules! bad_type {
 
 impl Type {
     pub fn common_type(lhs: &Rc<Type>, rhs: &Rc<Type>) -> anyhow::Result<Rc<Type>> {
+        /* Alias is kept, if common type is its target */
+        if let Type::Alias(_, target) = lhs.as_ref() {
+            let res = Self::common_type(target, rhs)?;
+            if Rc::ptr_eq(&res, target) {
+                return Ok(lhs.clone());
+            }
+            return Ok(res);
+        }
+        if let Type::Alias(_, target) = rhs.as_ref() {
+            let res = Self::common_type(lhs, target)?;
+            if Rc::ptr_eq(&res, target) {
+                return Ok(rhs.clone());
+            }
+            return Ok(res);
+        }
+
         bad_type!(0, Void, lhs);
         bad_type!(0, Void, rhs);
         bad_type!(1, Custom, lhs);
         bad_type!(1, Custom, rhs);
+        bad_type!(1, Enum, lhs);
+        bad_type!(1, Enum, rhs);
+        bad_type!(2, Array, lhs);
+        bad_type!(2, Array, rhs);
+        bad_type!(1, Tuple, lhs);
+        bad_type!(1, Tuple, rhs);
 
-        if let Type::Float(l_v) = lhs.as_ref() {
-            if let Type::Float(r_v) = rhs.as_ref() {
-                if l_v > r_v {
-                    return Ok(lhs.clone());
-                } else {
-                    return Ok(rhs.clone());
-                }
-            } else {
-                return Ok(lhs.clone());
-            }
-        } else if let Type::Float(_) = rhs.as_ref() {
-            return Ok(rhs.clone());
+        /* Newtypes are mixed only with themselves */
+        if let Type::Newtype(_, inner) = lhs.as_ref()
+            && lhs == rhs
+        {
+            Self::common_type(inner, inner)?;
+            return Ok(lhs.clone());
+        }
+        if let Type::Newtype(..) = lhs.as_ref() {
+            anyhow::bail!("Can't find common type for {} and {}", lhs, rhs);
+        }
+        if let Type::Newtype(..) = rhs.as_ref() {
+            anyhow::bail!("Can't find common type for {} and {}", lhs, rhs);
         }
 
-        // They are both ints
+        if let Type::Pointer(_) | Type::Func(..) = lhs.as_ref() {
+            return Self::common_ptr_type(lhs, rhs);
+        } else if let Type::Pointer(_) | Type::Func(..) = rhs.as_ref() {
+            return Self::common_ptr_type(rhs, lhs);
+        }
 
-        if let Type::Int(l_v) = lhs.as_ref() {
-            if let Type::Int(r_v) = rhs.as_ref() {
-                if l_v > r_v {
-                    return Ok(lhs.clone());
+        // Common type is the one, to which other type is widened (see `codegen::cast`):
+        // - float wins, if it represents every value of int type
+        // - same signedness => wider type
+        // - signed type can represent all values of narrower unsigned one => signed type
+        // Otherwise explicit `as` is required
+        if rhs.widens_to(lhs) {
+            Ok(lhs.clone())
+        } else if lhs.widens_to(rhs) {
+            Ok(rhs.clone())
+        } else {
+            anyhow::bail!(
+                "Can't find common type for {} and {}, use explicit `as` cast",
+                lhs,
+                rhs
+            );
+        }
+    }
+
+    /// Every value of int or float type `self` is represented by type `to` exactly
+    pub fn widens_to(&self, to: &Type) -> bool {
+        match (self.dealias(), to.dealias()) {
+            (Type::Int(from_int), Type::Int(to_int)) => {
+                if from_int.signed == to_int.signed {
+                    to_int.bitwidth >= from_int.bitwidth
                 } else {
-                    return Ok(rhs.clone());
+                    // Unsigned can be widened to bigger signed, but negative values can't become unsigned
+                    !from_int.signed && to_int.bitwidth > from_int.bitwidth
                 }
             }
+            (Type::Int(from_int), Type::Float(to_fp)) => {
+                from_int.bitwidth - from_int.signed as u8 <= to_fp.mantissa_bits()
+            }
+            (Type::Float(from_fp), Type::Float(to_fp)) => to_fp.bitwidth >= from_fp.bitwidth,
+            _ => false,
         }
+    }
 
-        unreachable!("Ifs above should never miss");
+    fn common_ptr_type(ptr: &Rc<Type>, other: &Rc<Type>) -> anyhow::Result<Rc<Type>> {
+        if ptr == other || other.is_void_ptr() {
+            Ok(ptr.clone())
+        } else if ptr.is_void_ptr() && matches!(other.as_ref(), Type::Pointer(_) | Type::Func(..)) {
+            Ok(other.clone())
+        } else {
+            anyhow::bail!("Can't find common type for {} and {}", ptr, other);
+        }
     }
 }
 
-type FuncType = (Vec<Rc<Type>>, Rc<Type>);
+/// Mangled name of method, it can't clash with usual functions, because `.` can't be used in identifiers
+pub fn method_name(type_name: &str, method: &str) -> String {
+    format!("{}.{}", type_name, method)
+}
+
+/// Mangled name of generic item instance, type args can't clash with identifiers.
+/// Aliases are replaced with their targets, so they share instance
+fn instance_name(name: &str, type_args: &[Rc<Type>]) -> String {
+    let args: Vec<_> = type_args.iter().map(|arg| format!("{:#}", arg)).collect();
+    format!("{}<{}>", name, args.join(", "))
+}
+
+/// (args, return type, is variadic)
+type FuncType = (Vec<Rc<Type>>, Rc<Type>, bool);
+
+/// `struct Name<T...> { fields }`, instantiated when `Name<Args...>` is used
+struct GenericStruct {
+    params: Vec<String>,
+    fields: Vec<TypedArg>,
+}
+
+/// Signature of `fn name<T...>(args) -> ret`, body is generated per instance
+struct GenericFunc {
+    params: Vec<String>,
+    args: Vec<TypedArg>,
+    rettype: TypeName,
+}
 
 pub struct ProgramDefinitions {
     /// typename => typedata
     pub types: HashMap<String, Rc<Type>>,
     /// func_name => func_info
     functions: HashMap<String, Rc<FuncType>>,
+    generic_structs: HashMap<String, GenericStruct>,
+    generic_funcs: HashMap<String, GenericFunc>,
+    /// Mangled name of struct instance => (generic name, type args)
+    instances: HashMap<String, (String, Vec<Rc<Type>>)>,
+    /// Struct instances, which are not registered in llvm yet
+    new_instances: Vec<Rc<Type>>,
+    /// Type params of generic item, which is being instantiated
+    type_params: HashMap<String, Rc<Type>>,
+    /// Module, which items are being defined or generated, `None` for root file
+    module: Option<String>,
+    /// Module => names of modules imported by it
+    imports: HashMap<Option<String>, HashSet<String>>,
+    /// Qualified names of `pub` items
+    public: HashSet<String>,
+    /// Items of root file, modules can't use them
+    root_items: HashSet<String>,
+    /// Qualified names of `const` & `static` items
+    globals: HashSet<String>,
+    /// External function => modules that declared it, each module can declare it once
+    externs: HashMap<String, HashSet<Option<String>>>,
 }
 
 impl ProgramDefinitions {
@@ -211,22 +650,44 @@ impl ProgramDefinitions {
         let mut me = Self {
             types: HashMap::new(),
             functions: HashMap::new(),
+            generic_structs: HashMap::new(),
+            generic_funcs: HashMap::new(),
+            instances: HashMap::new(),
+            new_instances: vec![],
+            type_params: HashMap::new(),
+            module: None,
+            imports: HashMap::from([(None, HashSet::new())]),
+            public: HashSet::new(),
+            root_items: HashSet::new(),
+            globals: HashSet::new(),
+            externs: HashMap::new(),
         };
 
         // Insert basic types
         me.types.insert("void".into(), Rc::new(Type::Void()));
 
-        me.types
-            .insert("bool".into(), Rc::new(Type::Int(IntType { bitwidth: 1 })));
+        // bool is unsigned, so it's extended into 0/1
+        me.types.insert(
+            "bool".into(),
+            Rc::new(Type::Int(IntType {
+                bitwidth: 1,
+                signed: false,
+            })),
+        );
 
+        for bitwidth in [8, 16, 32, 64] {
+            for (prefix, signed) in [("i", true), ("u", false)] {
+                me.types.insert(
+                    format!("{}{}", prefix, bitwidth),
+                    Rc::new(Type::Int(IntType { bitwidth, signed })),
+                );
+            }
+        }
+
+        // Strings are null terminated `*i8`
+        let i8_type = me.types["i8"].clone();
         me.types
-            .insert("i8".into(), Rc::new(Type::Int(IntType { bitwidth: 8 })));
-        me.types
-            .insert("i16".into(), Rc::new(Type::Int(IntType { bitwidth: 16 })));
-        me.types
-            .insert("i32".into(), Rc::new(Type::Int(IntType { bitwidth: 32 })));
-        me.types
-            .insert("i64".into(), Rc::new(Type::Int(IntType { bitwidth: 64 })));
+            .insert("str".into(), Rc::new(Type::Pointer(i8_type)));
 
         me.types.insert(
             "f32".into(),
@@ -242,14 +703,20 @@ impl ProgramDefinitions {
         me
     }
 
-    fn add_func(&mut self, name: &str, args: &Vec<TypedArg>, ret: &str) -> anyhow::Result<()> {
+    fn add_func(
+        &mut self,
+        name: &str,
+        args: &Vec<TypedArg>,
+        ret: &TypeName,
+        is_vararg: bool,
+    ) -> anyhow::Result<()> {
         let ret = self
-            .get_type(ret)
+            .resolve_type(ret)?
             .context(format!("Unknown type {} in func def", ret))?;
 
         let mut processed_args = Vec::with_capacity(args.len());
         for arg in args {
-            let argtype = self.get_type(&arg.tp);
+            let argtype = self.resolve_type(&arg.tp)?;
             if argtype.is_none() {
                 anyhow::bail!(
                     "Unknown type {} in {}-th arg of function {name}",
@@ -272,15 +739,192 @@ impl ProgramDefinitions {
 
         let res = self
             .functions
-            .insert(name.into(), Rc::new((processed_args, ret)));
+            .insert(name.into(), Rc::new((processed_args, ret, is_vararg)));
 
-        if res.is_some() {
+        if res.is_some() || self.generic_funcs.contains_key(name) {
             anyhow::bail!("Redefenition of func {}", name);
         }
 
         Ok(())
     }
 
+    fn add_generic_func(
+        &mut self,
+        name: &str,
+        node: &crate::utils::nodes::FuncImpl,
+    ) -> anyhow::Result<()> {
+        if self.functions.contains_key(name) {
+            anyhow::bail!("Redefenition of func {}", name);
+        }
+
+        let generic = GenericFunc {
+            params: node.type_params.clone(),
+            args: node.args.clone(),
+            rettype: node.rettype.clone(),
+        };
+        if self.generic_funcs.insert(name.into(), generic).is_some() {
+            anyhow::bail!("Redefenition of func {}", name);
+        }
+
+        Ok(())
+    }
+
+    pub fn is_generic_func(&self, name: &str) -> bool {
+        self.generic_funcs.contains_key(name)
+    }
+
+    pub fn generic_params(&self, name: &str) -> &Vec<String> {
+        &self.generic_funcs[name].params
+    }
+
+    /// Declares instance of generic function with given type args, returns its mangled name.
+    /// Second value is `true` if instance is new, so its body should be generated
+    pub fn instantiate_func(
+        &mut self,
+        name: &str,
+        type_args: &[Rc<Type>],
+    ) -> anyhow::Result<(String, bool)> {
+        let generic = &self.generic_funcs[name];
+        if generic.params.len() != type_args.len() {
+            anyhow::bail!(
+                "Function {} expects {} type arguments, but {} were given",
+                name,
+                generic.params.len(),
+                type_args.len()
+            );
+        }
+
+        let symbol = instance_name(name, type_args);
+        if self.functions.contains_key(&symbol) {
+            return Ok((symbol, false));
+        }
+
+        let params: HashMap<_, _> = generic
+            .params
+            .iter()
+            .cloned()
+            .zip(type_args.iter().cloned())
+            .collect();
+        let (args, rettype) = (generic.args.clone(), generic.rettype.clone());
+
+        // Signature is resolved in module of generic function
+        let prev = self.set_type_params(params);
+        let prev_module = self.enter_module(self.module_of(name));
+        let res = self.add_func(&symbol, &args, &rettype, false);
+        self.enter_module(prev_module);
+        self.set_type_params(prev);
+        res?;
+
+        Ok((symbol, true))
+    }
+
+    /// Deduces type args of generic function from types of call arguments.
+    /// Number literals (`literals[i]` is set) are used only for params,
+    /// which can't be deduced from other arguments
+    pub fn infer_type_args(
+        &self,
+        name: &str,
+        args: &[TypedValue],
+        literals: &[bool],
+    ) -> anyhow::Result<Vec<Rc<Type>>> {
+        let generic = &self.generic_funcs[name];
+        let mut bindings = HashMap::new();
+
+        for ((arg, value), literal) in generic.args.iter().zip(args).zip(literals) {
+            if !literal {
+                self.unify(name, &generic.params, &arg.tp, &value.ty, &mut bindings)?;
+            }
+        }
+
+        // Literals only fill params left unbound, they are casted to bound type
+        for ((arg, value), literal) in generic.args.iter().zip(args).zip(literals) {
+            if *literal {
+                let mut weak = HashMap::new();
+                self.unify(name, &generic.params, &arg.tp, &value.ty, &mut weak)?;
+                for (param, ty) in weak {
+                    bindings.entry(param).or_insert(ty);
+                }
+            }
+        }
+
+        generic
+            .params
+            .iter()
+            .map(|param| {
+                bindings.get(param).cloned().with_context(|| {
+                    format!("Can't infer type parameter {} of function {}", param, name)
+                })
+            })
+            .collect()
+    }
+
+    /// Matches written param type with actual arg type, binding generic params.
+    /// Mismatches of other types are left for implicit cast of argument
+    fn unify(
+        &self,
+        func: &str,
+        params: &[String],
+        tp: &TypeName,
+        ty: &Rc<Type>,
+        bindings: &mut HashMap<String, Rc<Type>>,
+    ) -> anyhow::Result<()> {
+        match (tp, ty.dealias()) {
+            (TypeName::Named(name), _) if params.contains(name) => match bindings.get(name) {
+                None => {
+                    bindings.insert(name.clone(), ty.clone());
+                }
+                Some(prev) if prev != ty => {
+                    anyhow::bail!(
+                        "Type parameter {} of function {} is both {} and {}",
+                        name,
+                        func,
+                        prev,
+                        ty
+                    );
+                }
+                Some(_) => {}
+            },
+            (TypeName::Pointer(pointee), Type::Pointer(ty)) => {
+                self.unify(func, params, pointee, ty, bindings)?
+            }
+            (TypeName::Array(elem, _), Type::Array(ty, _)) => {
+                self.unify(func, params, elem, ty, bindings)?
+            }
+            (TypeName::Generic(name, args), Type::Custom(custom)) => {
+                // Written name is resolved in module of generic function
+                let module = self.module_of(func);
+                if let Some((generic, type_args)) = self.instances.get(&custom.name)
+                    && self
+                        .type_name_in(module.as_deref(), name)
+                        .is_ok_and(|name| &name == generic)
+                    && type_args.len() == args.len()
+                {
+                    for (arg, ty) in args.iter().zip(type_args) {
+                        self.unify(func, params, arg, ty, bindings)?;
+                    }
+                }
+            }
+            (TypeName::Func(args, ret), Type::Func(arg_types, ret_type))
+                if args.len() == arg_types.len() =>
+            {
+                for (arg, ty) in args.iter().zip(arg_types) {
+                    self.unify(func, params, arg, ty, bindings)?;
+                }
+                self.unify(func, params, ret, ret_type, bindings)?;
+            }
+            (TypeName::Tuple(elems), Type::Tuple(elem_types))
+                if elems.len() == elem_types.len() =>
+            {
+                for (elem, ty) in elems.iter().zip(elem_types) {
+                    self.unify(func, params, elem, ty, bindings)?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
     pub fn get_func(&self, name: &str) -> Option<&Rc<FuncType>> {
         self.functions.get(name)
     }
@@ -289,35 +933,648 @@ impl ProgramDefinitions {
         self.types.get(name).cloned()
     }
 
+    /// Resolves type written in source, `None` if some type is unknown.
+    /// Instances of generic structs are created on demand, so it can fail
+    pub(super) fn resolve_type(&mut self, name: &TypeName) -> anyhow::Result<Option<Rc<Type>>> {
+        let res = match name {
+            TypeName::Named(name) => match self.type_params.get(name) {
+                Some(ty) => Some(ty.clone()),
+                None => self.get_type(&self.type_name(name)?),
+            },
+            TypeName::Pointer(pointee) => self
+                .resolve_type(pointee)?
+                .map(|pointee| Rc::new(Type::Pointer(pointee))),
+            TypeName::Array(elem, len) => self
+                .resolve_type(elem)?
+                .map(|elem| Rc::new(Type::Array(elem, *len))),
+            TypeName::Generic(name, args) => {
+                let mut type_args = Vec::with_capacity(args.len());
+                for arg in args {
+                    let Some(arg) = self.resolve_type(arg)? else {
+                        return Ok(None);
+                    };
+                    type_args.push(arg);
+                }
+                self.instantiate_struct(&self.type_name(name)?, type_args)?
+            }
+            TypeName::Func(args, ret) => {
+                let mut arg_types = Vec::with_capacity(args.len());
+                for arg in args {
+                    let Some(arg) = self.resolve_type(arg)? else {
+                        return Ok(None);
+                    };
+                    arg_types.push(arg);
+                }
+                self.resolve_type(ret)?
+                    .map(|ret| Rc::new(Type::Func(arg_types, ret)))
+            }
+            TypeName::Tuple(elems) => {
+                let mut elem_types = Vec::with_capacity(elems.len());
+                for elem in elems {
+                    let Some(elem) = self.resolve_type(elem)? else {
+                        return Ok(None);
+                    };
+                    elem_types.push(elem);
+                }
+                Some(Rc::new(Type::Tuple(elem_types)))
+            }
+        };
+
+        Ok(res)
+    }
+
+    fn instantiate_struct(
+        &mut self,
+        name: &str,
+        type_args: Vec<Rc<Type>>,
+    ) -> anyhow::Result<Option<Rc<Type>>> {
+        let Some(generic) = self.generic_structs.get(name) else {
+            return Ok(None);
+        };
+        if generic.params.len() != type_args.len() {
+            anyhow::bail!(
+                "Type {} expects {} type arguments, but {} were given",
+                name,
+                generic.params.len(),
+                type_args.len()
+            );
+        }
+
+        let mangled = instance_name(name, &type_args);
+        if let Some(ty) = self.types.get(&mangled) {
+            return Ok(Some(ty.clone()));
+        }
+
+        let params: HashMap<_, _> = generic
+            .params
+            .iter()
+            .cloned()
+            .zip(type_args.iter().cloned())
+            .collect();
+        let fields = generic.fields.clone();
+
+        /* Declare type before fields resolving, so it can contain pointers to itself */
+        let ty = Rc::new(Type::Custom(CustomType::declare(&mangled)));
+        self.types.insert(mangled.clone(), ty.clone());
+        self.instances.insert(mangled, (name.into(), type_args));
+        self.new_instances.push(ty.clone());
+
+        let Type::Custom(custom) = ty.as_ref() else {
+            unreachable!("just created as custom type");
+        };
+        let prev = self.set_type_params(params);
+        let prev_module = self.enter_module(self.module_of(name));
+        let res = custom.define(&fields, self);
+        self.enter_module(prev_module);
+        self.set_type_params(prev);
+        res?;
+
+        Ok(Some(ty))
+    }
+
+    /// Sets type params for resolving types inside generic item, returns previous ones
+    pub fn set_type_params(
+        &mut self,
+        params: HashMap<String, Rc<Type>>,
+    ) -> HashMap<String, Rc<Type>> {
+        std::mem::replace(&mut self.type_params, params)
+    }
+
+    /// Generic struct instances, created since previous call
+    pub(super) fn take_new_instances(&mut self) -> Vec<Rc<Type>> {
+        std::mem::take(&mut self.new_instances)
+    }
+
     pub fn function_names(&self) -> impl IntoIterator<Item = &String> {
         self.functions.keys()
     }
+
+    /// Switches module, which items are defined or used, returns previous one
+    pub fn enter_module(&mut self, module: Option<String>) -> Option<String> {
+        std::mem::replace(&mut self.module, module)
+    }
+
+    pub fn is_module(&self, name: &str) -> bool {
+        self.imports.contains_key(&Some(name.into()))
+    }
+
+    /// Module of qualified item name, `geometry` for `geometry::area`
+    fn module_of(&self, name: &str) -> Option<String> {
+        let (module, _) = name.split_once("::")?;
+        self.is_module(module).then(|| module.into())
+    }
+
+    /// Qualified name of item defined in current module, `geometry::area` for `area` in `geometry.nya`
+    pub fn item_name(&self, name: &str) -> String {
+        match &self.module {
+            Some(module) => format!("{}::{}", module, name),
+            None => name.into(),
+        }
+    }
+
+    /// Remembers item defined in current module, returns its qualified name
+    fn define_item(&mut self, name: &str, is_pub: bool) -> String {
+        let name = self.item_name(name);
+        if is_pub {
+            self.public.insert(name.clone());
+        }
+        if self.module.is_none() {
+            self.root_items.insert(name.clone());
+        }
+        name
+    }
+
+    /// Resolves name used in `module` into qualified name of item:
+    /// items of the module itself come first, then builtins & external functions.
+    /// `other::item` can refer only to `pub` item of imported module.
+    /// If there is no such item, name is returned as is, so caller reports it as unknown
+    fn qualify(
+        &self,
+        module: Option<&str>,
+        name: &str,
+        exists: impl Fn(&str) -> bool,
+    ) -> anyhow::Result<String> {
+        if let Some(other) = self.module_of(name) {
+            if module != Some(other.as_str()) {
+                let module = module.map(String::from);
+                if !self.imports[&module].contains(&other) {
+                    anyhow::bail!("Module {} is not imported", other);
+                }
+                if exists(name) && !self.public.contains(name) {
+                    anyhow::bail!("{} is private", name);
+                }
+            }
+            return Ok(name.into());
+        }
+
+        if let Some(module) = module {
+            let own = format!("{}::{}", module, name);
+            if exists(&own) {
+                return Ok(own);
+            }
+            /* Root item of another kind doesn't hide item of the module with the same name */
+            if self.root_items.contains(name) && exists(name) {
+                anyhow::bail!(
+                    "{} is defined in root file, module {} can't use it",
+                    name,
+                    module
+                );
+            }
+        }
+
+        Ok(name.into())
+    }
+
+    /// Methods of type from other module can be called only if they are `pub`
+    pub fn check_method_access(&self, type_name: &str, method: &str) -> anyhow::Result<()> {
+        let module = self.module_of(type_name);
+        if module.is_some() && module != self.module && !self.public.contains(method) {
+            anyhow::bail!("Method {} is private", method);
+        }
+        Ok(())
+    }
+
+    /// Qualified name of function or generic function used in current module
+    pub fn func_name(&self, name: &str) -> anyhow::Result<String> {
+        self.qualify(self.module.as_deref(), name, |name| {
+            self.functions.contains_key(name) || self.generic_funcs.contains_key(name)
+        })
+    }
+
+    /// Qualified name of type or generic struct used in current module
+    pub fn type_name(&self, name: &str) -> anyhow::Result<String> {
+        self.type_name_in(self.module.as_deref(), name)
+    }
+
+    fn type_name_in(&self, module: Option<&str>, name: &str) -> anyhow::Result<String> {
+        self.qualify(module, name, |name| {
+            self.types.contains_key(name) || self.generic_structs.contains_key(name)
+        })
+    }
+
+    /// Qualified name of `const` or `static` item used in current module
+    pub fn global_name(&self, name: &str) -> anyhow::Result<String> {
+        self.qualify(self.module.as_deref(), name, |name| {
+            self.globals.contains(name)
+        })
+    }
 }
 
 impl Visitor for ProgramDefinitions {
     fn visit_program(&mut self, node: &crate::utils::nodes::Program) -> anyhow::Result<()> {
+        /* Types are declared first, so fields & signatures can refer to types defined later */
+        let mut declarer = TypeDeclarer {
+            definitions: self,
+            aliases: vec![],
+        };
+        for block in &node.blocks {
+            block.accept(&mut declarer)?;
+        }
+        declarer.resolve_aliases()?;
+
         for block in &node.blocks {
             block.accept(self)?;
         }
 
+        /* Bodies are checked after all types are known */
+        let mut checker = MatchChecker {
+            definitions: self,
+            module: None,
+        };
+        for block in &node.blocks {
+            block.accept(&mut checker)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_module(&mut self, node: &crate::utils::nodes::Module) -> anyhow::Result<()> {
+        let prev = self.enter_module(Some(node.name.clone()));
+        for block in &node.blocks {
+            block.accept(self)?;
+        }
+        self.enter_module(prev);
+
         Ok(())
     }
 
     fn visit_funcdef(&mut self, node: &crate::utils::nodes::FuncDef) -> anyhow::Result<()> {
-        self.add_func(&node.name, &node.args, &node.rettype)
+        /* External functions aren't namespaced, several modules can declare the same one */
+        if let Some(modules) = self.externs.get_mut(&node.name) {
+            if !modules.insert(self.module.clone()) {
+                anyhow::bail!("Redefenition of func {}", node.name);
+            }
+            let prev = self.functions.remove(&node.name).unwrap();
+            self.add_func(&node.name, &node.args, &node.rettype, node.is_vararg)?;
+            if self.functions[&node.name] != prev {
+                anyhow::bail!("Conflicting declarations of func {}", node.name);
+            }
+            return Ok(());
+        }
+
+        self.add_func(&node.name, &node.args, &node.rettype, node.is_vararg)?;
+        self.externs
+            .insert(node.name.clone(), HashSet::from([self.module.clone()]));
+        Ok(())
     }
 
     fn visit_funcimpl(&mut self, node: &crate::utils::nodes::FuncImpl) -> anyhow::Result<()> {
-        self.add_func(&node.name, &node.args, &node.rettype)
+        if node.is_vararg {
+            anyhow::bail!(
+                "Variadic function {} can only be declared, not implemented",
+                node.name
+            );
+        }
+        let name = self.define_item(&node.name, node.is_pub);
+        if !node.type_params.is_empty() {
+            return self.add_generic_func(&name, node);
+        }
+        self.add_func(&name, &node.args, &node.rettype, false)
+    }
+
+    fn visit_global(&mut self, node: &crate::utils::nodes::Global) -> anyhow::Result<()> {
+        let name = self.define_item(&node.name, node.is_pub);
+        self.globals.insert(name);
+        Ok(())
+    }
+
+    fn visit_impl(&mut self, node: &crate::utils::nodes::Impl) -> anyhow::Result<()> {
+        let type_name = self.type_name(&node.type_name)?;
+        match self.types.get(&type_name).map(|ty| ty.dealias()) {
+            Some(Type::Custom(_)) => {}
+            Some(_) => anyhow::bail!("Can't impl methods for primitive type {}", node.type_name),
+            None => anyhow::bail!("Impl for unknown type {}", node.type_name),
+        }
+
+        for method in &node.methods {
+            let name = method_name(&type_name, &method.name);
+            if method.is_vararg {
+                anyhow::bail!(
+                    "Variadic function {} can only be declared, not implemented",
+                    name
+                );
+            }
+            if method.is_pub {
+                self.public.insert(name.clone());
+            }
+            self.add_func(&name, &method.args, &method.rettype, false)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_structdef(&mut self, node: &crate::utils::nodes::StructDef) -> anyhow::Result<()> {
+        if !node.type_params.is_empty() {
+            return Ok(());
+        }
+
+        let ty = self.types[&self.item_name(&node.name)].clone();
+        let Type::Custom(custom) = ty.as_ref() else {
+            unreachable!("declared as custom type");
+        };
+        custom.define(&node.fields, self)
+    }
+
+    fn visit_enumdef(&mut self, node: &crate::utils::nodes::EnumDef) -> anyhow::Result<()> {
+        let ty = self.types[&self.item_name(&node.name)].clone();
+        let Type::Enum(enum_type) = ty.as_ref() else {
+            unreachable!("declared as enum type");
+        };
+        enum_type.define(node, self)
+    }
+}
+
+/// `type` or `newtype` item, which target isn't resolved yet
+struct PendingAlias {
+    module: Option<String>,
+    name: String,
+    target: TypeName,
+    is_newtype: bool,
+}
+
+/// First pass over program: registers modules & imports, declares structs & enums
+/// without fields and resolves type aliases
+struct TypeDeclarer<'a> {
+    definitions: &'a mut ProgramDefinitions,
+    /// Aliases are resolved after all structs & enums are declared
+    aliases: Vec<PendingAlias>,
+}
+
+impl TypeDeclarer<'_> {
+    fn declare_name(&mut self, name: &str, is_pub: bool) -> anyhow::Result<String> {
+        let defs = &mut *self.definitions;
+        let name = defs.define_item(name, is_pub);
+        if defs.types.contains_key(&name)
+            || defs.generic_structs.contains_key(&name)
+            || self.aliases.iter().any(|alias| alias.name == name)
+        {
+            anyhow::bail!("Redefinition of {} type", name);
+        }
+        Ok(name)
+    }
+
+    /// Aliases can refer to each other in any order, so they are resolved until
+    /// no progress is made, then the first unresolved one is reported
+    fn resolve_aliases(mut self) -> anyhow::Result<()> {
+        loop {
+            let count = self.aliases.len();
+            let mut pending = vec![];
+            for alias in std::mem::take(&mut self.aliases) {
+                if !self.resolve_alias(&alias)? {
+                    pending.push(alias);
+                }
+            }
+
+            if pending.is_empty() {
+                return Ok(());
+            }
+            if pending.len() == count {
+                let alias = &pending[0];
+                anyhow::bail!(
+                    "Unknown type {} in definition of {}",
+                    alias.target,
+                    alias.name
+                );
+            }
+            self.aliases = pending;
+        }
+    }
+
+    /// Defines alias, if its target is already known
+    fn resolve_alias(&mut self, alias: &PendingAlias) -> anyhow::Result<bool> {
+        let defs = &mut *self.definitions;
+        let prev = defs.enter_module(alias.module.clone());
+        let target = defs.resolve_type(&alias.target);
+        defs.enter_module(prev);
+        let Some(target) = target? else {
+            return Ok(false);
+        };
+
+        let ty = if alias.is_newtype {
+            Rc::new(Type::Newtype(alias.name.clone(), target))
+        } else {
+            Rc::new(Type::Alias(alias.name.clone(), target))
+        };
+        defs.types.insert(alias.name.clone(), ty);
+
+        Ok(true)
+    }
+}
+
+impl Visitor for TypeDeclarer<'_> {
+    fn visit_import(&mut self, node: &crate::utils::nodes::Import) -> anyhow::Result<()> {
+        let defs = &mut *self.definitions;
+        defs.imports
+            .entry(defs.module.clone())
+            .or_default()
+            .insert(node.module_name());
+        Ok(())
+    }
+
+    fn visit_module(&mut self, node: &crate::utils::nodes::Module) -> anyhow::Result<()> {
+        let defs = &mut *self.definitions;
+        defs.imports.insert(Some(node.name.clone()), HashSet::new());
+
+        let prev = defs.enter_module(Some(node.name.clone()));
+        for block in &node.blocks {
+            block.accept(self)?;
+        }
+        self.definitions.enter_module(prev);
+
+        Ok(())
+    }
+
+    fn visit_typealias(&mut self, node: &crate::utils::nodes::TypeAlias) -> anyhow::Result<()> {
+        let name = self.declare_name(&node.name, node.is_pub)?;
+        self.aliases.push(PendingAlias {
+            module: self.definitions.module.clone(),
+            name,
+            target: node.target.clone(),
+            is_newtype: node.is_newtype,
+        });
+        Ok(())
     }
 
     fn visit_structdef(&mut self, node: &crate::utils::nodes::StructDef) -> anyhow::Result<()> {
-        if self.types.contains_key(&node.name) {
-            anyhow::bail!("Redefinition of {} type", node.name);
+        let name = self.declare_name(&node.name, node.is_pub)?;
+        let defs = &mut *self.definitions;
+
+        if !node.type_params.is_empty() {
+            let generic = GenericStruct {
+                params: node.type_params.clone(),
+                fields: node.fields.clone(),
+            };
+            defs.generic_structs.insert(name, generic);
+            return Ok(());
+        }
+
+        /* Fields are resolved in the second pass, so they can point to any type */
+        let ty = Rc::new(Type::Custom(CustomType::declare(&name)));
+        defs.types.insert(name, ty);
+        Ok(())
+    }
+
+    fn visit_enumdef(&mut self, node: &crate::utils::nodes::EnumDef) -> anyhow::Result<()> {
+        let name = self.declare_name(&node.name, node.is_pub)?;
+
+        /* Variants are resolved in the second pass, so payload can point to any type */
+        let ty = Rc::new(Type::Enum(EnumType::declare(&name)));
+        self.definitions.types.insert(name, ty);
+        Ok(())
+    }
+}
+
+/// Walks function bodies and checks `match` statements: patterns should refer to
+/// existing variants of one enum and cover all its variants unless `_` arm is present
+struct MatchChecker<'a> {
+    definitions: &'a ProgramDefinitions,
+    /// Module of checked bodies, enum names in patterns are resolved in it
+    module: Option<String>,
+}
+
+impl MatchChecker<'_> {
+    fn visit_body(&mut self, body: &Vec<Box<dyn Statement>>) -> anyhow::Result<()> {
+        for st in body {
+            st.accept(self)?;
+        }
+        Ok(())
+    }
+}
+
+impl Visitor for MatchChecker<'_> {
+    fn visit_module(&mut self, node: &crate::utils::nodes::Module) -> anyhow::Result<()> {
+        let prev = self.module.replace(node.name.clone());
+        self.visit_body(&node.blocks)?;
+        self.module = prev;
+        Ok(())
+    }
+
+    fn visit_funcimpl(&mut self, node: &crate::utils::nodes::FuncImpl) -> anyhow::Result<()> {
+        self.visit_body(&node.body)
+    }
+
+    fn visit_impl(&mut self, node: &crate::utils::nodes::Impl) -> anyhow::Result<()> {
+        for method in &node.methods {
+            method.accept(self)?;
+        }
+        Ok(())
+    }
+
+    fn visit_if(&mut self, node: &crate::utils::nodes::If) -> anyhow::Result<()> {
+        self.visit_body(&node.true_body)?;
+        if let Some(else_body) = &node.else_body {
+            self.visit_body(else_body)?;
+        }
+        Ok(())
+    }
+
+    fn visit_while(&mut self, node: &crate::utils::nodes::While) -> anyhow::Result<()> {
+        self.visit_body(&node.body)
+    }
+
+    fn visit_for(&mut self, node: &crate::utils::nodes::For) -> anyhow::Result<()> {
+        self.visit_body(&node.body)
+    }
+
+    fn visit_infiniteloop(
+        &mut self,
+        node: &crate::utils::nodes::InfiniteLoop,
+    ) -> anyhow::Result<()> {
+        self.visit_body(&node.body)
+    }
+
+    fn visit_dowhile(&mut self, node: &crate::utils::nodes::DoWhile) -> anyhow::Result<()> {
+        self.visit_body(&node.body)
+    }
+
+    fn visit_defer(&mut self, node: &crate::utils::nodes::Defer) -> anyhow::Result<()> {
+        node.stmt.accept(self)
+    }
+
+    fn visit_switch(&mut self, node: &crate::utils::nodes::Switch) -> anyhow::Result<()> {
+        for case in &node.cases {
+            self.visit_body(&case.body)?;
+        }
+        if let Some(default) = &node.default {
+            self.visit_body(default)?;
+        }
+        Ok(())
+    }
+
+    fn visit_match(&mut self, node: &Match) -> anyhow::Result<()> {
+        let mut enum_type: Option<&EnumType> = None;
+        let mut covered: Vec<&str> = vec![];
+        let mut has_wildcard = false;
+
+        for arm in &node.arms {
+            if has_wildcard {
+                anyhow::bail!("Unreachable match arm after `_`");
+            }
+
+            let Some(pattern) = &arm.pattern else {
+                has_wildcard = true;
+                continue;
+            };
+
+            let enum_name = self
+                .definitions
+                .type_name_in(self.module.as_deref(), &pattern.enum_name)?;
+            let ty = match self.definitions.types.get(&enum_name).map(|t| t.dealias()) {
+                Some(Type::Enum(ty)) => ty,
+                Some(_) => anyhow::bail!("Type {} in match pattern is not enum", pattern.enum_name),
+                None => anyhow::bail!("Unknown enum {} in match pattern", pattern.enum_name),
+            };
+            if let Some(prev) = enum_type
+                && prev != ty
+            {
+                anyhow::bail!("Match arms use different enums {} and {}", prev, ty);
+            }
+            enum_type = Some(ty);
+
+            let Some(variant) = ty.variant(&pattern.variant) else {
+                anyhow::bail!("Enum {} has no variant {}", ty, pattern.variant);
+            };
+            if variant.fields.len() != pattern.bindings.len() {
+                anyhow::bail!(
+                    "Variant {}::{} has {} fields, but pattern binds {}",
+                    ty,
+                    variant.name,
+                    variant.fields.len(),
+                    pattern.bindings.len()
+                );
+            }
+            if covered.contains(&variant.name.as_str()) {
+                anyhow::bail!("Variant {}::{} is matched twice", ty, variant.name);
+            }
+            covered.push(&variant.name);
+
+            self.visit_body(&arm.body)?;
+        }
+
+        if let Some(last) = node.arms.last()
+            && last.pattern.is_none()
+        {
+            self.visit_body(&last.body)?;
+        }
+
+        if let Some(ty) = enum_type
+            && !has_wildcard
+        {
+            let missing: Vec<_> = ty
+                .variants()
+                .iter()
+                .filter(|v| !covered.contains(&v.name.as_str()))
+                .map(|v| v.name.as_str())
+                .collect();
+            if !missing.is_empty() {
+                anyhow::bail!(
+                    "Match on {} is not exhaustive, missing variants: {}",
+                    ty,
+                    missing.join(", ")
+                );
+            }
         }
 
-        let ty = Rc::new(Type::Custom(CustomType::from_def(node, self)?));
-        self.types.insert(node.name.clone(), ty);
         Ok(())
     }
 }
@@ -346,13 +1603,27 @@ mod tests {
     }
 
     fn int(size: u8) -> Type {
-        Type::Int(IntType { bitwidth: size })
+        Type::Int(IntType {
+            bitwidth: size,
+            signed: true,
+        })
+    }
+
+    fn uint(size: u8) -> Type {
+        Type::Int(IntType {
+            bitwidth: size,
+            signed: false,
+        })
     }
 
     fn float(size: u8) -> Type {
         Type::Float(FloatType { bitwidth: size })
     }
 
+    fn ptr(pointee: Type) -> Type {
+        Type::Pointer(Rc::new(pointee))
+    }
+
     #[test]
     fn common_type() {
         // All ints
@@ -360,10 +1631,23 @@ mod tests {
         common_type_test!(Ok, int(1), int(8), int(8));
         common_type_test!(Ok, int(8), int(1), int(8));
 
-        // With float
-        common_type_test!(Ok, int(1), float(1), float(1));
-        common_type_test!(Ok, int(8), float(1), float(1));
-        common_type_test!(Ok, float(8), int(7), float(8));
+        // Mixed signedness, negative values can't become unsigned
+        common_type_test!(Ok, uint(8), uint(32), uint(32));
+        common_type_test!(Err, int(32), uint(32));
+        common_type_test!(Err, uint(32), int(32));
+        common_type_test!(Err, int(8), uint(32));
+        common_type_test!(Ok, int(64), uint(32), int(64));
+        common_type_test!(Ok, uint(32), int(64), int(64));
+        common_type_test!(Ok, uint(1), int(8), int(8));
+
+        // With float, it should represent every value of int
+        common_type_test!(Ok, int(1), float(32), float(32));
+        common_type_test!(Ok, int(16), float(32), float(32));
+        common_type_test!(Ok, float(64), int(32), float(64));
+        common_type_test!(Ok, float(32), float(64), float(64));
+        common_type_test!(Err, int(32), float(32));
+        common_type_test!(Err, int(64), float(64));
+        common_type_test!(Err, uint(64), float(32));
 
         // Can't negotiate with void
         common_type_test!(Err, int(1), Type::Void());
@@ -379,5 +1663,13 @@ mod tests {
         common_type_test!(Err, float(1), Type::Custom(CustomType::test_sample()));
         common_type_test!(Err, int(1), Type::Custom(CustomType::test_sample()));
         common_type_test!(Err, Type::Void(), Type::Custom(CustomType::test_sample()));
+
+        // Pointers are compatible only with same pointers or *void
+        common_type_test!(Ok, ptr(int(8)), ptr(int(8)), ptr(int(8)));
+        common_type_test!(Ok, ptr(int(8)), ptr(Type::Void()), ptr(int(8)));
+        common_type_test!(Ok, ptr(Type::Void()), ptr(int(8)), ptr(int(8)));
+        common_type_test!(Err, ptr(int(8)), ptr(int(16)));
+        common_type_test!(Err, ptr(int(8)), int(8));
+        common_type_test!(Err, float(8), ptr(float(8)));
     }
 }
@@ -2,6 +2,49 @@
 
 mod macros {
     macro_rules! check_codegen {
+        /* Program of several files, the first one is compiled */
+        (Files [$(($file: expr, $content: expr)),*], $($rest:tt)*) => {{
+            check_codegen!(InternalFiles [$(($file, $content)),*], $($rest)*)
+        }};
+
+        (InternalFiles $files: tt, CompilationError $err_regex: expr) => {{
+            use crate::codegen::{CodegenContext, JitEngine};
+            use crate::ast::Statement;
+            use regex::Regex;
+
+            let ee = check_codegen!(InternalLoad, $files);
+            assert!(ee.is_err());
+
+            let err = format!("{:?}", ee.err().unwrap());
+            println!("Error: {}", err);
+            assert!(Regex::new($err_regex).unwrap().is_match(&err));
+        }};
+
+        (InternalFiles $files: tt, $([$($args:tt)*]),*) => {{
+            use crate::codegen::{CodegenContext, JitEngine};
+            use crate::ast::Statement;
+
+            let ee = check_codegen!(InternalLoad, $files).unwrap();
+
+            // Codegen each rule
+            $( check_codegen!(ee $($args)*); )*
+        }};
+
+        (InternalLoad, [$(($file: expr, $content: expr)),*]) => {(|| -> anyhow::Result<JitEngine> {
+            let files = std::collections::HashMap::from([$(($file, $content)),*]);
+            let root = [$($file),*][0];
+            let prog = crate::loader::load_with(std::path::Path::new(root), |path| {
+                match files.get(path.to_str().unwrap()) {
+                    Some(content) => Ok(content.to_string()),
+                    None => anyhow::bail!("No such file"),
+                }
+            })?;
+            let mut cxt = CodegenContext::prepare(&prog)?;
+
+            prog.codegen(&mut cxt)?;
+            Ok(JitEngine::from_codegen_cxt(cxt))
+        })()};
+
         ($code: expr, $([$($args:tt)*]),*) => {{
             use crate::codegen::{CodegenContext, JitEngine};
             use crate::ast::Statement;
@@ -27,7 +70,7 @@ mod macros {
 
         /* Hacky solution via called lambda */
         (InternalCodegen, $code: expr) => {(|| -> anyhow::Result<JitEngine> {
-            let prog = crate::grammar::ProgramParser::new().parse($code)?;
+            let prog = crate::grammar::ProgramParser::new().parse(crate::lexer::Lexer::new($code))?;
             let mut cxt = CodegenContext::prepare(&prog)?;
 
             prog.codegen(&mut cxt)?;
@@ -58,3 +101,47 @@ mod simple;
 mod hacks;
 
 mod compilation_errors;
+
+mod pointers;
+
+mod arrays;
+
+mod strings;
+
+mod varargs;
+
+mod logic;
+
+mod operators;
+
+mod unsigned;
+
+mod casts;
+
+mod loops;
+
+mod branches;
+
+mod structs;
+
+mod methods;
+
+mod globals;
+
+mod enums;
+
+mod generics;
+
+mod func_pointers;
+
+mod modules;
+
+mod type_aliases;
+
+mod tuples;
+
+mod literals;
+
+mod heap;
+
+mod defer;
@@ -0,0 +1,125 @@
+use crate::codegen::tests::macros::check_codegen;
+
+#[test]
+fn stack_array() {
+    check_codegen!(
+        "
+        fn test(n: i64) -> i64 {
+            let arr = [0; 8];
+            for (let i = 0; i < 8; i = i + 1) {
+                arr[i] = i * n;
+            }
+
+            let sum = 0;
+            for (let i = 0; i < 8; i = i + 1) {
+                sum = sum + arr[i];
+            }
+            return sum;
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(0) == 0],
+        [assert test(1) == 28],
+        [assert test(2) == 56]
+    )
+}
+
+#[test]
+fn array_literal() {
+    check_codegen!(
+        "
+        fn test(i: i64) -> i64 {
+            let arr = [10, 20, 30, 40];
+            return arr[i];
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(0) == 10],
+        [assert test(3) == 40]
+    )
+}
+
+#[test]
+fn array_cast() {
+    check_codegen!(
+        "
+        fn test() -> f64 {
+            let arr: [f64; 3] = [1, 2, 3];
+            let mixed = [1, 2.5];
+            return arr[0] + arr[1] + arr[2] + mixed[1];
+        }
+        ",
+        [test as fn() -> f64],
+        [assert test() == 8.5]
+    )
+}
+
+#[test]
+fn nested_arrays() {
+    check_codegen!(
+        "
+        fn test(i: i64, j: i64) -> i64 {
+            let m: [[i64; 3]; 3] = [[0; 3]; 3];
+            m[1][2] = 12;
+            m[2][1] = 21;
+            return m[i][j];
+        }
+        ",
+        [test as fn(i64, i64) -> i64],
+        [assert test(1, 2) == 12],
+        [assert test(2, 1) == 21],
+        [assert test(0, 0) == 0]
+    )
+}
+
+#[test]
+fn struct_array_field() {
+    check_codegen!(
+        "
+        struct Point {
+            x: i64,
+            y: i64
+        }
+
+        struct Poly {
+            items: [Point; 4],
+            len: i64
+        }
+
+        fn test(n: i64) -> i64 {
+            let p = Poly {};
+            p.items[3].x = n;
+            p.items[3].y = 2 * n;
+            p.items[0].x = 1;
+
+            let ptr = &p;
+            return ptr.items[3].x + ptr.items[3].y + p.items[0].x + p.items[1].x;
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(1) == 4],
+        [assert test(10) == 31]
+    )
+}
+
+#[test]
+fn pointer_indexing() {
+    check_codegen!(
+        "
+        fn sum(p: *i64, n: i64) -> i64 {
+            let accum = 0;
+            for (let i = 0; i < n; i = i + 1) {
+                accum = accum + p[i];
+            }
+            return accum;
+        }
+
+        fn test() -> i64 {
+            let arr = [1, 2, 3, 4];
+            return sum(&arr[0], 4) + sum(&arr[2], 2);
+        }
+        ",
+        [test as fn() -> i64],
+        [assert test() == 17]
+    )
+}
@@ -0,0 +1,111 @@
+use crate::codegen::tests::macros::check_codegen;
+
+#[test]
+fn else_if_chain() {
+    check_codegen!(
+        "
+        fn sign(a: i64) -> i32 {
+            if (a < 0) {
+                return -1;
+            } else if (a == 0) {
+                return 0;
+            } else {
+                return 1;
+            }
+        }
+
+        fn grade(a: i64) -> i32 {
+            let res: i32 = 0;
+            if (a >= 90) {
+                res = 5;
+            } else if (a >= 75) {
+                res = 4;
+            } else if (a >= 50) {
+                res = 3;
+            }
+            return res;
+        }
+        ",
+        [sign as fn(i64) -> i32],
+        [grade as fn(i64) -> i32],
+        [assert sign(-5) == -1],
+        [assert sign(0) == 0],
+        [assert sign(7) == 1],
+        [assert grade(95) == 5],
+        [assert grade(80) == 4],
+        [assert grade(50) == 3],
+        [assert grade(10) == 0]
+    )
+}
+
+#[test]
+fn switch() {
+    check_codegen!(
+        "
+        fn classify(a: i32) -> i32 {
+            let res: i32 = 0;
+            switch (a) {
+                case 1, 2: { res = 10; }
+                case -1: { res = 20; }
+                case 3: {
+                    let x: i32 = 30;
+                    res = x;
+                }
+                default: { res = -1; }
+            }
+            return res;
+        }
+
+        fn no_default(a: u8) -> i32 {
+            switch (a) {
+                case 255: { return 1; }
+            }
+            return 0;
+        }
+        ",
+        [classify as fn(i32) -> i32],
+        [no_default as fn(u8) -> i32],
+        [assert classify(1) == 10],
+        [assert classify(2) == 10],
+        [assert classify(-1) == 20],
+        [assert classify(3) == 30],
+        [assert classify(4) == -1],
+        [assert no_default(255) == 1],
+        [assert no_default(0) == 0]
+    )
+}
+
+#[test]
+fn switch_state_machine() {
+    // Count words separated by spaces
+    check_codegen!(
+        "
+        fn words(s: str) -> i64 {
+            let state: i32 = 0;
+            let count: i64 = 0;
+            let i: i64 = 0;
+            while (s[i] != 0) {
+                switch (state) {
+                    case 0: {
+                        if (s[i] != 32) {
+                            state = 1;
+                            count = count + 1;
+                        }
+                    }
+                    case 1: {
+                        if (s[i] == 32) {
+                            state = 0;
+                        }
+                    }
+                }
+                i = i + 1;
+            }
+            return count;
+        }
+
+        fn test() -> i64 { return words(\"  hello nya   world \"); }
+        ",
+        [test as fn() -> i64],
+        [assert test() == 3]
+    )
+}
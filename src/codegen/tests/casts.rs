@@ -0,0 +1,94 @@
+use crate::codegen::tests::macros::check_codegen;
+
+#[test]
+fn narrowing() {
+    check_codegen!(
+        "
+        fn trunc(a: i64) -> i8 { return a as i8; }
+        fn to_unsigned(a: i32) -> u8 { return a as u8; }
+        fn chained(a: i64) -> i64 { return a as u8 as i64; }
+        ",
+        [trunc as fn(i64) -> i8],
+        [to_unsigned as fn(i32) -> u8],
+        [chained as fn(i64) -> i64],
+        [assert trunc(300) == 44],
+        [assert trunc(-1) == -1],
+        [assert to_unsigned(-1) == 255],
+        [assert chained(257) == 1]
+    )
+}
+
+#[test]
+fn float_int() {
+    check_codegen!(
+        "
+        fn to_int(a: f64) -> i32 { return a as i32; }
+        fn to_f32(a: f64) -> f32 { return a as f32; }
+        fn ratio(a: i64, b: i64) -> f64 { return a as f64 / b as f64; }
+        ",
+        [to_int as fn(f64) -> i32],
+        [to_f32 as fn(f64) -> f32],
+        [ratio as fn(i64, i64) -> f64],
+        [assert to_int(2.9) == 2],
+        [assert to_int(-2.9) == -2],
+        [assert to_f32(0.5) == 0.5],
+        [assert ratio(1, 4) == 0.25]
+    )
+}
+
+#[test]
+fn pointers() {
+    check_codegen!(
+        "
+        fn roundtrip(a: i64) -> i64 {
+            let p = &a;
+            let addr = p as u64;
+            let q = addr as *i64;
+            return *q;
+        }
+
+        fn reinterpret(a: i64) -> i8 {
+            let p = &a as *i8;
+            return *p;
+        }
+        ",
+        [roundtrip as fn(i64) -> i64],
+        [reinterpret as fn(i64) -> i8],
+        [assert roundtrip(42) == 42],
+        [assert reinterpret(0x0102) == 2]
+    )
+}
+
+#[test]
+fn to_bool() {
+    check_codegen!(
+        "
+        fn test(a: f64) -> i32 {
+            if (a as bool) {
+                return 1;
+            }
+            return 0;
+        }
+        ",
+        [test as fn(f64) -> i32],
+        [assert test(0.0) == 0],
+        [assert test(0.5) == 1]
+    )
+}
+
+#[test]
+fn implicit_constants() {
+    // Constants which fit into the target type are converted implicitly
+    check_codegen!(
+        "
+        fn test() -> u8 {
+            let a: u8 = 255;
+            let b: i8 = -128;
+            let c: f32 = 1.5;
+            return a + 1;
+        }
+        ",
+        [test as fn() -> u8],
+        [assert test() == 0]
+    )
+}
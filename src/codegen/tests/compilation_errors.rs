@@ -47,7 +47,6 @@ fn double_type_def() {
 fn test_type_order() {
     check_codegen!("
         struct B {a: A}
-        struct A {a: i64}
 
         fn test() -> i32 {
             let b = B {};
@@ -68,3 +67,1120 @@ fn test_unknown_var() {
         CompilationError "Unknown variable a"
     );
 }
+
+#[test]
+fn test_pointer_errors() {
+    check_codegen!(
+        "
+        fn test(x: i32) -> i32 { return *x; }
+        ",
+        CompilationError "Dereference of non-pointer type i32"
+    );
+
+    check_codegen!(
+        "
+        fn test() -> i32 { let p = &1; return 0; }
+        ",
+        CompilationError "is not addressable"
+    );
+
+    check_codegen!(
+        "
+        fn test(x: i32) -> i32 { let p: *i64 = &x; return 0; }
+        ",
+        CompilationError "Cast from \\*i32 to \\*i64 pointer is forbidden"
+    );
+
+    check_codegen!(
+        "
+        fn test() -> i32 { let p = null; return *p; }
+        ",
+        CompilationError "Dereference of void pointer"
+    );
+
+    check_codegen!(
+        "
+        fn test(p: *i32) -> i32 { return p < null; }
+        ",
+        CompilationError "Pointers can only be compared with == and !="
+    );
+}
+
+#[test]
+fn test_recursive_type() {
+    check_codegen!(
+        "
+        struct A { a: A }
+        ",
+        CompilationError "Recursive types, loop begins at A"
+    );
+
+    check_codegen!(
+        "
+        struct A { t: (A, i64) }
+        ",
+        CompilationError "Recursive types, loop begins at A"
+    );
+}
+
+#[test]
+fn test_array_errors() {
+    check_codegen!(
+        "
+        fn test() -> i64 { let a = [1, 2, 3]; return a[3]; }
+        ",
+        CompilationError "Index 3 is out of bounds of variable \\(a\\) with length 3"
+    );
+
+    check_codegen!(
+        "
+        fn test(x: i64) -> i64 { return x[0]; }
+        ",
+        CompilationError "Index subscription of variable \\(x\\) with non-array type \\(i64\\)"
+    );
+
+    check_codegen!(
+        "
+        fn test() -> i64 { let a = [1, 2]; return a[1.5]; }
+        ",
+        CompilationError "Index of variable \\(a\\) should be integer"
+    );
+
+    check_codegen!(
+        "
+        fn test() -> i64 { let a: [i64; 2] = [1, 2, 3]; return 0; }
+        ",
+        CompilationError "Cast from \\[i64; 3\\] to \\[i64; 2\\] is forbidden"
+    );
+}
+
+#[test]
+fn test_string_errors() {
+    check_codegen!(
+        r#"
+        fn test() -> str { return "bad \q"; }
+        "#,
+        CompilationError "Invalid escape sequence \\\\q"
+    );
+
+    check_codegen!(
+        r#"
+        fn test() -> i64 { let s: *i64 = "str"; return 0; }
+        "#,
+        CompilationError "Cast from \\*i8 to \\*i64 pointer is forbidden"
+    );
+}
+
+#[test]
+fn test_call_arity() {
+    check_codegen!(
+        "
+        fn id(a: i64) -> i64 { return a; }
+        fn test() -> i64 { return id(1, 2); }
+        ",
+        CompilationError "Function id expects 1 arguments, but 2 were given"
+    );
+
+    check_codegen!(
+        "
+        fn printf(fmt: str, ...) -> i32;
+        fn test() -> i32 { return printf(); }
+        ",
+        CompilationError "Function printf expects at least 1 arguments, but 0 were given"
+    );
+
+    check_codegen!(
+        "
+        struct A {}
+        fn printf(fmt: str, ...) -> i32;
+        fn test() -> i32 { return printf(\"%d\", A {}); }
+        ",
+        CompilationError "Value of type A can't be passed as variadic argument"
+    );
+}
+
+#[test]
+fn test_vararg_impl() {
+    check_codegen!(
+        "
+        fn sum(n: i64, ...) -> i64 { return n; }
+        ",
+        CompilationError "Variadic function sum can only be declared, not implemented"
+    );
+}
+
+#[test]
+fn test_bitwise_on_floats() {
+    check_codegen!(
+        "
+        fn test(a: f64) -> i64 { return a & 1; }
+        ",
+        CompilationError "Operator & can be applied only to integers, not f64"
+    );
+
+    check_codegen!(
+        "
+        fn test(a: f64) -> i64 { return 1 << a; }
+        ",
+        CompilationError "Operator << can be applied only to integers, not f64"
+    );
+
+    check_codegen!(
+        "
+        fn test(a: f64) -> f64 { return ~a; }
+        ",
+        CompilationError "Bitwise not on unsupported type f64"
+    );
+}
+
+#[test]
+fn test_implicit_narrowing() {
+    check_codegen!(
+        "
+        fn test(x: i64) -> i64 {
+            let a: u8 = x;
+            return a;
+        }
+        ",
+        CompilationError "Implicit conversion from i64 to u8 can lose data, use explicit `as` cast"
+    );
+
+    check_codegen!(
+        "
+        fn test(x: f64) -> i32 { return x; }
+        ",
+        CompilationError "Implicit conversion from f64 to i32 can lose data"
+    );
+
+    check_codegen!(
+        "
+        fn take(x: u32) -> u32 { return x; }
+        fn test(x: i32) -> u32 { return take(x); }
+        ",
+        CompilationError "Implicit conversion from i32 to u32 can lose data"
+    );
+
+    check_codegen!(
+        "
+        fn test() -> i8 {
+            let a: i8 = 300;
+            return a;
+        }
+        ",
+        CompilationError "Implicit conversion from i64 to i8 can lose data"
+    );
+
+    check_codegen!(
+        "
+        fn test(x: i64) -> f64 { return x; }
+        ",
+        CompilationError "Implicit conversion from i64 to f64 can lose data"
+    );
+
+    check_codegen!(
+        "
+        fn test() -> f32 {
+            let x: f32 = 1e300;
+            return x;
+        }
+        ",
+        CompilationError "Implicit conversion from f64 to f32 can lose data"
+    );
+
+    check_codegen!(
+        "
+        fn test(p: *i64) -> bool { return p; }
+        ",
+        CompilationError "Cast from \\*i64 to bool is forbidden"
+    );
+}
+
+#[test]
+fn test_mixed_operands() {
+    check_codegen!(
+        "
+        fn test(a: i32, b: u32) -> bool { return a < b; }
+        ",
+        CompilationError "Can't find common type for i32 and u32, use explicit `as` cast"
+    );
+
+    check_codegen!(
+        "
+        fn test(a: i64, b: f64) -> f64 { return a * b; }
+        ",
+        CompilationError "Can't find common type for i64 and f64"
+    );
+
+    check_codegen!(
+        "
+        fn test(a: u64) -> i64 {
+            let arr = [a, -1];
+            return arr[1];
+        }
+        ",
+        CompilationError "Can't find common type for u64 and i64"
+    );
+}
+
+#[test]
+fn test_forbidden_cast() {
+    check_codegen!(
+        "
+        struct A {}
+        fn test() -> i64 { return A {} as i64; }
+        ",
+        CompilationError "Cast from A to i64 is forbidden"
+    );
+
+    check_codegen!(
+        "
+        fn test(x: f64) -> i64 { return x as Unknown; }
+        ",
+        CompilationError "Unknown type Unknown in `as` cast"
+    );
+}
+
+#[test]
+fn test_loop_control_outside_loop() {
+    check_codegen!(
+        "
+        fn test() -> i64 {
+            break;
+            return 0;
+        }
+        ",
+        CompilationError "`break` outside of a loop"
+    );
+
+    check_codegen!(
+        "
+        fn test(x: i64) -> i64 {
+            if (x) {
+                continue;
+            }
+            return 0;
+        }
+        ",
+        CompilationError "`continue` outside of a loop"
+    );
+
+    check_codegen!(
+        "
+        fn test() -> i64 {
+            'a: while (true) {
+                while (true) {
+                    break 'b;
+                }
+            }
+            return 0;
+        }
+        ",
+        CompilationError "Unknown loop label 'b in `break`"
+    );
+
+    check_codegen!(
+        "
+        fn test() -> i64 {
+            'a: while (true) {
+                'a: while (true) {
+                    break 'a;
+                }
+            }
+            return 0;
+        }
+        ",
+        CompilationError "Loop label 'a is already used by enclosing loop"
+    );
+}
+
+#[test]
+fn test_switch_errors() {
+    check_codegen!(
+        "
+        fn test(a: f64) -> i64 {
+            switch (a) { case 1: {} }
+            return 0;
+        }
+        ",
+        CompilationError "Switch on non-integer type f64"
+    );
+
+    check_codegen!(
+        "
+        fn test(a: i64, b: i64) -> i64 {
+            switch (a) { case b: {} }
+            return 0;
+        }
+        ",
+        CompilationError "Case value must be an integer constant"
+    );
+
+    check_codegen!(
+        "
+        fn test(a: i64) -> i64 {
+            switch (a) { case 1, 2: {} case 2: {} }
+            return 0;
+        }
+        ",
+        CompilationError "Duplicate case value 2 in switch"
+    );
+
+    check_codegen!(
+        "
+        fn test(a: u8) -> i64 {
+            switch (a) { case 256: {} }
+            return 0;
+        }
+        ",
+        CompilationError "Implicit conversion from i64 to u8 can lose data"
+    );
+}
+
+#[test]
+fn test_struct_literal_errors() {
+    check_codegen!(
+        "
+        struct Point { x: i64, y: f64 }
+        fn test() -> i64 {
+            let p = Point { z: 1 };
+            return 0;
+        }
+        ",
+        CompilationError "Type Point has no field z"
+    );
+
+    check_codegen!(
+        "
+        struct Point { x: i64, y: f64 }
+        fn test() -> i64 {
+            let p = Point { x: 1, x: 2 };
+            return 0;
+        }
+        ",
+        CompilationError "Field x of Point is initialized twice"
+    );
+
+    check_codegen!(
+        "
+        struct Point { x: u8, y: f64 }
+        fn test(v: i64) -> i64 {
+            let p = Point { x: v };
+            return 0;
+        }
+        ",
+        CompilationError "Implicit conversion from i64 to u8 can lose data"
+    );
+
+    check_codegen!(
+        "
+        struct A { a: i64 }
+        struct B { a: A }
+        fn test() -> i64 {
+            let p = B { a: 1 };
+            return 0;
+        }
+        ",
+        CompilationError "Cast from i64 to A is forbidden"
+    );
+}
+
+#[test]
+fn test_method_errors() {
+    check_codegen!(
+        "
+        struct A { v: i64 }
+        fn test() -> i64 {
+            let a = A {};
+            return a.missing();
+        }
+        ",
+        CompilationError "Type A has no method missing"
+    );
+
+    check_codegen!(
+        "
+        fn test(x: i64) -> i64 { return x.len(); }
+        ",
+        CompilationError "Method len called on value of primitive type i64"
+    );
+
+    check_codegen!(
+        "
+        impl Missing { fn get(self) {} }
+        ",
+        CompilationError "Impl for unknown type Missing"
+    );
+
+    check_codegen!(
+        "
+        struct A { v: i64 }
+        impl A { fn get(self, k: i64) -> i64 { return self.v * k; } }
+        fn test() -> i64 {
+            let a = A {};
+            return a.get();
+        }
+        ",
+        CompilationError "Function A.get expects 1 arguments, but 0 were given"
+    );
+
+    check_codegen!(
+        "
+        struct A { v: i64 }
+        impl A { fn get(self) {} }
+        impl A { fn get(self) {} }
+        ",
+        CompilationError "Redefenition of func A.get"
+    );
+}
+
+#[test]
+fn test_global_errors() {
+    check_codegen!(
+        "
+        fn value() -> i64 { return 1; }
+        const A: i64 = value();
+        ",
+        CompilationError "Initializer of constant A is not a compile-time constant"
+    );
+
+    check_codegen!(
+        "
+        static A: i64 = 1;
+        static B: i64 = A + 1;
+        ",
+        CompilationError "Initializer of static B is not a compile-time constant"
+    );
+
+    check_codegen!(
+        "
+        struct P { x: i64, y: i64 }
+        static A: i64 = 1;
+        const B: P = P { x: 1, y: A };
+        ",
+        CompilationError "Initializer of constant B is not a compile-time constant"
+    );
+
+    check_codegen!(
+        "const A: i64 = 1 / 0;",
+        CompilationError "Division by zero"
+    );
+
+    check_codegen!(
+        "const A: i64 = 1 << 64;",
+        CompilationError "Shift by 64 is out of range of i64"
+    );
+
+    check_codegen!(
+        "
+        const A: i64 = 1;
+        fn test() { A = 2; }
+        ",
+        CompilationError "Constant A is read-only, it can't be assigned or addressed"
+    );
+
+    check_codegen!(
+        "
+        const A: i64 = 1;
+        fn test() -> *i64 { return &A; }
+        ",
+        CompilationError "Constant A is read-only"
+    );
+
+    check_codegen!(
+        "
+        const A: i8 = 1000;
+        ",
+        CompilationError "Implicit conversion from i64 to i8 can lose data"
+    );
+
+    check_codegen!(
+        "
+        static A: i64 = 1;
+        const A: i64 = 2;
+        ",
+        CompilationError "Redefinition of global A"
+    );
+
+    // Globals are defined before functions, so they can be used before definition
+    check_codegen!(
+        "
+        fn test() -> i64 { return A; }
+        const A: i64 = 1;
+        ",
+        [test as fn() -> i64],
+        [assert test() == 1]
+    );
+}
+
+#[test]
+fn test_enum_errors() {
+    check_codegen!(
+        "
+        enum Color { Red, Green = 0 }
+        ",
+        CompilationError "Variants Red and Green of Color have the same value 0"
+    );
+
+    check_codegen!(
+        "
+        enum Color { Red, Red }
+        ",
+        CompilationError "Variant Red of Color is defined twice"
+    );
+
+    check_codegen!(
+        "
+        enum Limit { Max = 9223372036854775808 }
+        ",
+        CompilationError "Literal 9223372036854775808 is out of range of i64"
+    );
+
+    check_codegen!(
+        "
+        enum Limit { Min = -9223372036854775809 }
+        ",
+        CompilationError "Literal -9223372036854775809 is out of range of i64"
+    );
+
+    check_codegen!(
+        "
+        enum Shape { Circle(f64), Rect(f64, f64), Empty }
+        fn test(s: Shape) {
+            match (s) {
+                Shape::Circle(r) => {}
+            }
+        }
+        ",
+        CompilationError "Match on Shape is not exhaustive, missing variants: Rect, Empty"
+    );
+
+    check_codegen!(
+        "
+        enum Shape { Circle(f64), Empty }
+        fn test(s: Shape) {
+            match (s) {
+                Shape::Circle(r, x) => {}
+                _ => {}
+            }
+        }
+        ",
+        CompilationError "Variant Shape::Circle has 1 fields, but pattern binds 2"
+    );
+
+    check_codegen!(
+        "
+        enum Shape { Circle(f64), Empty }
+        fn test(s: Shape) {
+            match (s) {
+                Shape::Empty => {}
+                Shape::Empty => {}
+                _ => {}
+            }
+        }
+        ",
+        CompilationError "Variant Shape::Empty is matched twice"
+    );
+
+    check_codegen!(
+        "
+        enum Shape { Circle(f64), Empty }
+        fn test(s: Shape) {
+            match (s) {
+                _ => {}
+                Shape::Empty => {}
+            }
+        }
+        ",
+        CompilationError "Unreachable match arm after `_`"
+    );
+
+    check_codegen!(
+        "
+        enum Shape { Circle(f64), Empty }
+        enum Color { Red }
+        fn test(s: Shape) {
+            match (s) {
+                Color::Red => {}
+            }
+        }
+        ",
+        CompilationError "Match on value of type Shape, but patterns are of enum Color"
+    );
+
+    check_codegen!(
+        "
+        enum Shape { Circle(f64), Empty }
+        fn test() {
+            let s = Shape::Circle(1.0, 2.0);
+        }
+        ",
+        CompilationError "Variant Shape::Circle expects 1 values, got 2"
+    );
+
+    check_codegen!(
+        "
+        enum Shape { Circle(f64), Empty }
+        fn test(a: Shape, b: Shape) -> bool { return a == b; }
+        ",
+        CompilationError "Can't compare Shape with Shape"
+    );
+
+    check_codegen!(
+        "
+        enum Shape { Circle(f64), Empty }
+        fn test(a: Shape) -> i64 { return a as i64; }
+        ",
+        CompilationError "Cast from Shape to i64 is forbidden"
+    );
+}
+
+#[test]
+fn test_generic_errors() {
+    check_codegen!(
+        "
+        fn max<T>(a: T, b: T) -> T { return a; }
+        fn test(a: i32, b: f64) -> f64 { return max(a, b); }
+        ",
+        CompilationError "Type parameter T of function max is both i32 and f64"
+    );
+
+    // Only literals are bound weakly, not every constant expression
+    check_codegen!(
+        "
+        fn max<T>(a: T, b: T) -> T { return a; }
+        fn test(a: i8) -> i8 { return max(1 + 2, a); }
+        ",
+        CompilationError "Type parameter T of function max is both i64 and i8"
+    );
+
+    check_codegen!(
+        "
+        fn zero<T>() -> T { return 0; }
+        fn test() -> i64 { return zero(); }
+        ",
+        CompilationError "Can't infer type parameter T of function zero"
+    );
+
+    check_codegen!(
+        "
+        fn max<T>(a: T, b: T) -> T { return a; }
+        fn test() -> i64 { return max::<i64, i64>(1, 2); }
+        ",
+        CompilationError "Function max expects 1 type arguments, but 2 were given"
+    );
+
+    check_codegen!(
+        "
+        fn id(a: i64) -> i64 { return a; }
+        fn test() -> i64 { return id::<i64>(1); }
+        ",
+        CompilationError "Function id is not generic"
+    );
+
+    check_codegen!(
+        "
+        struct Pair<T> { a: T, b: T }
+        fn test() { let p: Pair<i32, i32> = Pair::<i32> {}; }
+        ",
+        CompilationError "Type Pair expects 1 type arguments, but 2 were given"
+    );
+
+    check_codegen!(
+        "
+        struct Pair<T> { a: T, b: T }
+        fn test() { let p = Pair::<Unknown> {}; }
+        ",
+        CompilationError "Ctor for unknown type Pair<Unknown>"
+    );
+
+    check_codegen!(
+        "
+        fn add<T>(a: T, b: T) -> T { return a + b; }
+        struct S {}
+        fn test() { let s = add(S {}, S {}); }
+        ",
+        CompilationError "Arithmetic on incomptable types"
+    );
+}
+
+#[test]
+fn test_func_pointer_errors() {
+    check_codegen!(
+        "
+        fn inc(x: i64) -> i64 { return x + 1; }
+        fn test() { let f: fn(f64) -> i64 = inc; }
+        ",
+        CompilationError "Cast from fn\\(i64\\) -> i64 to function pointer fn\\(f64\\) -> i64 is forbidden"
+    );
+
+    check_codegen!(
+        "
+        fn inc(x: i64) -> i64 { return x + 1; }
+        fn test() -> i64 { let f = inc; return f(1, 2); }
+        ",
+        CompilationError "Function f expects 1 arguments, but 2 were given"
+    );
+
+    check_codegen!(
+        "
+        fn test() -> i64 { let x = 1; return x(2); }
+        ",
+        CompilationError "Can't call x of type i64, it's not a function"
+    );
+
+    check_codegen!(
+        "
+        fn printf(fmt: str, ...) -> i32;
+        fn test() { let p = printf; }
+        ",
+        CompilationError "Variadic function printf can't be used as value"
+    );
+
+    check_codegen!(
+        "
+        fn id<T>(x: T) -> T { return x; }
+        fn test() { let f = id; }
+        ",
+        CompilationError "Generic function id can't be used as value"
+    );
+
+    check_codegen!(
+        "
+        fn inc(x: i64) -> i64 { return x + 1; }
+        fn test() -> bool { let f = inc; return f < inc; }
+        ",
+        CompilationError "Pointers can only be compared with == and !="
+    );
+}
+
+#[test]
+fn test_module_errors() {
+    check_codegen!(
+        Files [
+            ("main.nya", "mod geometry; fn test() -> i64 { return geometry::helper(); }"),
+            ("geometry.nya", "fn helper() -> i64 { return 1; }")
+        ],
+        CompilationError "geometry::helper is private"
+    );
+
+    check_codegen!(
+        Files [
+            ("main.nya", "mod geometry; fn test() { let p = geometry::Point { x: 1 }; }"),
+            ("geometry.nya", "struct Point { x: i64 }")
+        ],
+        CompilationError "geometry::Point is private"
+    );
+
+    check_codegen!(
+        Files [
+            ("main.nya", "mod geometry; fn test(p: geometry::Point) -> i64 { return p.get(); }"),
+            ("geometry.nya", "pub struct Point { x: i64 } impl Point { fn get(self) -> i64 { return self.x; } }")
+        ],
+        CompilationError "Method geometry::Point.get is private"
+    );
+
+    check_codegen!(
+        Files [
+            ("main.nya", "mod b; mod a;"),
+            ("a.nya", "pub fn f() -> i64 { return b::g(); }"),
+            ("b.nya", "pub fn g() -> i64 { return 1; }")
+        ],
+        CompilationError "Module b is not imported"
+    );
+
+    check_codegen!(
+        Files [
+            ("main.nya", "fn helper() -> i64 { return 1; } mod geometry;"),
+            ("geometry.nya", "pub fn f() -> i64 { return helper(); }")
+        ],
+        CompilationError "helper is defined in root file, module geometry can't use it"
+    );
+
+    check_codegen!(
+        Files [
+            ("main.nya", "mod a;"),
+            ("a.nya", "import \"main.nya\";")
+        ],
+        CompilationError "Import cycle: main.nya -> a.nya -> main.nya"
+    );
+}
+
+#[test]
+fn test_type_alias_errors() {
+    check_codegen!(
+        "
+        newtype Meters = f64;
+        fn test(x: f64) -> Meters { return x; }
+        ",
+        CompilationError "Implicit conversion from f64 to Meters is forbidden, use explicit `as` cast"
+    );
+
+    check_codegen!(
+        "
+        newtype Meters = f64;
+        newtype Feet = f64;
+        fn test(a: Meters, b: Feet) -> f64 { return (a + b) as f64; }
+        ",
+        CompilationError "Can't find common type for Meters and Feet"
+    );
+
+    check_codegen!(
+        "
+        newtype Meters = f64;
+        fn test(a: Meters) -> f64 { return a; }
+        ",
+        CompilationError "Implicit conversion from Meters to f64 is forbidden"
+    );
+
+    check_codegen!(
+        "
+        struct Point { x: i64 }
+        newtype Pos = Point;
+        fn test(a: Pos, b: Pos) -> Pos { return a + b; }
+        ",
+        CompilationError "Arithmetic on incomptable types"
+    );
+
+    check_codegen!(
+        "
+        newtype Handle = *void;
+        fn test(a: Handle, b: Handle) -> bool { return a < b; }
+        ",
+        CompilationError "Pointers can only be compared with == and !="
+    );
+
+    check_codegen!(
+        "
+        type Meters = Length;
+        ",
+        CompilationError "Unknown type Length in definition of Meters"
+    );
+
+    check_codegen!(
+        "
+        type Id = i64;
+        struct Id { x: i64 }
+        ",
+        CompilationError "Redefinition of Id type"
+    );
+
+    check_codegen!(
+        "
+        type Meters = f64;
+        fn test(x: Meters) -> i32 { return x; }
+        ",
+        CompilationError "Implicit conversion from Meters to i32 can lose data"
+    );
+
+    check_codegen!(
+        "
+        type Meters = f64;
+        fn test(p: *Meters) -> *i64 { return p; }
+        ",
+        CompilationError "Cast from \\*Meters to \\*i64 pointer is forbidden"
+    );
+}
+
+#[test]
+fn test_compound_assignment_errors() {
+    check_codegen!(
+        "
+        fn test(b: i64) { let a: u32 = 1; a += b; }
+        ",
+        CompilationError "Implicit conversion from i64 to u32 can lose data"
+    );
+
+    check_codegen!(
+        "
+        fn test() { let a = 1.5; a <<= 1; }
+        ",
+        CompilationError "Operator << can be applied only to integers, not f64"
+    );
+
+    check_codegen!(
+        "
+        struct Point { x: i64 }
+        fn test() { let p = Point { x: 1 }; p++; }
+        ",
+        CompilationError "Arithmetic on incomptable types"
+    );
+}
+
+#[test]
+fn test_tuple_errors() {
+    check_codegen!(
+        "
+        fn divmod(a: i64, b: i64) -> (i64, i64) { return (a / b, a % b); }
+        fn test() { let (a, b, c) = divmod(1, 2); }
+        ",
+        CompilationError "Can't destructure tuple \\(i64, i64\\) into 3 variables"
+    );
+
+    check_codegen!(
+        "
+        fn test() { let (a, b) = 5; }
+        ",
+        CompilationError "Can't destructure value of non-tuple type i64"
+    );
+
+    check_codegen!(
+        "
+        fn test() -> i64 { let t = (1, 2); return t.2; }
+        ",
+        CompilationError "Tuple \\(i64, i64\\) of variable \\(t\\) has no element 2"
+    );
+
+    check_codegen!(
+        "
+        fn test() { let t: (i64, i64) = (1, 2.5); }
+        ",
+        CompilationError "Implicit conversion from f64 to i64 can lose data"
+    );
+
+    check_codegen!(
+        "
+        fn test() { let t = (1, 2) + (3, 4); }
+        ",
+        CompilationError "Arithmetic on incomptable types"
+    );
+}
+
+#[test]
+fn test_literal_errors() {
+    check_codegen!(
+        "
+        fn test() -> u8 { return 300u8; }
+        ",
+        CompilationError "Literal 300u8 is out of range of u8"
+    );
+
+    // Minimal value of signed type can be written only with minus
+    check_codegen!(
+        "
+        fn test() -> i8 { return 128i8; }
+        ",
+        CompilationError "Literal 128i8 is out of range of i8"
+    );
+
+    check_codegen!(
+        "
+        fn test() -> u64 { return 0x1_0000_0000_0000_0000; }
+        ",
+        CompilationError "Literal 0x1_0000_0000_0000_0000 is out of range of u64"
+    );
+
+    check_codegen!(
+        "
+        fn test() -> f32 { return 1e39f32; }
+        ",
+        CompilationError "Literal 1e39f32 is out of range of f32"
+    );
+
+    check_codegen!(
+        "
+        fn test() -> i8 { return 'é'; }
+        ",
+        CompilationError "Char literal 'é' is not a single ASCII char"
+    );
+
+    check_codegen!(
+        "
+        fn test() -> i8 { let a: [i8; 2u8] = [1, 2]; return a[0]; }
+        ",
+        CompilationError "UnrecognizedToken"
+    );
+}
+
+#[test]
+fn test_heap_errors() {
+    check_codegen!(
+        "
+        fn test() { let x = 1; delete x; }
+        ",
+        CompilationError "Delete of non-pointer type i64"
+    );
+
+    check_codegen!(
+        "
+        fn test() { let p = new [i64; 2.5]; }
+        ",
+        CompilationError "Length of allocated array should be integer, not f64"
+    );
+
+    check_codegen!(
+        "
+        fn test() { let p = new [Unknown; 2]; }
+        ",
+        CompilationError "Allocation of unknown type Unknown"
+    );
+
+    check_codegen!(
+        "
+        fn test() { let p = new Unknown {}; }
+        ",
+        CompilationError "Ctor for unknown type Unknown"
+    );
+
+    check_codegen!(
+        "
+        fn nya_free(p: *void, size: i64);
+        fn test(p: *i64) { delete p; }
+        ",
+        CompilationError "Conflicting declaration of nya_free"
+    );
+}
+
+#[test]
+fn test_defer_errors() {
+    check_codegen!(
+        "
+        fn test() { defer return; }
+        ",
+        CompilationError "`return` can't be used in deferred statement"
+    );
+
+    check_codegen!(
+        "
+        fn test() { while (true) { defer break; } }
+        ",
+        CompilationError "`break` can't leave deferred statement"
+    );
+
+    check_codegen!(
+        "
+        fn test(x: i64) {
+            while (true) {
+                defer if (x > 0) { continue; }
+            }
+        }
+        ",
+        CompilationError "`continue` can't leave deferred statement"
+    );
+
+    check_codegen!(
+        "
+        fn test() { defer defer test(); }
+        ",
+        CompilationError "`defer` can't be nested in deferred statement"
+    );
+
+    check_codegen!(
+        "
+        fn test() {
+            defer x = 1;
+            let x = 2;
+        }
+        ",
+        CompilationError "Unknown variable x"
+    );
+}
+
+#[test]
+fn double_extern_decl() {
+    check_codegen!(
+        "fn foo(a: i8) -> i32;
+         fn foo(a: i8) -> i32;
+        ",
+        CompilationError "Redefenition of func foo"
+    );
+}
@@ -0,0 +1,193 @@
+use crate::codegen::tests::macros::check_codegen;
+use nyastd::{live_allocations, nya_alloc, nya_free};
+
+/// `log(d)` appends digit, so order of deferred statements can be checked
+macro_rules! log_fixture {
+    () => {
+        "
+        static LOG: i64 = 0;
+        fn log(d: i64) { LOG = LOG * 10 + d; }
+        "
+    };
+}
+
+#[test]
+fn scope_exit() {
+    check_codegen!(
+        concat!(
+            log_fixture!(),
+            "
+        fn run(x: i64) {
+            defer log(1);
+            defer log(2);
+            if (x > 0) {
+                defer log(3);
+                log(4);
+            }
+            log(5);
+        }
+
+        fn test(x: i64) -> i64 {
+            LOG = 0;
+            run(x);
+            return LOG;
+        }
+            "
+        ),
+        [test as fn(i64) -> i64],
+        [assert test(0) == 521],
+        [assert test(1) == 43521]
+    )
+}
+
+#[test]
+fn returns() {
+    check_codegen!(
+        concat!(
+            log_fixture!(),
+            "
+        fn early(x: i64) -> i64 {
+            defer log(1);
+            if (x > 0) {
+                defer log(2);
+                return x;
+            }
+            log(3);
+            return 0;
+        }
+
+        fn test(x: i64) -> i64 {
+            LOG = 0;
+            let res = early(x);
+            return LOG * 10 + res;
+        }
+
+        fn value() -> i64 {
+            let x = 1;
+            defer x = 2;
+            return x;
+        }
+
+        fn shadow() {
+            let x = 7;
+            defer log(x);
+            if (x > 0) {
+                let x = 8;
+                return;
+            }
+        }
+
+        fn shadowed() -> i64 {
+            LOG = 0;
+            shadow();
+            return LOG;
+        }
+
+        fn later_shadow() -> i64 {
+            LOG = 0;
+            let x = 1;
+            if (x > 0) {
+                defer log(x);
+                let x = 2;
+                log(x);
+            }
+            return LOG;
+        }
+            "
+        ),
+        [test as fn(i64) -> i64],
+        [value as fn() -> i64],
+        [shadowed as fn() -> i64],
+        [later_shadow as fn() -> i64],
+        [assert test(5) == 215],
+        [assert test(0) == 310],
+        [assert value() == 1],
+        [assert shadowed() == 7],
+        [assert later_shadow() == 21]
+    )
+}
+
+#[test]
+fn loops() {
+    check_codegen!(
+        concat!(
+            log_fixture!(),
+            "
+        fn test() -> i64 {
+            LOG = 0;
+            for (let i = 1; i <= 4; i++) {
+                defer log(i);
+                if (i == 2) {
+                    continue;
+                }
+                if (i == 3) {
+                    break;
+                }
+                log(9);
+            }
+            return LOG;
+        }
+
+        fn inner_loop() -> i64 {
+            LOG = 0;
+            if (true) {
+                defer while (true) {
+                    log(1);
+                    break;
+                }
+                log(2);
+            }
+            return LOG;
+        }
+
+        fn labeled() -> i64 {
+            LOG = 0;
+            'outer: while (true) {
+                defer log(1);
+                while (true) {
+                    defer log(2);
+                    break 'outer;
+                }
+            }
+            return LOG;
+        }
+            "
+        ),
+        [test as fn() -> i64],
+        [inner_loop as fn() -> i64],
+        [labeled as fn() -> i64],
+        [assert test() == 9123],
+        [assert inner_loop() == 21],
+        [assert labeled() == 21]
+    )
+}
+
+#[test]
+fn resources() {
+    check_codegen!(
+        "
+        fn sum(n: i64) -> i64 {
+            let arr = new [i64; n];
+            defer delete arr;
+            if (n > 100) {
+                return -1;
+            }
+
+            for (let i = 0; i < n; i++) {
+                arr[i] = i;
+            }
+            let res = 0;
+            for (let i = 0; i < n; i++) {
+                res += arr[i];
+            }
+            return res;
+        }
+        ",
+        [extern nya_alloc],
+        [extern nya_free],
+        [sum as fn(i64) -> i64],
+        [assert sum(5) == 10],
+        [assert sum(1000) == -1],
+        [assert live_allocations() == (0, 0)]
+    )
+}
@@ -0,0 +1,190 @@
+use crate::codegen::tests::macros::check_codegen;
+
+#[test]
+fn payload_match() {
+    check_codegen!(
+        "
+        enum Shape { Circle(f64), Rect(f64, f64), Empty }
+
+        fn area(s: Shape) -> f64 {
+            match (s) {
+                Shape::Circle(r) => { return 3.0 * r * r; }
+                Shape::Rect(w, h) => { return w * h; }
+                Shape::Empty => { return 0.0; }
+            }
+            return -1.0;
+        }
+
+        fn circle(r: f64) -> f64 { return area(Shape::Circle(r)); }
+        fn rect(w: f64, h: f64) -> f64 { return area(Shape::Rect(w, h)); }
+        fn empty() -> f64 { return area(Shape::Empty); }
+        ",
+        [circle as fn(f64) -> f64],
+        [rect as fn(f64, f64) -> f64],
+        [empty as fn() -> f64],
+        [assert circle(2.0) == 12.0],
+        [assert rect(2.0, 1.5) == 3.0],
+        [assert empty() == 0.0]
+    )
+}
+
+#[test]
+fn wildcard() {
+    check_codegen!(
+        "
+        enum Value { Int(i64), Pair(i32, i8), Nothing }
+
+        fn sum(v: Value) -> i64 {
+            let res = 0;
+            match (v) {
+                Value::Pair(a, _) => { res = a; }
+                _ => { res = -1; }
+            }
+            return res;
+        }
+
+        fn test(x: i64) -> i64 {
+            return sum(Value::Pair(x as i32, 7)) + sum(Value::Int(x)) + sum(Value::Nothing);
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(10) == 8]
+    )
+}
+
+#[test]
+fn bindings_are_mutable() {
+    check_codegen!(
+        "
+        enum Counter { Some(i64), None }
+
+        fn test(x: i64) -> i64 {
+            let c = Counter::Some(x);
+            let res = 0;
+            match (c) {
+                Counter::Some(v) => {
+                    v = v * 2;
+                    res = v;
+                }
+                Counter::None => {}
+            }
+            return res;
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(21) == 42]
+    )
+}
+
+#[test]
+fn c_like() {
+    check_codegen!(
+        "
+        enum Color { Red, Green = 5, Blue, Black = -1 }
+
+        fn value(c: i32) -> i64 {
+            let colors = [Color::Red, Color::Green, Color::Blue, Color::Black];
+            return colors[c] as i64;
+        }
+
+        fn is_green(c: i32) -> bool {
+            let colors = [Color::Red, Color::Green, Color::Blue, Color::Black];
+            return colors[c] == Color::Green;
+        }
+        ",
+        [value as fn(i32) -> i64],
+        [is_green as fn(i32) -> bool],
+        [assert value(0) == 0],
+        [assert value(1) == 5],
+        [assert value(2) == 6],
+        [assert value(3) == -1],
+        [assert is_green(1) == true],
+        [assert is_green(2) == false]
+    )
+}
+
+#[test]
+fn value_limits() {
+    check_codegen!(
+        "
+        enum Limit { Min = -9223372036854775808, Max = 9223372036854775807 }
+
+        fn min() -> i64 { return Limit::Min as i64; }
+        fn max() -> i64 { return Limit::Max as i64; }
+        ",
+        [min as fn() -> i64],
+        [max as fn() -> i64],
+        [assert min() == i64::MIN],
+        [assert max() == i64::MAX]
+    )
+}
+
+#[test]
+fn recursive() {
+    check_codegen!(
+        "
+        enum Op { Add, Mul }
+        enum Expr { Num(i64), Bin(Op, *Expr, *Expr) }
+
+        fn eval(e: *Expr) -> i64 {
+            let res = 0;
+            match (*e) {
+                Expr::Num(v) => { res = v; }
+                Expr::Bin(op, l, r) => {
+                    match (op) {
+                        Op::Add => { res = eval(l) + eval(r); }
+                        Op::Mul => { res = eval(l) * eval(r); }
+                    }
+                }
+            }
+            return res;
+        }
+
+        fn test(a: i64, b: i64, c: i64) -> i64 {
+            let na = Expr::Num(a);
+            let nb = Expr::Num(b);
+            let nc = Expr::Num(c);
+            let mul = Expr::Bin(Op::Mul, &nb, &nc);
+            let root = Expr::Bin(Op::Add, &na, &mul);
+            return eval(&root);
+        }
+        ",
+        [test as fn(i64, i64, i64) -> i64],
+        [assert test(2, 3, 4) == 14]
+    )
+}
+
+#[test]
+fn in_structs_and_globals() {
+    check_codegen!(
+        "
+        enum Op { Add, Mul }
+        struct Task { op: Op, arg: i64 }
+        const DEFAULT_OP: Op = Op::Mul;
+        static last: Op = Op::Add;
+
+        fn run(t: Task, x: i64) -> i64 {
+            last = t.op;
+            let res = x;
+            match (t.op) {
+                Op::Add => { res = x + t.arg; }
+                Op::Mul => { res = x * t.arg; }
+            }
+            return res;
+        }
+
+        fn test(x: i64) -> i64 {
+            let t = Task { arg: 3 };
+            let res = run(t, x);
+            t.op = DEFAULT_OP;
+            res = res * 100 + run(t, x);
+            if (last == Op::Mul) {
+                res = -res;
+            }
+            return res;
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(5) == -815]
+    )
+}
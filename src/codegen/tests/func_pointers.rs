@@ -0,0 +1,116 @@
+use crate::codegen::tests::macros::check_codegen;
+use nyastd::{int_cmp, map_ints, sort_ints};
+
+#[test]
+fn variables_and_params() {
+    check_codegen!(
+        "
+        fn inc(x: i64) -> i64 { return x + 1; }
+        fn dbl(x: i64) -> i64 { return x * 2; }
+
+        fn apply(f: fn(i64) -> i64, x: i64) -> i64 { return f(x); }
+        fn twice<T>(f: fn(T) -> T, x: T) -> T { return f(f(x)); }
+
+        fn pick(double: bool) -> fn(i64) -> i64 {
+            if (double) {
+                return dbl;
+            }
+            return inc;
+        }
+
+        fn test(x: i64, double: bool) -> i64 {
+            let f = pick(double);
+            let g: fn(i64) -> i64 = inc;
+            return apply(f, x) * 100 + twice(g, x);
+        }
+        ",
+        [test as fn(i64, bool) -> i64],
+        [assert test(5, false) == 607],
+        [assert test(5, true) == 1007]
+    )
+}
+
+#[test]
+fn fields_and_tables() {
+    check_codegen!(
+        "
+        fn add(a: i64, b: i64) -> i64 { return a + b; }
+        fn sub(a: i64, b: i64) -> i64 { return a - b; }
+        fn mul(a: i64, b: i64) -> i64 { return a * b; }
+
+        struct Op { name: str, eval: fn(i64, i64) -> i64 }
+
+        static OPS: [fn(i64, i64) -> i64; 3] = [add, sub, mul];
+
+        fn by_table(i: i64, a: i64, b: i64) -> i64 { return OPS[i](a, b); }
+
+        fn by_field(a: i64, b: i64) -> i64 {
+            let op = Op { name: \"mul\", eval: mul };
+            let p = &op;
+            let ops = [op, Op { name: \"sub\", eval: sub }];
+            return op.eval(a, b) + p.eval(1, b) * 100 + ops[1].eval(a, b) * 1000;
+        }
+        ",
+        [by_table as fn(i64, i64, i64) -> i64],
+        [by_field as fn(i64, i64) -> i64],
+        [assert by_table(0, 7, 3) == 10],
+        [assert by_table(1, 7, 3) == 4],
+        [assert by_table(2, 7, 3) == 21],
+        [assert by_field(4, 5) == -480]
+    )
+}
+
+#[test]
+fn null_and_compare() {
+    check_codegen!(
+        "
+        fn neg(x: i64) -> i64 { return -x; }
+
+        fn call_or(f: fn(i64) -> i64, x: i64) -> i64 {
+            if (f == null) {
+                return 0;
+            }
+            return f(x);
+        }
+
+        fn test(x: i64) -> i64 {
+            let none: fn(i64) -> i64 = null;
+            let f = neg;
+            return call_or(none, x) + call_or(f, x) * 10 + (f == neg) as i64 * 1000;
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(3) == 970]
+    )
+}
+
+#[test]
+fn stdlib_callbacks() {
+    check_codegen!(
+        "
+        fn sort_ints(arr: *i64, len: i64, cmp: fn(i64, i64) -> i32);
+        fn int_cmp(a: i64, b: i64) -> i32;
+        fn map_ints(arr: *i64, len: i64, f: fn(i64) -> i64);
+
+        fn desc(a: i64, b: i64) -> i32 { return int_cmp(b, a); }
+        fn square(x: i64) -> i64 { return x * x; }
+
+        fn test(descending: bool) -> i64 {
+            let a = [3, -1, 2];
+            let cmp = int_cmp;
+            if (descending) {
+                cmp = desc;
+            }
+            sort_ints(&a[0], 3, cmp);
+            map_ints(&a[0], 3, square);
+            return a[0] * 100 + a[1] * 10 + a[2];
+        }
+        ",
+        [extern sort_ints],
+        [extern int_cmp],
+        [extern map_ints],
+        [test as fn(bool) -> i64],
+        [assert test(false) == 149],
+        [assert test(true) == 941]
+    )
+}
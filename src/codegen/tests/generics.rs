@@ -0,0 +1,123 @@
+use crate::codegen::tests::macros::check_codegen;
+
+#[test]
+fn generic_function() {
+    check_codegen!(
+        "
+        fn max<T>(a: T, b: T) -> T {
+            if (a > b) {
+                return a;
+            }
+            return b;
+        }
+
+        fn test_i32(a: i32, b: i32) -> i32 { return max(a, b); }
+        fn test_f64(a: f64, b: f64) -> f64 { return max(a, b); }
+        fn test_explicit(a: i64) -> u8 { return max::<u8>(a as u8, 200); }
+        fn test_const(a: i8) -> i8 { return max(a, 3); }
+        fn test_negative(a: i8) -> i8 { return max(-3, a); }
+        fn test_float_const(a: f32) -> f32 { return max(a, 0.5); }
+        ",
+        [test_i32 as fn(i32, i32) -> i32],
+        [test_f64 as fn(f64, f64) -> f64],
+        [test_explicit as fn(i64) -> u8],
+        [test_const as fn(i8) -> i8],
+        [test_negative as fn(i8) -> i8],
+        [test_float_const as fn(f32) -> f32],
+        [assert test_i32(-5, 3) == 3],
+        [assert test_i32(7, 3) == 7],
+        [assert test_f64(0.5, -1.5) == 0.5],
+        [assert test_explicit(255) == 255],
+        [assert test_explicit(1) == 200],
+        [assert test_const(-100) == 3],
+        [assert test_negative(-100) == -3],
+        [assert test_float_const(0.25) == 0.5]
+    )
+}
+
+#[test]
+fn generic_struct() {
+    check_codegen!(
+        "
+        struct Pair<T> { a: T, b: T }
+
+        fn sum<T>(p: Pair<T>) -> T { return p.a + p.b; }
+
+        fn swap<T>(p: *Pair<T>) {
+            let tmp = p.a;
+            p.a = p.b;
+            p.b = tmp;
+        }
+
+        fn test_int(x: i64, y: i64) -> i64 {
+            let p = Pair::<i64> { a: x, b: y };
+            swap(&p);
+            return sum(p) * 1000 + p.a;
+        }
+
+        fn test_float(x: f32) -> f32 {
+            let p: Pair<f32> = Pair::<f32> { a: x, b: 0.25 };
+            return sum::<f32>(p);
+        }
+        ",
+        [test_int as fn(i64, i64) -> i64],
+        [test_float as fn(f32) -> f32],
+        [assert test_int(1, 2) == 3002],
+        [assert test_float(1.5) == 1.75]
+    )
+}
+
+#[test]
+fn nested_instances() {
+    check_codegen!(
+        "
+        struct Pair<T> { a: T, b: T }
+        struct Node<T> { value: T, next: *Node<T> }
+
+        fn make_pair<T>(a: T, b: T) -> Pair<T> { return Pair::<T> { a: a, b: b }; }
+
+        fn length<T>(n: *Node<T>) -> i64 {
+            let len = 0;
+            while (n != null) {
+                len = len + 1;
+                n = n.next;
+            }
+            return len;
+        }
+
+        fn test(x: i32) -> i64 {
+            let pp: Pair<Pair<i32>> = make_pair(make_pair(x, 1), make_pair(2 as i32, 3));
+            let last = Node::<i8> { value: 1 };
+            let first = Node::<i8> { value: 2, next: &last };
+            return (pp.a.a + pp.b.b) as i64 * 10 + length(&first);
+        }
+        ",
+        [test as fn(i32) -> i64],
+        [assert test(4) == 72]
+    )
+}
+
+#[test]
+fn instance_over_enum() {
+    check_codegen!(
+        "
+        enum Shape { Circle(f64), Rect(f64, f64) }
+        struct Box<T> { value: T }
+
+        fn area(s: Shape) -> f64 {
+            match (s) {
+                Shape::Circle(r) => { return 3.0 * r * r; }
+                Shape::Rect(w, h) => { return w * h; }
+            }
+            return -1.0;
+        }
+
+        fn test(w: f64, h: f64) -> f64 {
+            let b = Box::<Shape> { value: Shape::Rect(w, h) };
+            return area(b.value);
+        }
+        ",
+        [test as fn(f64, f64) -> f64],
+        [assert test(2.0, 1.5) == 3.0]
+    )
+}
@@ -0,0 +1,152 @@
+use crate::codegen::tests::macros::check_codegen;
+use nyastd::str_len;
+
+#[test]
+fn constants() {
+    check_codegen!(
+        "
+        const SIZE: i64 = 4;
+        const DOUBLE: i64 = SIZE * 2 + (1 << 3);
+        const HALF: f32 = 0.5;
+        const MASK: u8 = ~0 as u8;
+        const TABLE: [i64; 3] = [1, SIZE, DOUBLE];
+
+        fn test(i: i64) -> i64 { return TABLE[i] + SIZE; }
+        fn half(a: f32) -> f32 { return a * HALF; }
+        fn mask() -> u8 { return MASK; }
+        ",
+        [test as fn(i64) -> i64],
+        [half as fn(f32) -> f32],
+        [mask as fn() -> u8],
+        [assert test(0) == 5],
+        [assert test(1) == 8],
+        [assert test(2) == 20],
+        [assert half(3.0) == 1.5],
+        [assert mask() == 255]
+    )
+}
+
+#[test]
+fn logical_constants() {
+    check_codegen!(
+        "
+        const DEBUG: bool = true && false;
+        const VERBOSE: bool = DEBUG || 1 > 0;
+        const QUIET: bool = !VERBOSE && true;
+
+        fn test() -> i32 { return DEBUG as i32 * 100 + VERBOSE as i32 * 10 + QUIET as i32; }
+        ",
+        [test as fn() -> i32],
+        [assert test() == 10]
+    )
+}
+
+#[test]
+fn constants_in_switch() {
+    check_codegen!(
+        "
+        const START: i32 = 1;
+        const STOP: i32 = START + 1;
+
+        fn test(cmd: i32) -> i32 {
+            switch (cmd) {
+                case START: { return 10; }
+                case STOP: { return 20; }
+            }
+            return 0;
+        }
+        ",
+        [test as fn(i32) -> i32],
+        [assert test(1) == 10],
+        [assert test(2) == 20],
+        [assert test(3) == 0]
+    )
+}
+
+#[test]
+fn statics() {
+    check_codegen!(
+        "
+        static counter: i64 = 0;
+        static history: [i64; 4] = [0; 4];
+
+        fn next() -> i64 {
+            counter = counter + 1;
+            history[counter % 4] = counter;
+            return counter;
+        }
+
+        fn last(i: i64) -> i64 { return history[i]; }
+        ",
+        [next as fn() -> i64],
+        [last as fn(i64) -> i64],
+        [assert next() == 1],
+        [assert next() == 2],
+        [assert next() == 3],
+        [assert last(2) == 2],
+        [assert last(0) == 0]
+    )
+}
+
+#[test]
+fn struct_globals() {
+    check_codegen!(
+        "
+        struct A { a: i64, b: i64 }
+        struct B { first: A, second: A, scale: f64 }
+
+        const ORIGIN: B = B { first: A { a: 1 }, second: A { b: 2 + 3 }, scale: 0.5 };
+        static current: A = A { a: 7, b: 8 };
+
+        fn test() -> i64 {
+            current.a = current.a + ORIGIN.second.b;
+            return ORIGIN.first.a + ORIGIN.first.b + (ORIGIN.scale * 20) as i64;
+        }
+        fn get() -> i64 { return current.a * 10 + current.b; }
+        ",
+        [test as fn() -> i64],
+        [get as fn() -> i64],
+        [assert test() == 11],
+        [assert get() == 128]
+    )
+}
+
+#[test]
+fn shadowing() {
+    check_codegen!(
+        "
+        const X: i64 = 10;
+        static Y: i64 = 20;
+
+        fn test(flag: bool) -> i64 {
+            let res: i64 = X + Y;
+            if (flag) {
+                let X = 1;
+                let Y = 2;
+                X = X + 1;
+                res = X + Y;
+            }
+            return res;
+        }
+        ",
+        [test as fn(bool) -> i64],
+        [assert test(false) == 30],
+        [assert test(true) == 4]
+    )
+}
+
+#[test]
+fn static_string() {
+    check_codegen!(
+        "
+        fn str_len(s: str) -> i64;
+
+        static GREETING: str = \"nya~\";
+
+        fn test() -> i64 { return str_len(GREETING); }
+        ",
+        [extern str_len],
+        [test as fn() -> i64],
+        [assert test() == 4]
+    )
+}
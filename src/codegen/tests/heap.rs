@@ -0,0 +1,132 @@
+use crate::codegen::tests::macros::check_codegen;
+use nyastd::{live_allocations, nya_alloc, nya_free};
+
+#[test]
+fn new_delete() {
+    check_codegen!(
+        "
+        struct Node { val: i64, next: *Node }
+
+        fn push(head: *Node, val: i64) -> *Node {
+            return new Node { val: val, next: head };
+        }
+
+        fn test(n: i64) -> i64 {
+            let head: *Node = null;
+            for (let i = 1; i <= n; i++) {
+                head = push(head, i);
+            }
+
+            let res = 0;
+            while (head != null) {
+                res += head.val;
+                let next = head.next;
+                delete head;
+                head = next;
+            }
+            return res;
+        }
+        ",
+        [extern nya_alloc],
+        [extern nya_free],
+        [test as fn(i64) -> i64],
+        [assert test(10) == 55],
+        [assert test(0) == 0],
+        [assert live_allocations() == (0, 0)]
+    )
+}
+
+#[test]
+fn arrays() {
+    check_codegen!(
+        "
+        fn squares(n: u32) -> i64 {
+            let arr = new [i64; n];
+            for (let i = 0; i < n; i++) {
+                arr[i] = i * i;
+            }
+
+            let res = 0;
+            for (let i = 0; i < n; i++) {
+                res += arr[i];
+            }
+            delete arr;
+            return res;
+        }
+
+        fn zeroed(n: i64) -> i64 {
+            let arr = new [i32; n];
+            let res = 0;
+            for (let i = 0; i < n; i++) {
+                res += arr[i];
+            }
+            delete arr;
+            return res;
+        }
+        ",
+        [extern nya_alloc],
+        [extern nya_free],
+        [squares as fn(u32) -> i64],
+        [zeroed as fn(i64) -> i64],
+        [assert squares(4) == 14],
+        [assert zeroed(100) == 0],
+        [assert live_allocations() == (0, 0)]
+    )
+}
+
+#[test]
+fn leaks() {
+    check_codegen!(
+        "
+        fn leak() -> i64 {
+            let p = new i64 {};
+            *p = 42;
+            return *p;
+        }
+
+        fn no_leak() -> i64 {
+            let p = new i64 {};
+            let res = *p;
+            delete p;
+            delete null;
+            return res;
+        }
+        ",
+        [extern nya_alloc],
+        [extern nya_free],
+        [leak as fn() -> i64],
+        [no_leak as fn() -> i64],
+        [assert no_leak() == 0],
+        [assert live_allocations() == (0, 0)],
+        [assert leak() == 42],
+        [assert live_allocations() == (1, 8)]
+    )
+}
+
+#[test]
+fn invalid_sizes() {
+    // Negative or overflowing size aborts the program, so it's allocated in child test process
+    if let Ok(len) = std::env::var("NYA_ALLOC_LEN") {
+        check_codegen!(
+            "fn alloc(n: i64) -> *i64 { return new [i64; n]; }",
+            [extern nya_alloc],
+            [alloc as fn(i64) -> *mut i64],
+            [assert alloc(len.parse().unwrap()) == std::ptr::null_mut()]
+        );
+        return;
+    }
+
+    let test_name = concat!(module_path!(), "::invalid_sizes");
+    let test_name = test_name.split_once("::").unwrap().1;
+    for len in [-1, i64::MAX / 4] {
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([test_name, "--exact", "--nocapture", "--test-threads=1"])
+            .env("NYA_ALLOC_LEN", len.to_string())
+            .output()
+            .unwrap();
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("nya_alloc: invalid allocation size -1"));
+    }
+}
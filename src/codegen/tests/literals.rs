@@ -0,0 +1,93 @@
+use std::ffi::c_char;
+
+use crate::codegen::tests::macros::check_codegen;
+
+#[test]
+fn numeric() {
+    check_codegen!(
+        "
+        fn test() -> i64 {
+            return 0xFF + 0b1010 + 0o17 + 1_000;
+        }
+
+        fn mask(x: u64) -> u64 {
+            // Doesn't fit into i64, so literal is u64
+            return x & 0xFFFF_FFFF_FFFF_FFF0;
+        }
+
+        fn small(x: u8) -> u8 {
+            let y = 250u8;
+            return x + y;
+        }
+
+        fn scale(x: f64) -> f64 {
+            return x * 1e-3 + 2.5E1;
+        }
+
+        fn half(x: f32) -> f32 {
+            let k = 0.5f32;
+            return x * k;
+        }
+        ",
+        [test as fn() -> i64],
+        [mask as fn(u64) -> u64],
+        [small as fn(u8) -> u8],
+        [scale as fn(f64) -> f64],
+        [half as fn(f32) -> f32],
+        [assert test() == 1280],
+        [assert mask(u64::MAX) == u64::MAX - 15],
+        [assert small(10) == 4],
+        [assert scale(2000.0) == 27.0],
+        [assert half(3.0) == 1.5]
+    )
+}
+
+#[test]
+fn signed_minimums() {
+    check_codegen!(
+        "
+        fn min8() -> i8 { return -128i8; }
+        fn min64() -> i64 { return -9223372036854775808; }
+        fn min32() -> i32 { return -(0x8000_0000i32); }
+        ",
+        [min8 as fn() -> i8],
+        [min64 as fn() -> i64],
+        [min32 as fn() -> i32],
+        [assert min8() == i8::MIN],
+        [assert min64() == i64::MIN],
+        [assert min32() == i32::MIN]
+    )
+}
+
+#[test]
+fn chars() {
+    check_codegen!(
+        r#"
+        fn count(s: str, c: i8) -> i64 {
+            let res = 0;
+            for (let i = 0; s[i] != '\0'; i++) {
+                if (s[i] == c) {
+                    res++;
+                }
+            }
+            return res;
+        }
+
+        fn test(s: str) -> i64 {
+            return count(s, 'l') * 10 + count(s, '\n');
+        }
+
+        fn upper(c: i8) -> i8 {
+            if (c >= 'a' && c <= 'z') {
+                return c - 'a' + 'A';
+            }
+            return c;
+        }
+        "#,
+        [test as fn(*const c_char) -> i64],
+        [upper as fn(i8) -> i8],
+        [assert test(c"hello\nworld\n".as_ptr()) == 32],
+        [assert upper(b'q' as i8) == b'Q' as i8],
+        [assert upper(b'1' as i8) == b'1' as i8]
+    )
+}
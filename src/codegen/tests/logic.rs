@@ -0,0 +1,112 @@
+use crate::codegen::tests::macros::check_codegen;
+
+#[test]
+fn both_positive() {
+    check_codegen!(
+        "
+        fn test(a: i64, b: i64) -> i32 {
+            if (a > 0 && b > 0) {
+                return 1;
+            }
+            return 0;
+        }
+        ",
+        [test as fn(i64, i64) -> i32],
+        [assert test(1, 1) == 1],
+        [assert test(1, 0) == 0],
+        [assert test(0, 1) == 0],
+        [assert test(-1, -1) == 0]
+    )
+}
+
+#[test]
+fn any_positive() {
+    check_codegen!(
+        "
+        fn test(a: i64, b: i64) -> i32 {
+            let res: i32 = 0;
+            if (a > 0 || b > 0 || false) {
+                res = 1;
+            }
+            return res;
+        }
+        ",
+        [test as fn(i64, i64) -> i32],
+        [assert test(1, 1) == 1],
+        [assert test(1, 0) == 1],
+        [assert test(0, 1) == 1],
+        [assert test(0, 0) == 0]
+    )
+}
+
+#[test]
+fn short_circuit() {
+    check_codegen!(
+        "
+        fn bump(counter: *i64) -> bool {
+            *counter = *counter + 1;
+            return true;
+        }
+
+        fn test_and(flag: i32) -> i64 {
+            let counter = 0;
+            let res = flag != 0 && bump(&counter);
+            return counter;
+        }
+
+        fn test_or(flag: i32) -> i64 {
+            let counter = 0;
+            let res = flag != 0 || bump(&counter);
+            return counter;
+        }
+        ",
+        [test_and as fn(i32) -> i64],
+        [test_or as fn(i32) -> i64],
+        [assert test_and(0) == 0],
+        [assert test_and(1) == 1],
+        [assert test_or(0) == 1],
+        [assert test_or(1) == 0]
+    )
+}
+
+#[test]
+fn non_bool_operands() {
+    check_codegen!(
+        "
+        fn test(a: i64, b: f64, p: *i64) -> i32 {
+            if (a && b && p) {
+                return 1;
+            }
+            return 0;
+        }
+        ",
+        [test as fn(i64, f64, *const i64) -> i32],
+        [assert test(2, 0.5, &0) == 1],
+        [assert test(2, 0.0, &0) == 0],
+        [assert test(0, 0.5, &0) == 0],
+        [assert test(2, 0.5, std::ptr::null()) == 0]
+    )
+}
+
+#[test]
+fn nested_in_loop() {
+    check_codegen!(
+        "
+        fn test(n: i64) -> i64 {
+            let count = 0;
+            let stop = false;
+            for (let i = 0; i < n && !stop; i = i + 1) {
+                if (i == 3 || i == 5) {
+                    count = count + 1;
+                }
+                stop = i >= 5;
+            }
+            return count;
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(3) == 0],
+        [assert test(4) == 1],
+        [assert test(100) == 2]
+    )
+}
@@ -0,0 +1,192 @@
+use crate::codegen::tests::macros::check_codegen;
+
+#[test]
+fn break_while() {
+    check_codegen!(
+        "
+        fn find(needle: i64) -> i64 {
+            let i: i64 = 0;
+            while (true) {
+                if (i * i >= needle) {
+                    break;
+                }
+                i = i + 1;
+            }
+            return i;
+        }
+        ",
+        [find as fn(i64) -> i64],
+        [assert find(0) == 0],
+        [assert find(16) == 4],
+        [assert find(17) == 5]
+    )
+}
+
+#[test]
+fn continue_for_runs_step() {
+    check_codegen!(
+        "
+        fn sum_odd(n: i64) -> i64 {
+            let sum: i64 = 0;
+            for (let i: i64 = 0; i < n; i = i + 1) {
+                if (i % 2 == 0) {
+                    continue;
+                }
+                sum = sum + i;
+            }
+            return sum;
+        }
+        ",
+        [sum_odd as fn(i64) -> i64],
+        [assert sum_odd(0) == 0],
+        [assert sum_odd(4) == 4],
+        [assert sum_odd(7) == 9]
+    )
+}
+
+#[test]
+fn continue_while() {
+    check_codegen!(
+        "
+        fn count(n: i64) -> i64 {
+            let i: i64 = 0;
+            let res: i64 = 0;
+            while (i < n) {
+                i = i + 1;
+                if (i % 3 != 0) {
+                    continue;
+                }
+                res = res + 1;
+            }
+            return res;
+        }
+        ",
+        [count as fn(i64) -> i64],
+        [assert count(2) == 0],
+        [assert count(9) == 3]
+    )
+}
+
+#[test]
+fn labeled() {
+    check_codegen!(
+        "
+        // Index of first row containing given value
+        fn find_row(needle: i64) -> i64 {
+            let m = [[1, 2, 3], [4, 5, 6], [7, 8, 9]];
+            let row: i64 = -1;
+            'outer: for (let i: i64 = 0; i < 3; i = i + 1) {
+                for (let j: i64 = 0; j < 3; j = j + 1) {
+                    if (m[i][j] == needle) {
+                        row = i;
+                        break 'outer;
+                    }
+                }
+            }
+            return row;
+        }
+
+        // Sum of rows prefixes before first value greater than limit
+        fn prefix_sum(limit: i64) -> i64 {
+            let m = [[1, 9, 1], [2, 2, 9], [9, 3, 3]];
+            let sum: i64 = 0;
+            'rows: for (let i: i64 = 0; i < 3; i = i + 1) {
+                let j: i64 = 0;
+                while (j < 3) {
+                    if (m[i][j] > limit) {
+                        continue 'rows;
+                    }
+                    sum = sum + m[i][j];
+                    j = j + 1;
+                }
+            }
+            return sum;
+        }
+        ",
+        [find_row as fn(i64) -> i64],
+        [prefix_sum as fn(i64) -> i64],
+        [assert find_row(1) == 0],
+        [assert find_row(6) == 1],
+        [assert find_row(8) == 2],
+        [assert find_row(10) == -1],
+        [assert prefix_sum(5) == 5],
+        [assert prefix_sum(9) == 39]
+    )
+}
+
+#[test]
+fn infinite_loop() {
+    check_codegen!(
+        "
+        fn collatz(n: i64) -> i64 {
+            let steps = 0;
+            loop {
+                if (n == 1) {
+                    return steps;
+                }
+                if (n % 2 == 0) {
+                    n /= 2;
+                } else {
+                    n = 3 * n + 1;
+                }
+                steps++;
+            }
+        }
+
+        fn first_square(from: i64) -> i64 {
+            let i = 0;
+            'search: loop {
+                i++;
+                if (i * i < from) {
+                    continue 'search;
+                }
+                break;
+            }
+            return i * i;
+        }
+        ",
+        [collatz as fn(i64) -> i64],
+        [first_square as fn(i64) -> i64],
+        [assert collatz(1) == 0],
+        [assert collatz(6) == 8],
+        [assert first_square(10) == 16],
+        [assert first_square(16) == 16]
+    )
+}
+
+#[test]
+fn do_while() {
+    check_codegen!(
+        "
+        fn digits(n: i64) -> i64 {
+            let res = 0;
+            do {
+                res++;
+                n /= 10;
+            } while (n != 0);
+            return res;
+        }
+
+        fn retries(fail_times: i64) -> i64 {
+            let attempts = 0;
+            do {
+                attempts++;
+                if (attempts <= fail_times) {
+                    // `continue` checks condition too
+                    continue;
+                }
+                break;
+            } while (attempts < 5);
+            return attempts;
+        }
+        ",
+        [digits as fn(i64) -> i64],
+        [retries as fn(i64) -> i64],
+        [assert digits(0) == 1],
+        [assert digits(7) == 1],
+        [assert digits(12345) == 5],
+        [assert retries(0) == 1],
+        [assert retries(2) == 3],
+        [assert retries(10) == 5]
+    )
+}
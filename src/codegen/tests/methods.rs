@@ -0,0 +1,128 @@
+use crate::codegen::tests::macros::check_codegen;
+
+#[test]
+fn simple() {
+    check_codegen!(
+        "
+        struct Point { x: f64, y: f64 }
+
+        impl Point {
+            fn len2(self) -> f64 { return self.x * self.x + self.y * self.y; }
+            fn dot(self, other: Point) -> f64 { return self.x * other.x + self.y * other.y; }
+        }
+
+        fn test(x: f64, y: f64) -> f64 {
+            let p = Point { x: x, y: y };
+            return p.len2() + p.dot(Point { x: 1, y: 1 });
+        }
+        ",
+        [test as fn(f64, f64) -> f64],
+        [assert test(3.0, 4.0) == 32.0],
+        [assert test(0.0, 0.0) == 0.0]
+    )
+}
+
+#[test]
+fn mutating() {
+    check_codegen!(
+        "
+        struct Counter { value: i64, step: i64 }
+
+        impl Counter {
+            fn inc(self) { self.value = self.value + self.step; }
+            // Methods can call each other through `self`
+            fn inc_twice(self) -> i64 {
+                self.inc();
+                self.inc();
+                return self.value;
+            }
+        }
+
+        fn test(step: i64) -> i64 {
+            let c = Counter { step: step };
+            c.inc();
+            return c.inc_twice();
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(1) == 3],
+        [assert test(5) == 15]
+    )
+}
+
+#[test]
+fn receivers() {
+    // Methods can be called on fields, array elements & through pointers
+    check_codegen!(
+        "
+        struct A { v: i64 }
+        struct B { a: A, arr: [A; 2], ptr: *A }
+
+        impl A {
+            fn get(self) -> i64 { return self.v; }
+        }
+
+        fn test() -> i64 {
+            let x = A { v: 100 };
+            let b = B { a: A { v: 1 }, arr: [A { v: 10 }, A { v: 20 }], ptr: &x };
+            let p = &b;
+            return b.a.get() + b.arr[1].get() + b.ptr.get() + p.a.get();
+        }
+        ",
+        [test as fn() -> i64],
+        [assert test() == 122]
+    )
+}
+
+#[test]
+fn same_names() {
+    // Methods with the same name on different types & free function don't clash
+    check_codegen!(
+        "
+        struct A { v: i64 }
+        struct B { v: i64 }
+
+        impl A { fn value(self) -> i64 { return self.v; } }
+        impl B { fn value(self) -> i64 { return self.v * 10; } }
+
+        fn value() -> i64 { return 1000; }
+
+        fn test() -> i64 {
+            let a = A { v: 1 };
+            let b = B { v: 2 };
+            return a.value() + b.value() + value();
+        }
+        ",
+        [test as fn() -> i64],
+        [assert test() == 1021]
+    )
+}
+
+#[test]
+fn temporary_receivers() {
+    // Receiver, which isn't variable, is stored into temporary
+    check_codegen!(
+        "
+        struct Point { x: i64, y: i64 }
+
+        impl Point {
+            fn sum(self) -> i64 { return self.x + self.y; }
+            fn shifted(self, d: i64) -> Point { return Point { x: self.x + d, y: self.y + d }; }
+        }
+
+        fn make(x: i64) -> Point { return Point { x: x, y: 1 }; }
+        fn by_ptr(p: *Point) -> *Point { return p; }
+
+        fn test(x: i64) -> i64 {
+            let p = make(2);
+            let res = 0;
+            for (let i: i64 = 0; i < 2; i = i + 1) {
+                res = res + make(x).sum();
+            }
+            return res + Point { x: 10, y: 0 }.sum() + (make(x)).shifted(1).sum() + by_ptr(&p).sum();
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(1) == 21]
+    )
+}
@@ -0,0 +1,176 @@
+use crate::codegen::tests::macros::check_codegen;
+
+#[test]
+fn functions_and_types() {
+    check_codegen!(
+        Files [
+            (
+                "main.nya",
+                "
+                import \"geometry.nya\";
+
+                fn test(w: f64, h: f64) -> f64 {
+                    let p = geometry::Point { x: w, y: h };
+                    let s: geometry::Shape = geometry::Shape::Rect(w, h);
+                    return geometry::area(s) + p.len2() + geometry::SCALE;
+                }
+                "
+            ),
+            (
+                "geometry.nya",
+                "
+                pub const SCALE: f64 = 0.5;
+
+                pub struct Point { x: f64, y: f64 }
+                impl Point {
+                    pub fn len2(self) -> f64 { return self.x * self.x + self.y * self.y; }
+                }
+
+                pub enum Shape { Rect(f64, f64), Empty }
+
+                // Private helper, items of the module see each other without prefix
+                fn mul(a: f64, b: f64) -> f64 { return a * b; }
+
+                pub fn area(s: Shape) -> f64 {
+                    match (s) {
+                        Shape::Rect(w, h) => { return mul(w, h); }
+                        Shape::Empty => { return 0.0; }
+                    }
+                    return -1.0;
+                }
+                "
+            )
+        ],
+        [test as fn(f64, f64) -> f64],
+        [assert test(3.0, 4.0) == 37.5]
+    )
+}
+
+#[test]
+fn nested_imports() {
+    check_codegen!(
+        Files [
+            (
+                "main.nya",
+                "
+                mod io;
+                mod math;
+
+                // Same names in different modules don't clash
+                fn sqr(x: i64) -> i64 { return -1; }
+
+                fn test(x: i64) -> i64 { return io::twice_sqr(x) + math::sqr(x) + sqr(x); }
+                fn max(a: i64, b: i64) -> i64 { return math::max(a, b); }
+                "
+            ),
+            (
+                "io.nya",
+                "
+                import \"./lib/../math.nya\";
+                pub fn twice_sqr(x: i64) -> i64 { return math::sqr(x) * 2; }
+                "
+            ),
+            (
+                "math.nya",
+                "
+                pub fn sqr(x: i64) -> i64 { return x * x; }
+                pub fn max<T>(a: T, b: T) -> T {
+                    if (a > b) {
+                        return a;
+                    }
+                    return b;
+                }
+                "
+            )
+        ],
+        [test as fn(i64) -> i64],
+        [max as fn(i64, i64) -> i64],
+        [assert test(3) == 26],
+        [assert max(3, 7) == 7]
+    )
+}
+
+#[test]
+fn function_values() {
+    check_codegen!(
+        Files [
+            (
+                "main.nya",
+                "
+                mod ops;
+
+                fn apply(f: fn(i64) -> i64, x: i64) -> i64 { return f(x); }
+                fn test(x: i64) -> i64 { return apply(ops::inc, x) + ops::COUNT; }
+                "
+            ),
+            (
+                "ops.nya",
+                "
+                pub static COUNT: i64 = 10;
+                pub fn inc(x: i64) -> i64 { return x + 1; }
+                "
+            )
+        ],
+        [test as fn(i64) -> i64],
+        [assert test(1) == 12]
+    )
+}
+
+#[test]
+fn root_item_with_same_name() {
+    check_codegen!(
+        Files [
+            (
+                "main.nya",
+                "
+                mod math;
+
+                fn sqr(x: i64) -> i64 { return -1; }
+
+                fn test(x: i64) -> i64 { return math::sqr_twice(x) + sqr(x); }
+                "
+            ),
+            (
+                "math.nya",
+                "
+                fn sqr(x: i64) -> i64 { return x * x; }
+
+                pub fn sqr_twice(x: i64) -> i64 {
+                    let f: fn(i64) -> i64 = sqr;
+                    return sqr(x) + f(x);
+                }
+                "
+            )
+        ],
+        [test as fn(i64) -> i64],
+        [assert test(3) == 17]
+    )
+}
+
+#[test]
+fn shared_extern() {
+    check_codegen!(
+        Files [
+            (
+                "main.nya",
+                "
+                mod text;
+
+                fn str_len(s: str) -> i64;
+
+                fn test() -> i64 { return text::len() + str_len(\"ab\"); }
+                "
+            ),
+            (
+                "text.nya",
+                "
+                fn str_len(s: str) -> i64;
+
+                pub fn len() -> i64 { return str_len(\"abc\"); }
+                "
+            )
+        ],
+        [test as fn() -> i64],
+        [assert test() == 5]
+    )
+}
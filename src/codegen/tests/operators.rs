@@ -0,0 +1,173 @@
+use crate::codegen::tests::macros::check_codegen;
+
+#[test]
+fn remainder() {
+    check_codegen!(
+        "
+        fn rem(a: i64, b: i64) -> i64 { return a % b; }
+        fn frem(a: f64, b: f64) -> f64 { return a % b; }
+        ",
+        [rem as fn(i64, i64) -> i64],
+        [frem as fn(f64, f64) -> f64],
+        [assert rem(7, 3) == 1],
+        [assert rem(-7, 3) == -1],
+        [assert rem(6, 3) == 0],
+        [assert frem(7.5, 2.0) == 1.5],
+        [assert frem(-7.5, 2.0) == -1.5]
+    )
+}
+
+#[test]
+fn bitwise() {
+    check_codegen!(
+        "
+        fn and(a: i64, b: i64) -> i64 { return a & b; }
+        fn or(a: i64, b: i64) -> i64 { return a | b; }
+        fn xor(a: i64, b: i64) -> i64 { return a ^ b; }
+        fn not(a: i64) -> i64 { return ~a; }
+        ",
+        [and as fn(i64, i64) -> i64],
+        [or as fn(i64, i64) -> i64],
+        [xor as fn(i64, i64) -> i64],
+        [not as fn(i64) -> i64],
+        [assert and(0b1100, 0b1010) == 0b1000],
+        [assert or(0b1100, 0b1010) == 0b1110],
+        [assert xor(0b1100, 0b1010) == 0b0110],
+        [assert not(0) == -1],
+        [assert not(5) == -6]
+    )
+}
+
+#[test]
+fn shifts() {
+    check_codegen!(
+        "
+        fn shl(a: i64, b: i64) -> i64 { return a << b; }
+        fn shr(a: i64, b: i64) -> i64 { return a >> b; }
+        ",
+        [shl as fn(i64, i64) -> i64],
+        [shr as fn(i64, i64) -> i64],
+        [assert shl(1, 10) == 1024],
+        [assert shl(3, 2) == 12],
+        [assert shr(1024, 3) == 128],
+        [assert shr(-8, 1) == -4]
+    )
+}
+
+#[test]
+fn shift_types() {
+    check_codegen!(
+        "
+        // Result has type of lhs, not common type
+        fn shl8(x: i8, n: i64) -> i64 { return x << n; }
+        fn shr8(x: u8, n: i32) -> u8 { return x >> n; }
+        ",
+        [shl8 as fn(i8, i64) -> i64],
+        [shr8 as fn(u8, i32) -> u8],
+        [assert shl8(1, 7) == -128],
+        [assert shl8(3, 1) == 6],
+        // Amount is taken modulo bitwidth
+        [assert shl8(1, 9) == 2],
+        [assert shr8(128, 7) == 1],
+        [assert shr8(128, 8) == 128]
+    )
+}
+
+#[test]
+fn hashing() {
+    check_codegen!(
+        "
+        // FNV-1a over bytes of integer
+        fn hash(x: i64) -> i64 {
+            let h = 2166136261;
+            for (let i = 0; i < 8; i = i + 1) {
+                h = h ^ ((x >> (i * 8)) & 255);
+                h = (h * 16777619) & 4294967295;
+            }
+            return h;
+        }
+
+        fn is_flag_set(flags: i64, bit: i64) -> i32 {
+            if (flags & (1 << bit)) {
+                return 1;
+            }
+            return 0;
+        }
+        ",
+        [hash as fn(i64) -> i64],
+        [is_flag_set as fn(i64, i64) -> i32],
+        [assert hash(0) == fnv1a(0)],
+        [assert hash(123456789) == fnv1a(123456789)],
+        [assert is_flag_set(0b101, 0) == 1],
+        [assert is_flag_set(0b101, 1) == 0],
+        [assert is_flag_set(0b101, 2) == 1]
+    )
+}
+
+fn fnv1a(x: i64) -> i64 {
+    let mut h: i64 = 2166136261;
+    for byte in x.to_le_bytes() {
+        h ^= byte as i64;
+        h = h.wrapping_mul(16777619) & 4294967295;
+    }
+    h
+}
+
+#[test]
+fn compound_assignment() {
+    check_codegen!(
+        "
+        struct Point { x: i64, y: f64 }
+
+        static CALLS: i64 = 0;
+        fn next() -> i64 {
+            CALLS += 1;
+            return CALLS - 1;
+        }
+
+        fn test(x: i64) -> i64 {
+            CALLS = 0;
+            let arr: [i64; 3] = [1, 2, 3];
+            // Index is computed once
+            arr[next()] += x;
+            arr[next()] *= 10;
+
+            let s: i64 = 0;
+            for (let i: i64 = 0; i < 3; i++) {
+                s += arr[i];
+            }
+            return s * 100 + CALLS;
+        }
+
+        fn bits(x: u8) -> u8 {
+            let r = x;
+            r |= 1;
+            r <<= 2;
+            r &= 60;
+            r ^= 3;
+            r >>= 1;
+            r -= 1;
+            r %= 7;
+            return r;
+        }
+
+        fn fields(x: i64) -> f64 {
+            let p = Point { x: x, y: 1.5 };
+            let q = &p;
+            p.x++;
+            q.x *= 3;
+            q.y /= 2;
+            p.y -= 0.25;
+            q.x--;
+            return p.x as f64 + p.y;
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [bits as fn(u8) -> u8],
+        [fields as fn(i64) -> f64],
+        [assert test(5) == 2902],
+        [assert bits(4) == 3],
+        [assert bits(255) == 2],
+        [assert fields(1) == 5.5]
+    )
+}
@@ -0,0 +1,139 @@
+use crate::codegen::tests::macros::check_codegen;
+
+#[test]
+fn swap() {
+    check_codegen!(
+        "
+        fn swap(a: *i64, b: *i64) {
+            let tmp = *a;
+            *a = *b;
+            *b = tmp;
+        }
+
+        fn test(x: i64, y: i64) -> i64 {
+            swap(&x, &y);
+            return x * 10 + y;
+        }
+        ",
+        [test as fn(i64, i64) -> i64],
+        [assert test(1, 2) == 21],
+        [assert test(3, 0) == 3]
+    )
+}
+
+#[test]
+fn pointer_to_pointer() {
+    check_codegen!(
+        "
+        fn test(x: i64) -> i64 {
+            let p = &x;
+            let pp: **i64 = &p;
+            **pp = **pp + 1;
+            return x;
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(1) == 2],
+        [assert test(-1) == 0]
+    )
+}
+
+#[test]
+fn struct_by_reference() {
+    check_codegen!(
+        "
+        struct Point { x: i64, y: i64 }
+
+        fn shift(p: *Point, delta: i64) {
+            p.x = p.x + delta;
+            p.y = p.y - delta;
+        }
+
+        fn test(delta: i64) -> i64 {
+            let p = Point {};
+            shift(&p, delta);
+            shift(&p, delta);
+            return p.x * 100 + p.y;
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(1) == 198],
+        [assert test(0) == 0]
+    )
+}
+
+#[test]
+fn linked_list() {
+    check_codegen!(
+        "
+        struct Node { val: i64, next: *Node }
+
+        fn sum(head: *Node) -> i64 {
+            let res = 0;
+            let cur = head;
+            while (cur != null) {
+                res = res + cur.val;
+                cur = cur.next;
+            }
+            return res;
+        }
+
+        fn test() -> i64 {
+            let a = Node {};
+            let b = Node {};
+            let c = Node {};
+            a.val = 1;
+            b.val = 2;
+            c.val = 3;
+            a.next = &b;
+            b.next = &c;
+            a.next.next.val = 4;
+            return sum(&a);
+        }
+        ",
+        [test as fn() -> i64],
+        [assert test() == 7]
+    )
+}
+
+#[test]
+fn field_address() {
+    check_codegen!(
+        "
+        struct Pair { first: i64, second: i64 }
+
+        fn set(p: *i64, val: i64) { *p = val; }
+
+        fn test(x: i64) -> i64 {
+            let pair = Pair {};
+            set(&pair.second, x);
+            return pair.first + pair.second;
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(5) == 5]
+    )
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn test_write_ptr(ptr: *mut i64, val: i64) {
+    unsafe { *ptr = val };
+}
+
+#[test]
+fn ffi_pointer() {
+    check_codegen!(
+        "
+        fn test_write_ptr(ptr: *i64, val: i64);
+
+        fn test(x: i64) -> i64 {
+            let res = 0;
+            test_write_ptr(&res, x);
+            return res;
+        }
+        ",
+        [extern test_write_ptr],
+        [test as fn(i64) -> i64],
+        [assert test(42) == 42]
+    )
+}
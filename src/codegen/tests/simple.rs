@@ -1,5 +1,3 @@
-use std::i32;
-
 use crate::codegen::tests::macros::check_codegen;
 
 #[test]
@@ -365,3 +363,21 @@ fn test_self_shadowing() {
         [assert test(1) == 3]
     );
 }
+
+#[test]
+fn test_comments() {
+    check_codegen!(
+        "
+        /// Sums two numbers
+        fn sum(a: i32, b: i32) -> i32 {
+            // line comment
+            return a /* inline */ + b; // trailing comment
+            /* nested /* block */ comment
+               return 0;
+            */
+        }
+        ",
+        [sum as fn(i32, i32) -> i32],
+        [assert sum(1, 2) == 3]
+    )
+}
@@ -0,0 +1,88 @@
+use std::ffi::{CStr, c_char};
+
+use crate::codegen::tests::macros::check_codegen;
+use nyastd::{str_concat, str_eq, str_free, str_len};
+
+#[test]
+fn literal() {
+    check_codegen!(
+        r#"
+        fn hello() -> str {
+            return "Hello,\tworld!\n";
+        }
+
+        fn raw_ptr() -> *i8 {
+            return "\x41\\\"";
+        }
+        "#,
+        [hello as fn() -> *const c_char],
+        [raw_ptr as fn() -> *const c_char],
+        [assert unsafe_str(hello()) == "Hello,\tworld!\n"],
+        [assert unsafe_str(raw_ptr()) == "A\\\""]
+    )
+}
+
+fn unsafe_str(s: *const c_char) -> &'static str {
+    unsafe { CStr::from_ptr(s) }.to_str().unwrap()
+}
+
+#[test]
+fn string_chars() {
+    check_codegen!(
+        r#"
+        fn test(i: i64) -> i8 {
+            let s = "abc";
+            return s[i];
+        }
+        "#,
+        [test as fn(i64) -> i8],
+        [assert test(0) == b'a' as i8],
+        [assert test(2) == b'c' as i8],
+        [assert test(3) == 0]
+    )
+}
+
+#[test]
+fn std_functions() {
+    check_codegen!(
+        r#"
+        fn str_len(s: str) -> i64;
+        fn str_eq(lhs: str, rhs: str) -> i32;
+        fn str_concat(lhs: str, rhs: str) -> str;
+        fn str_free(s: str);
+
+        fn len() -> i64 {
+            return str_len("four") + str_len("");
+        }
+
+        fn eq(flag: i32) -> i32 {
+            let s = "nya";
+            if (flag) {
+                s = "meow";
+            }
+            return str_eq(s, "nya");
+        }
+
+        fn concat() -> i64 {
+            let s = str_concat("Value: ", "42");
+            let res = 0;
+            if (str_eq(s, "Value: 42")) {
+                res = str_len(s);
+            }
+            str_free(s);
+            return res;
+        }
+        "#,
+        [extern str_len],
+        [extern str_eq],
+        [extern str_concat],
+        [extern str_free],
+        [len as fn() -> i64],
+        [eq as fn(i32) -> i32],
+        [concat as fn() -> i64],
+        [assert len() == 4],
+        [assert eq(0) == 1],
+        [assert eq(1) == 0],
+        [assert concat() == 9]
+    )
+}
@@ -0,0 +1,102 @@
+use crate::codegen::tests::macros::check_codegen;
+
+#[derive(PartialEq, Debug)]
+#[repr(C)]
+struct Point {
+    x: i64,
+    y: f64,
+}
+
+#[test]
+fn literal() {
+    check_codegen!(
+        "
+        struct Point { x: i64, y: f64 }
+
+        fn full(x: i64) -> Point { return Point { x: x, y: 2.5 }; }
+        fn partial(y: f64) -> Point { return Point { y: y }; }
+        fn reordered() -> Point { return Point { y: 1, x: 2, }; }
+        ",
+        [full as fn(i64) -> Point],
+        [partial as fn(f64) -> Point],
+        [reordered as fn() -> Point],
+        [assert full(3) == Point { x: 3, y: 2.5 }],
+        [assert partial(0.5) == Point { x: 0, y: 0.5 }],
+        [assert reordered() == Point { x: 2, y: 1.0 }]
+    )
+}
+
+#[test]
+fn nested() {
+    check_codegen!(
+        "
+        struct A { a: i64, b: i64 }
+        struct B { first: A, second: A, tag: i8 }
+
+        fn test(x: i64) -> i64 {
+            let b = B { first: A { a: x }, second: A { a: x * 2, b: x * 3 }, tag: 1 };
+            return b.first.a + b.first.b + b.second.a + b.second.b + b.tag;
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(1) == 7],
+        [assert test(10) == 61]
+    )
+}
+
+#[test]
+fn pointer_field() {
+    check_codegen!(
+        "
+        struct Node { value: i64, next: *Node }
+
+        fn test() -> i64 {
+            let last = Node { value: 2 };
+            let first = Node { value: 1, next: &last };
+            return first.value + first.next.value;
+        }
+        ",
+        [test as fn() -> i64],
+        [assert test() == 3]
+    )
+}
+
+#[test]
+fn forward_reference() {
+    // Types are declared before fields are resolved, so order of definitions doesn't matter
+    check_codegen!(
+        "
+        struct B { a: A }
+        struct A { a: i64 }
+
+        fn test() -> i64 {
+            let b = B {};
+            b.a.a = 1;
+            return b.a.a;
+        }
+        ",
+        [test as fn() -> i64],
+        [assert test() == 1]
+    )
+}
+
+#[test]
+fn mutual_pointers() {
+    check_codegen!(
+        "
+        // Types can be used before their definition
+        struct A { value: i64, b: BPtr }
+        type BPtr = *B;
+        struct B { value: i64, a: *A }
+
+        fn test(x: i64) -> i64 {
+            let a = A { value: x };
+            let b = B { value: 2, a: &a };
+            a.b = &b;
+            return a.b.a.value * 10 + b.a.b.value;
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(3) == 32]
+    )
+}
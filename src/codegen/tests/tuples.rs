@@ -0,0 +1,63 @@
+use crate::codegen::tests::macros::check_codegen;
+
+#[test]
+fn multiple_returns() {
+    check_codegen!(
+        "
+        fn divmod(a: i64, b: i64) -> (i64, i64) { return (a / b, a % b); }
+        fn swap<T, U>(p: (T, U)) -> (U, T) { return (p.1, p.0); }
+
+        fn test(a: i64, b: i64) -> i64 {
+            let (q, r) = divmod(a, b);
+            let t = divmod(b, 3);
+            t.0 += 100;
+            let (x, _) = swap((2.5, q));
+            let nested = ((1, 2), 3);
+            nested.0.1 = 7;
+            return q * 1000 + r * 100 + t.0 + x + nested.0.1;
+        }
+        ",
+        [test as fn(i64, i64) -> i64],
+        [assert test(17, 5) == 3311],
+        [assert test(4, 4) == 1000 + 101 + 1 + 7]
+    )
+}
+
+#[test]
+fn conversions_and_fields() {
+    check_codegen!(
+        "
+        struct Range { bounds: (i32, i32), step: i64 }
+        const ORIGIN: (i32, f64) = (1, 0.5);
+
+        fn small() -> (i32, f32) { return (1, 2.5); }
+
+        fn test(a: i32, b: i32) -> f64 {
+            let s = Range { bounds: (a, b), step: 2 };
+            let (lo, hi): (f64, f64) = s.bounds;
+            let (i, f) = small();
+            return (hi - lo) * ORIGIN.1 + ORIGIN.0 + i + f;
+        }
+        ",
+        [test as fn(i32, i32) -> f64],
+        [assert test(2, 10) == 8.5]
+    )
+}
+
+#[test]
+fn struct_elements() {
+    check_codegen!(
+        "
+        struct A { t: (B, i64), pair: [(B, B); 2] }
+        struct B { x: i64 }
+
+        fn test(x: i64) -> i64 {
+            let a = A { t: (B { x: x }, 2) };
+            a.pair[1] = (B { x: 3 }, a.t.0);
+            return a.t.0.x * a.t.1 + a.pair[1].0.x + a.pair[1].1.x;
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(5) == 18]
+    )
+}
@@ -0,0 +1,137 @@
+use crate::codegen::tests::macros::check_codegen;
+
+#[test]
+fn aliases() {
+    check_codegen!(
+        "
+        type Id = i64;
+        type Coord = f64;
+        struct Point { x: Coord, y: Coord }
+        type Pos = Point;
+        type PosPtr = *Pos;
+        type Row = [Id; 3];
+
+        fn shift(p: PosPtr, dx: Coord) { p.x = p.x + dx; }
+
+        fn test(x: f64, id: i64) -> f64 {
+            let p: Pos = Point { x: x, y: 1 };
+            shift(&p, 0.5);
+            // Alias is the same type, so values mix freely
+            let row: Row = [id, 2, 3];
+            let sum: i64 = row[0] + row[1];
+            return p.x + p.y + sum as f64;
+        }
+        ",
+        [test as fn(f64, i64) -> f64],
+        [assert test(1.0, 10) == 14.5]
+    )
+}
+
+#[test]
+fn generic_alias() {
+    check_codegen!(
+        "
+        struct Pair<T> { a: T, b: T }
+        type IntPair = Pair<i32>;
+
+        fn sum(p: IntPair) -> i32 { return p.a + p.b; }
+        fn test(a: i32) -> i32 { return sum(Pair::<i32> { a: a, b: 2 }); }
+        ",
+        [test as fn(i32) -> i32],
+        [assert test(40) == 42]
+    )
+}
+
+#[test]
+fn newtypes() {
+    check_codegen!(
+        "
+        newtype Meters = f64;
+        newtype Count = u8;
+        const MILE: Meters = 1609.0;
+
+        fn total(a: Meters, b: Meters) -> Meters { return a + b * 2.0; }
+        fn test(km: f64) -> f64 {
+            let m = km as Meters * 1000;
+            let t: Meters = total(m, MILE);
+            if (t > 2.0 * MILE) {
+                return t as f64;
+            }
+            return -(t as f64);
+        }
+
+        fn steps(n: u8) -> i64 {
+            let c = n as Count;
+            let res = 0;
+            while (c) {
+                c = c - 1;
+                res = res + 1;
+            }
+            switch (n as Count) {
+                case 3: { res = res * 10; }
+            }
+            return res;
+        }
+        ",
+        [test as fn(f64) -> f64],
+        [steps as fn(u8) -> i64],
+        [assert test(1.0) == 4218.0],
+        [assert test(0.0) == -3218.0],
+        [assert steps(3) == 30],
+        [assert steps(2) == 2]
+    )
+}
+
+#[test]
+fn non_numeric_newtypes() {
+    check_codegen!(
+        "
+        struct Point { x: i64, y: i64 }
+        newtype Pos = Point;
+        newtype Handle = *void;
+
+        fn make(x: i64) -> Pos { return Point { x: x, y: 2 } as Pos; }
+        fn open(p: *Point) -> Handle { return p as Handle; }
+        fn get(h: Handle) -> i64 {
+            let p = h as *Point;
+            return p.x;
+        }
+
+        fn test(x: i64) -> i64 {
+            let p = make(x) as Point;
+            let h = open(&p);
+            if (h == open(&p)) {
+                return get(h) + p.y;
+            }
+            return -1;
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(40) == 42]
+    )
+}
+
+#[test]
+fn newtype_access() {
+    // Fields & elements are accessed through newtype as through wrapped type
+    check_codegen!(
+        "
+        struct Point { x: i64, y: i64 }
+        newtype Pos = Point;
+        newtype Row = [i64; 3];
+        newtype PosPtr = *Point;
+
+        fn test(x: i64) -> i64 {
+            let p = Point { x: x, y: 2 } as Pos;
+            p.y = 3;
+            let r = [1, 2, 3] as Row;
+            r[1] = 10;
+            let ptr = &p as *Point as PosPtr;
+            ptr.x = ptr.x + 1;
+            return p.x + p.y + r[1];
+        }
+        ",
+        [test as fn(i64) -> i64],
+        [assert test(1) == 15]
+    )
+}
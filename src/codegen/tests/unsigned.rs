@@ -0,0 +1,129 @@
+use crate::codegen::tests::macros::check_codegen;
+
+#[test]
+fn wrapping() {
+    check_codegen!(
+        "
+        fn test(x: u8) -> i32 {
+            let a: u8 = x;
+            a = a + 1;
+            return a;
+        }
+        ",
+        [test as fn(u8) -> i32],
+        [assert test(200) == 201],
+        [assert test(255) == 0]
+    )
+}
+
+#[test]
+fn division() {
+    check_codegen!(
+        "
+        fn div(a: u64, b: u64) -> u64 { return a / b; }
+        fn rem(a: u64, b: u64) -> u64 { return a % b; }
+        fn shr(a: u64, b: u64) -> u64 { return a >> b; }
+        fn sdiv(a: i64, b: i64) -> i64 { return a / b; }
+        ",
+        [div as fn(u64, u64) -> u64],
+        [rem as fn(u64, u64) -> u64],
+        [shr as fn(u64, u64) -> u64],
+        [sdiv as fn(i64, i64) -> i64],
+        [assert div(u64::MAX, 2) == u64::MAX / 2],
+        [assert rem(u64::MAX, 10) == u64::MAX % 10],
+        [assert shr(u64::MAX, 60) == 15],
+        [assert sdiv(-7, 2) == -3]
+    )
+}
+
+#[test]
+fn compare() {
+    check_codegen!(
+        "
+        fn lt(a: u32, b: u32) -> i32 {
+            if (a < b) {
+                return 1;
+            }
+            return 0;
+        }
+
+        fn mixed_lt(a: i32, b: u32) -> i32 {
+            if (a < b as i32) {
+                return 1;
+            }
+            return 0;
+        }
+
+        // u32 fits into i64, so comparison is signed
+        fn wide_lt(a: i64, b: u32) -> i32 {
+            if (a < b) {
+                return 1;
+            }
+            return 0;
+        }
+        ",
+        [lt as fn(u32, u32) -> i32],
+        [mixed_lt as fn(i32, u32) -> i32],
+        [wide_lt as fn(i64, u32) -> i32],
+        [assert lt(1, u32::MAX) == 1],
+        [assert lt(u32::MAX, 1) == 0],
+        [assert mixed_lt(-1, 1) == 1],
+        [assert mixed_lt(1, 2) == 1],
+        [assert wide_lt(-1, 1) == 1]
+    )
+}
+
+#[test]
+fn widening() {
+    check_codegen!(
+        "
+        fn widen_u(a: u8) -> i64 { return a; }
+        fn widen_s(a: i8) -> i64 { return a; }
+        fn from_bool(a: i64) -> i64 { return (a > 0) as i64 + 1; }
+        ",
+        [widen_u as fn(u8) -> i64],
+        [widen_s as fn(i8) -> i64],
+        [from_bool as fn(i64) -> i64],
+        [assert widen_u(200) == 200],
+        [assert widen_s(-56) == -56],
+        [assert from_bool(1) == 2],
+        [assert from_bool(-1) == 1]
+    )
+}
+
+#[test]
+fn float_conversions() {
+    check_codegen!(
+        "
+        fn to_float(a: u64) -> f64 { return a as f64; }
+        fn exact(a: u32) -> f64 { return a; }
+        fn from_float(a: f64) -> u8 { return a as u8; }
+        ",
+        [to_float as fn(u64) -> f64],
+        [exact as fn(u32) -> f64],
+        [from_float as fn(f64) -> u8],
+        [assert to_float(u64::MAX) == u64::MAX as f64],
+        [assert to_float(1) == 1.0],
+        [assert exact(u32::MAX) == u32::MAX as f64],
+        [assert from_float(200.0) == 200]
+    )
+}
+
+#[test]
+fn to_bool() {
+    check_codegen!(
+        "
+        fn test(a: i64) -> i32 {
+            let flag = a as bool;
+            if (flag) {
+                return 1;
+            }
+            return 0;
+        }
+        ",
+        [test as fn(i64) -> i32],
+        [assert test(0) == 0],
+        [assert test(2) == 1],
+        [assert test(-1) == 1]
+    )
+}
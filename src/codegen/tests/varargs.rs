@@ -0,0 +1,41 @@
+use crate::codegen::tests::macros::check_codegen;
+use nyastd::str_eq;
+
+#[test]
+fn snprintf() {
+    check_codegen!(
+        r#"
+        fn snprintf(buf: str, size: i64, fmt: str, ...) -> i32;
+        fn str_eq(lhs: str, rhs: str) -> i32;
+
+        fn test() -> i32 {
+            let buf: [i8; 64] = [0 as i8; 64];
+            let small: i8 = -5;
+            let half: f32 = 2.5;
+            let written = snprintf(&buf[0], 64, "%d %.1f %ld %s", small, half, 1 + 2, "nya");
+            if (str_eq(&buf[0], "-5 2.5 3 nya")) {
+                return written;
+            }
+            return -1;
+        }
+        "#,
+        [extern str_eq],
+        [test as fn() -> i32],
+        [assert test() == 12]
+    )
+}
+
+#[test]
+fn no_extra_args() {
+    check_codegen!(
+        r#"
+        fn snprintf(buf: str, size: i64, fmt: str, ...) -> i32;
+
+        fn test() -> i32 {
+            return snprintf(null, 0, "nya");
+        }
+        "#,
+        [test as fn() -> i32],
+        [assert test() == 3]
+    )
+}
@@ -0,0 +1,761 @@
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use logos::{FilterResult, Logos, SpannedIter};
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub enum LexicalError {
+    #[default]
+    InvalidToken,
+    UnterminatedComment,
+    InvalidEscape(String),
+    InvalidLiteral(String),
+    LiteralOutOfRange(String, String),
+    NonAsciiChar(String),
+}
+
+impl Display for LexicalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LexicalError::InvalidToken => write!(f, "Invalid token"),
+            LexicalError::UnterminatedComment => write!(f, "Unterminated block comment"),
+            LexicalError::InvalidEscape(seq) => write!(f, "Invalid escape sequence \\{}", seq),
+            LexicalError::InvalidLiteral(lit) => write!(f, "Invalid literal {}", lit),
+            LexicalError::LiteralOutOfRange(lit, ty) => {
+                write!(f, "Literal {} is out of range of {}", lit, ty)
+            }
+            LexicalError::NonAsciiChar(lit) => {
+                write!(f, "Char literal {} is not a single ASCII char", lit)
+            }
+        }
+    }
+}
+
+/// Skips (possibly nested) block comment, lexer is positioned right after opening `/*`
+fn block_comment<'input>(
+    lex: &mut logos::Lexer<'input, Token<'input>>,
+) -> FilterResult<(), LexicalError> {
+    let rest = lex.remainder().as_bytes();
+    let mut depth = 1;
+    let mut pos = 0;
+
+    while pos < rest.len() {
+        match &rest[pos..] {
+            [b'/', b'*', ..] => {
+                depth += 1;
+                pos += 2;
+            }
+            [b'*', b'/', ..] => {
+                depth -= 1;
+                pos += 2;
+                if depth == 0 {
+                    lex.bump(pos);
+                    return FilterResult::Skip;
+                }
+            }
+            _ => pos += 1,
+        }
+    }
+
+    lex.bump(rest.len());
+    FilterResult::Error(LexicalError::UnterminatedComment)
+}
+
+/// Unescapes string literal content, lexer slice includes quotes
+fn string_literal<'input>(
+    lex: &mut logos::Lexer<'input, Token<'input>>,
+) -> Result<String, LexicalError> {
+    let slice = lex.slice();
+    unescape(&slice[1..slice.len() - 1])
+}
+
+/// Char literal is a single ASCII char (possibly escaped) and has `i8` type
+fn char_literal<'input>(lex: &mut logos::Lexer<'input, Token<'input>>) -> Result<u8, LexicalError> {
+    let slice = lex.slice();
+    let res = unescape(&slice[1..slice.len() - 1])?;
+    match res.as_bytes() {
+        [c] if c.is_ascii() => Ok(*c),
+        _ => Err(LexicalError::NonAsciiChar(slice.into())),
+    }
+}
+
+/// Replaces escape sequences in literal content
+fn unescape(content: &str) -> Result<String, LexicalError> {
+    let mut chars = content.chars();
+    let mut res = String::new();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            res.push(c);
+            continue;
+        }
+
+        // Regex guarantees that backslash is followed by some char
+        let escaped = chars.next().unwrap();
+        res.push(match escaped {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            'x' => {
+                let digits: String = chars.by_ref().take(2).collect();
+                match u8::from_str_radix(&digits, 16) {
+                    Ok(code) if digits.len() == 2 && code.is_ascii() => code as char,
+                    _ => return Err(LexicalError::InvalidEscape(format!("x{}", digits))),
+                }
+            }
+            other => return Err(LexicalError::InvalidEscape(other.to_string())),
+        });
+    }
+
+    Ok(res)
+}
+
+const INT_SUFFIXES: [&str; 8] = ["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64"];
+
+/// Parses integer literal (`0xFF`, `0b1010`, `0o17`, `1_000u8`) into value and optional type suffix.
+/// Value must fit into suffix type
+pub fn parse_int(literal: &str) -> Result<(u64, Option<&str>), LexicalError> {
+    let (digits, suffix) = match INT_SUFFIXES.iter().find(|s| literal.ends_with(*s)) {
+        Some(suffix) => (&literal[..literal.len() - suffix.len()], Some(*suffix)),
+        None => (literal, None),
+    };
+    let (radix, digits) = match digits.get(..2) {
+        Some("0x") => (16, &digits[2..]),
+        Some("0o") => (8, &digits[2..]),
+        Some("0b") => (2, &digits[2..]),
+        _ => (10, digits),
+    };
+
+    let digits = digits.replace('_', "");
+    let out_of_range =
+        || LexicalError::LiteralOutOfRange(literal.into(), suffix.unwrap_or("u64").into());
+    let val = u64::from_str_radix(&digits, radix).map_err(|e| match e.kind() {
+        std::num::IntErrorKind::PosOverflow => out_of_range(),
+        _ => LexicalError::InvalidLiteral(literal.into()),
+    })?;
+
+    // Signed literal can be one bigger than maximum, it's minimal value with unary minus (`-128i8`).
+    // Literal without minus is rejected at codegen
+    let max = match suffix {
+        Some("i8") => i8::MIN.unsigned_abs() as u64,
+        Some("i16") => i16::MIN.unsigned_abs() as u64,
+        Some("i32") => i32::MIN.unsigned_abs() as u64,
+        Some("i64") => i64::MIN.unsigned_abs(),
+        Some("u8") => u8::MAX as u64,
+        Some("u16") => u16::MAX as u64,
+        Some("u32") => u32::MAX as u64,
+        _ => u64::MAX,
+    };
+    if val > max {
+        return Err(out_of_range());
+    }
+
+    Ok((val, suffix))
+}
+
+/// Parses float literal (`1.5`, `1e-9`, `2.0f32`) into value and optional type suffix
+pub fn parse_float(literal: &str) -> Result<(f64, Option<&str>), LexicalError> {
+    let (digits, suffix) = match ["f32", "f64"].iter().find(|s| literal.ends_with(*s)) {
+        Some(suffix) => (&literal[..literal.len() - suffix.len()], Some(*suffix)),
+        None => (literal, None),
+    };
+
+    let val = f64::from_str(&digits.replace('_', ""))
+        .map_err(|_| LexicalError::InvalidLiteral(literal.into()))?;
+    let fits = match suffix {
+        Some("f32") => (val as f32).is_finite(),
+        _ => val.is_finite(),
+    };
+    if !fits {
+        return Err(LexicalError::LiteralOutOfRange(
+            literal.into(),
+            suffix.unwrap_or("f64").into(),
+        ));
+    }
+
+    Ok((val, suffix))
+}
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+#[logos(error = LexicalError)]
+#[logos(skip r"[ \t\r\n\f]+")]
+#[logos(skip r"//[^\n]*")]
+pub enum Token<'input> {
+    #[token("/*", block_comment)]
+    BlockComment,
+    // `////...` is usual comment
+    #[regex(r"///([^/\n][^\n]*)?", |lex| lex.slice()[3..].strip_prefix(' ').unwrap_or(&lex.slice()[3..]))]
+    DocComment(&'input str),
+
+    // — Keywords
+    #[token("fn")]
+    Fn,
+    #[token("struct")]
+    Struct,
+    #[token("enum")]
+    Enum,
+    #[token("match")]
+    Match,
+    #[token("impl")]
+    Impl,
+    #[token("import")]
+    Import,
+    #[token("mod")]
+    Mod,
+    #[token("pub")]
+    Pub,
+    #[token("type")]
+    Type,
+    #[token("newtype")]
+    Newtype,
+    #[token("self")]
+    SelfValue,
+    #[token("let")]
+    Let,
+    #[token("const")]
+    Const,
+    #[token("static")]
+    Static,
+    #[token("while")]
+    While,
+    #[token("loop")]
+    Loop,
+    #[token("do")]
+    Do,
+    #[token("for")]
+    For,
+    #[token("if")]
+    If,
+    #[token("else")]
+    Else,
+    #[token("switch")]
+    Switch,
+    #[token("case")]
+    Case,
+    #[token("default")]
+    Default,
+    #[token("return")]
+    Return,
+    #[token("break")]
+    Break,
+    #[token("continue")]
+    Continue,
+    #[token("null")]
+    Null,
+    #[token("as")]
+    As,
+    #[token("new")]
+    New,
+    #[token("delete")]
+    Delete,
+    #[token("defer")]
+    Defer,
+    #[token("true")]
+    True,
+    #[token("false")]
+    False,
+
+    // — Literals & identificators
+    #[regex(r"[a-zA-Z_][a-zA-Z_0-9]*")]
+    Ident(&'input str),
+    #[regex(r"'[a-zA-Z_][a-zA-Z_0-9]*", |lex| &lex.slice()[1..])]
+    Label(&'input str),
+    #[regex(r"(0x[0-9a-fA-F_]+|0o[0-7_]+|0b[01_]+|[0-9][0-9_]*)([iu](8|16|32|64))?")]
+    Int(&'input str),
+    #[regex(r"[0-9][0-9_]*(\.[0-9_]*([eE][+-]?[0-9_]+)?|[eE][+-]?[0-9_]+)(f32|f64)?")]
+    #[regex(r"[0-9][0-9_]*(f32|f64)")]
+    Float(&'input str),
+    #[regex(r"'([^'\\\n]|\\[^\nx]|\\x[0-9a-fA-F]{2})'", char_literal)]
+    Char(u8),
+    #[regex(r#""([^"\\\n]|\\[^\n])*""#, string_literal)]
+    Str(String),
+
+    // — Punctuation
+    #[token("(")]
+    LParen,
+    #[token(")")]
+    RParen,
+    #[token("{")]
+    LBrace,
+    #[token("}")]
+    RBrace,
+    #[token("[")]
+    LBracket,
+    #[token("]")]
+    RBracket,
+    #[token(",")]
+    Comma,
+    #[token(";")]
+    Semicolon,
+    #[token(":")]
+    Colon,
+    #[token("::")]
+    PathSep,
+    #[token(".")]
+    Dot,
+    #[token("...")]
+    Ellipsis,
+    #[token("->")]
+    Arrow,
+    #[token("=>")]
+    FatArrow,
+
+    // — Operators
+    #[token("=")]
+    Assign,
+    #[token("==")]
+    Eq,
+    #[token("!=")]
+    Ne,
+    #[token(">=")]
+    Ge,
+    #[token("<=")]
+    Le,
+    #[token(">")]
+    Gt,
+    #[token("<")]
+    Lt,
+    #[token("+")]
+    Plus,
+    #[token("-")]
+    Minus,
+    #[token("*")]
+    Star,
+    #[token("/")]
+    Slash,
+    #[token("%")]
+    Percent,
+    #[token("|")]
+    Pipe,
+    #[token("^")]
+    Caret,
+    #[token("~")]
+    Tilde,
+    #[token("<<")]
+    Shl,
+    #[token(">>")]
+    Shr,
+    #[token("!")]
+    Bang,
+    #[token("&")]
+    Amp,
+    #[token("&&")]
+    AndAnd,
+    #[token("||")]
+    OrOr,
+
+    // — Compound assignment
+    #[token("+=")]
+    PlusAssign,
+    #[token("-=")]
+    MinusAssign,
+    #[token("*=")]
+    StarAssign,
+    #[token("/=")]
+    SlashAssign,
+    #[token("%=")]
+    PercentAssign,
+    #[token("&=")]
+    AmpAssign,
+    #[token("|=")]
+    PipeAssign,
+    #[token("^=")]
+    CaretAssign,
+    #[token("<<=")]
+    ShlAssign,
+    #[token(">>=")]
+    ShrAssign,
+    #[token("++")]
+    Inc,
+    #[token("--")]
+    Dec,
+}
+
+impl Token<'_> {
+    /// Token can start item, which doc comment is attached to
+    fn starts_item(&self) -> bool {
+        matches!(
+            self,
+            Token::Fn
+                | Token::Struct
+                | Token::Enum
+                | Token::Impl
+                | Token::Import
+                | Token::Mod
+                | Token::Pub
+                | Token::Type
+                | Token::Newtype
+                | Token::Const
+                | Token::Static
+        )
+    }
+}
+
+impl Display for Token<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+pub type Spanned<Tok, Loc, Error> = Result<(Loc, Tok, Loc), Error>;
+
+pub struct Lexer<'input> {
+    token_stream: SpannedIter<'input, Token<'input>>,
+    /// Tokens lexed ahead, but not returned yet
+    pending: VecDeque<Spanned<Token<'input>, usize, LexicalError>>,
+    /// Doc comments before item, they are returned before it
+    docs: VecDeque<Spanned<Token<'input>, usize, LexicalError>>,
+}
+
+impl<'input> Lexer<'input> {
+    pub fn new(input: &'input str) -> Self {
+        Self {
+            token_stream: Token::lexer(input).spanned(),
+            pending: VecDeque::new(),
+            docs: VecDeque::new(),
+        }
+    }
+
+    /// Lexes next token into `pending`.
+    /// Digits after `.` are tuple index (`t.1.0`), so they are lexed as int, not as float literal
+    fn lex(&mut self) {
+        let Some((token, span)) = self.token_stream.next() else {
+            return;
+        };
+        let is_dot = token == Ok(Token::Dot);
+        self.pending
+            .push_back(token.map(|token| (span.start, token, span.end)));
+
+        if is_dot {
+            let digits = self
+                .token_stream
+                .remainder()
+                .bytes()
+                .take_while(u8::is_ascii_digit)
+                .count();
+            if digits > 0 {
+                let index = &self.token_stream.source()[span.end..span.end + digits];
+                self.token_stream.bump(digits);
+                self.pending
+                    .push_back(Ok((span.end, Token::Int(index), span.end + digits)));
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Option<Spanned<Token<'input>, usize, LexicalError>> {
+        if self.pending.is_empty() {
+            self.lex();
+        }
+        self.pending.pop_front()
+    }
+
+    fn peek_token(&mut self) -> Option<&Token<'input>> {
+        if self.pending.is_empty() {
+            self.lex();
+        }
+        match self.pending.front() {
+            Some(Ok((_, token, _))) => Some(token),
+            _ => None,
+        }
+    }
+}
+
+impl<'input> Iterator for Lexer<'input> {
+    type Item = Spanned<Token<'input>, usize, LexicalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(doc) = self.docs.pop_front() {
+            return Some(doc);
+        }
+
+        loop {
+            let token = self.next_token()?;
+            if !matches!(token, Ok((_, Token::DocComment(_), _))) {
+                return Some(token);
+            }
+
+            /* Doc comments of statements, fields, imports etc. are usual comments */
+            let mut docs = VecDeque::from([token]);
+            while matches!(self.peek_token(), Some(Token::DocComment(_))) {
+                docs.push_back(self.next_token().unwrap());
+            }
+            if self.peek_token().is_some_and(Token::starts_item) {
+                self.docs = docs;
+                return self.docs.pop_front();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(input: &str) -> Vec<Result<Token<'_>, LexicalError>> {
+        Lexer::new(input)
+            .map(|t| t.map(|(_, tok, _)| tok))
+            .collect()
+    }
+
+    #[test]
+    fn line_comments() {
+        assert_eq!(
+            tokens("a // comment\n b"),
+            vec![Ok(Token::Ident("a")), Ok(Token::Ident("b"))]
+        );
+        assert_eq!(tokens("// only comment"), vec![]);
+    }
+
+    #[test]
+    fn block_comments() {
+        assert_eq!(
+            tokens("a /* comment */ b"),
+            vec![Ok(Token::Ident("a")), Ok(Token::Ident("b"))]
+        );
+        assert_eq!(
+            tokens("a /* outer /* inner */ still outer */ b"),
+            vec![Ok(Token::Ident("a")), Ok(Token::Ident("b"))]
+        );
+        assert_eq!(
+            tokens("a /* multi\n line // not a line comment */ b"),
+            vec![Ok(Token::Ident("a")), Ok(Token::Ident("b"))]
+        );
+        assert_eq!(
+            tokens("a /* /* */"),
+            vec![
+                Ok(Token::Ident("a")),
+                Err(LexicalError::UnterminatedComment)
+            ]
+        );
+    }
+
+    #[test]
+    fn doc_comments() {
+        assert_eq!(
+            tokens("/// Some doc\nfn"),
+            vec![Ok(Token::DocComment("Some doc")), Ok(Token::Fn)]
+        );
+        assert_eq!(
+            tokens("///no space\n/// second\nstruct"),
+            vec![
+                Ok(Token::DocComment("no space")),
+                Ok(Token::DocComment("second")),
+                Ok(Token::Struct)
+            ]
+        );
+        assert_eq!(
+            tokens("/// Block\nimpl /// Module\nmod"),
+            vec![
+                Ok(Token::DocComment("Block")),
+                Ok(Token::Impl),
+                Ok(Token::DocComment("Module")),
+                Ok(Token::Mod)
+            ]
+        );
+        assert_eq!(tokens("//// not a doc\nfn"), vec![Ok(Token::Fn)]);
+        assert_eq!(
+            tokens("///\nfn"),
+            vec![Ok(Token::DocComment("")), Ok(Token::Fn)]
+        );
+    }
+
+    #[test]
+    fn doc_comments_outside_items() {
+        assert_eq!(
+            tokens("{ /// not a doc\n let }"),
+            vec![Ok(Token::LBrace), Ok(Token::Let), Ok(Token::RBrace)]
+        );
+        assert_eq!(tokens("/// not a doc\nx: i32"), tokens("x: i32"));
+        assert_eq!(tokens("/// at the end"), vec![]);
+    }
+
+    #[test]
+    fn doc_comments_outside_items_are_parsed() {
+        let program = "
+            struct Point {
+                /// Field
+                x: i32
+            }
+
+            /// Methods of Point
+            impl Point {
+                /// Doubled x
+                fn double(self) -> i32 {
+                    /// Statement
+                    return self.x * 2;
+                }
+            }
+        ";
+        assert!(
+            crate::grammar::ProgramParser::new()
+                .parse(Lexer::new(program))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn keywords_and_idents() {
+        assert_eq!(
+            tokens("fn fnord"),
+            vec![Ok(Token::Fn), Ok(Token::Ident("fnord"))]
+        );
+        assert_eq!(tokens("@"), vec![Err(LexicalError::InvalidToken)]);
+    }
+
+    #[test]
+    fn labels() {
+        assert_eq!(
+            tokens("'outer: break 'outer;"),
+            vec![
+                Ok(Token::Label("outer")),
+                Ok(Token::Colon),
+                Ok(Token::Break),
+                Ok(Token::Label("outer")),
+                Ok(Token::Semicolon)
+            ]
+        );
+    }
+
+    #[test]
+    fn string_literals() {
+        assert_eq!(
+            tokens(r#""hello" "" "a\tb\n\"q\" \\ \x41""#),
+            vec![
+                Ok(Token::Str("hello".into())),
+                Ok(Token::Str("".into())),
+                Ok(Token::Str("a\tb\n\"q\" \\ A".into()))
+            ]
+        );
+        assert_eq!(
+            tokens(r#""/* not a comment */ // nor this""#),
+            vec![Ok(Token::Str("/* not a comment */ // nor this".into()))]
+        );
+        assert_eq!(
+            tokens(r#""bad \q""#),
+            vec![Err(LexicalError::InvalidEscape("q".into()))]
+        );
+        assert_eq!(
+            tokens(r#""bad \xZZ""#),
+            vec![Err(LexicalError::InvalidEscape("xZZ".into()))]
+        );
+    }
+
+    #[test]
+    fn char_literals() {
+        assert_eq!(
+            tokens(r"'a' '\n' '\'' '\x41' 'outer"),
+            vec![
+                Ok(Token::Char(b'a')),
+                Ok(Token::Char(b'\n')),
+                Ok(Token::Char(b'\'')),
+                Ok(Token::Char(b'A')),
+                Ok(Token::Label("outer"))
+            ]
+        );
+        assert_eq!(
+            tokens("'é'"),
+            vec![Err(LexicalError::NonAsciiChar("'é'".into()))]
+        );
+    }
+
+    #[test]
+    fn tuple_indices() {
+        assert_eq!(
+            tokens("t.1.0"),
+            vec![
+                Ok(Token::Ident("t")),
+                Ok(Token::Dot),
+                Ok(Token::Int("1")),
+                Ok(Token::Dot),
+                Ok(Token::Int("0"))
+            ]
+        );
+        // Index is never float literal, so these are errors in parser
+        assert_eq!(
+            tokens("t.1e0"),
+            vec![
+                Ok(Token::Ident("t")),
+                Ok(Token::Dot),
+                Ok(Token::Int("1")),
+                Ok(Token::Ident("e0"))
+            ]
+        );
+        assert_eq!(
+            tokens("t.1.0f32"),
+            vec![
+                Ok(Token::Ident("t")),
+                Ok(Token::Dot),
+                Ok(Token::Int("1")),
+                Ok(Token::Dot),
+                Ok(Token::Int("0")),
+                Ok(Token::Ident("f32"))
+            ]
+        );
+        assert_eq!(tokens("1.5"), vec![Ok(Token::Float("1.5"))]);
+    }
+
+    #[test]
+    fn numeric_literals() {
+        assert_eq!(
+            tokens("0xFF 0b1010 0o17 1_000u8 1.5 1e-9 2.0f32 3f64"),
+            vec![
+                Ok(Token::Int("0xFF")),
+                Ok(Token::Int("0b1010")),
+                Ok(Token::Int("0o17")),
+                Ok(Token::Int("1_000u8")),
+                Ok(Token::Float("1.5")),
+                Ok(Token::Float("1e-9")),
+                Ok(Token::Float("2.0f32")),
+                Ok(Token::Float("3f64"))
+            ]
+        );
+
+        assert_eq!(parse_int("0xFF"), Ok((255, None)));
+        assert_eq!(parse_int("0b1010_1010u8"), Ok((170, Some("u8"))));
+        assert_eq!(parse_int("0o17i16"), Ok((15, Some("i16"))));
+        assert_eq!(parse_int("1_000"), Ok((1000, None)));
+        assert_eq!(parse_int("128i8"), Ok((128, Some("i8"))));
+        assert_eq!(
+            parse_int("129i8"),
+            Err(LexicalError::LiteralOutOfRange("129i8".into(), "i8".into()))
+        );
+        assert_eq!(
+            parse_int("300u8"),
+            Err(LexicalError::LiteralOutOfRange("300u8".into(), "u8".into()))
+        );
+        assert_eq!(
+            parse_int("18446744073709551616"),
+            Err(LexicalError::LiteralOutOfRange(
+                "18446744073709551616".into(),
+                "u64".into()
+            ))
+        );
+        assert_eq!(
+            parse_int("0x_"),
+            Err(LexicalError::InvalidLiteral("0x_".into()))
+        );
+
+        assert_eq!(parse_float("1e-9"), Ok((1e-9, None)));
+        assert_eq!(parse_float("2.5f32"), Ok((2.5, Some("f32"))));
+        assert_eq!(parse_float("1_000.0"), Ok((1000.0, None)));
+        assert_eq!(
+            parse_float("1e39f32"),
+            Err(LexicalError::LiteralOutOfRange(
+                "1e39f32".into(),
+                "f32".into()
+            ))
+        );
+        assert_eq!(
+            parse_float("1e400"),
+            Err(LexicalError::LiteralOutOfRange(
+                "1e400".into(),
+                "f64".into()
+            ))
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use crate::ast::Statement;
+use crate::ast::statement::module_name;
+use crate::lexer::Lexer;
+use crate::utils::nodes::{Import, Module, Program};
+use crate::visitor::Visitor;
+
+/// Loads program from file `root` with all files it imports
+pub fn load_program(root: &Path) -> anyhow::Result<Program> {
+    load_with(root, |path| Ok(std::fs::read_to_string(path)?))
+}
+
+/// Loads program, reading files via `read`.
+/// Imported files become modules placed before their importers, so items are defined before use
+pub fn load_with(
+    root: &Path,
+    read: impl Fn(&Path) -> anyhow::Result<String>,
+) -> anyhow::Result<Program> {
+    let root = normalize(root);
+    let mut loader = Loader {
+        read,
+        modules: vec![],
+        loaded: HashMap::new(),
+        stack: vec![root.clone()],
+    };
+
+    let blocks = loader.load(&root)?;
+    let mut all_blocks = loader.modules;
+    all_blocks.extend(blocks);
+
+    Ok(Program::new(all_blocks))
+}
+
+struct Loader<F> {
+    read: F,
+    /// Already loaded modules in dependency order
+    modules: Vec<Box<dyn Statement>>,
+    /// Path of file of each loaded module
+    loaded: HashMap<String, PathBuf>,
+    /// Files being loaded, to detect import cycles
+    stack: Vec<PathBuf>,
+}
+
+impl<F: Fn(&Path) -> anyhow::Result<String>> Loader<F> {
+    /// Parses file `path` and loads its imports, returns blocks of the file
+    fn load(&mut self, path: &Path) -> anyhow::Result<Vec<Box<dyn Statement>>> {
+        let content = (self.read)(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {} with error {}", path.display(), e))?;
+        let program = crate::grammar::ProgramParser::new()
+            .parse(Lexer::new(&content))
+            .map_err(|e| anyhow::anyhow!("Failed to parse {} with error {}", path.display(), e))?;
+
+        let mut imports = ImportCollector::default();
+        for block in &program.blocks {
+            block.accept(&mut imports)?;
+        }
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for import in imports.paths {
+            let dep = normalize(&dir.join(import));
+            if let Some(pos) = self.stack.iter().position(|file| *file == dep) {
+                let cycle = self.stack[pos..]
+                    .iter()
+                    .chain([&dep])
+                    .map(|file| file.display().to_string())
+                    .collect::<Vec<_>>();
+                anyhow::bail!("Import cycle: {}", cycle.join(" -> "));
+            }
+
+            let name = module_name(&dep);
+            match self.loaded.get(&name) {
+                Some(file) if *file == dep => continue,
+                Some(file) => anyhow::bail!(
+                    "Module name {} is used by both {} and {}",
+                    name,
+                    file.display(),
+                    dep.display()
+                ),
+                None => {}
+            }
+
+            self.stack.push(dep.clone());
+            let blocks = self.load(&dep)?;
+            self.stack.pop();
+
+            self.loaded.insert(name.clone(), dep);
+            self.modules.push(Box::new(Module::new(name, blocks)));
+        }
+
+        Ok(program.blocks)
+    }
+}
+
+/// Paths of files imported by program blocks
+#[derive(Default)]
+struct ImportCollector {
+    paths: Vec<String>,
+}
+
+impl Visitor for ImportCollector {
+    fn visit_import(&mut self, node: &Import) -> anyhow::Result<()> {
+        self.paths.push(node.path.clone());
+        Ok(())
+    }
+}
+
+/// Removes `.` and `..` without touching filesystem, so the same file always has the same path
+fn normalize(path: &Path) -> PathBuf {
+    let mut res = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(res.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                res.pop();
+            }
+            _ => res.push(component),
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    use super::{load_with, normalize};
+    use crate::utils::nodes::Module;
+    use crate::visitor::Visitor;
+
+    fn load(files: &[(&str, &str)]) -> anyhow::Result<Vec<String>> {
+        let files: HashMap<_, _> = files.iter().cloned().collect();
+        let program = load_with(Path::new(files.keys().min().unwrap()), |path| {
+            let path = path.to_str().unwrap();
+            match files.get(path) {
+                Some(content) => Ok(content.to_string()),
+                None => anyhow::bail!("No such file"),
+            }
+        })?;
+
+        let mut names = ModuleNames::default();
+        for block in &program.blocks {
+            block.accept(&mut names)?;
+        }
+        Ok(names.0)
+    }
+
+    /// Names of modules in load order
+    #[derive(Default)]
+    struct ModuleNames(Vec<String>);
+
+    impl Visitor for ModuleNames {
+        fn visit_module(&mut self, node: &Module) -> anyhow::Result<()> {
+            self.0.push(node.name.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn dependency_order() {
+        let names = load(&[
+            ("a_main.nya", "import \"geometry.nya\"; mod io;"),
+            ("geometry.nya", "import \"lib/math.nya\";"),
+            ("io.nya", "mod geometry;"),
+            ("lib/math.nya", "fn sqr(x: i64) -> i64 { return x * x; }"),
+        ])
+        .unwrap();
+        assert_eq!(names, ["math", "geometry", "io"]);
+    }
+
+    #[test]
+    fn import_errors() {
+        let err = load(&[
+            ("a.nya", "mod b;"),
+            ("b.nya", "mod c;"),
+            ("c.nya", "mod a;"),
+        ]);
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Import cycle: a.nya -> b.nya -> c.nya -> a.nya"
+        );
+
+        let err = load(&[("a.nya", "mod a;")]);
+        assert_eq!(err.unwrap_err().to_string(), "Import cycle: a.nya -> a.nya");
+
+        let err = load(&[("a.nya", "mod b;")]);
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Failed to read b.nya with error No such file"
+        );
+
+        let err = load(&[
+            ("a.nya", "mod b; import \"lib/b.nya\";"),
+            ("b.nya", ""),
+            ("lib/b.nya", ""),
+        ]);
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Module name b is used by both b.nya and lib/b.nya"
+        );
+    }
+
+    #[test]
+    fn normalize_path() {
+        assert_eq!(
+            normalize(Path::new("./a/../b/./c.nya")),
+            Path::new("b/c.nya")
+        );
+        assert_eq!(
+            normalize(Path::new("../a/b/../c.nya")),
+            Path::new("../a/c.nya")
+        );
+    }
+}
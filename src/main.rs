@@ -1,5 +1,7 @@
 mod ast;
 mod codegen;
+mod lexer;
+mod loader;
 mod utils;
 mod visitor;
 
@@ -21,7 +23,7 @@ struct Cli {
     target: CompileTarget,
 
     #[arg(short, long, value_name = "FILE")]
-    /// Path of input NyaC program
+    /// Path of input NyaC program, files it imports are loaded relative to it
     input: PathBuf,
 }
 
@@ -39,27 +41,25 @@ enum CompileTarget {
 
         #[arg(long)]
         no_optimize: bool,
+
+        /// Report memory allocated by `new` and not released by `delete` at exit of linked program
+        #[arg(long)]
+        leak_report: bool,
     },
     /// Compile & execute via LLVM JIT
-    Jit {},
+    Jit {
+        /// Report memory allocated by `new` and not released by `delete` after exit
+        #[arg(long)]
+        leak_report: bool,
+    },
 }
 
 fn main() {
     let cli = Cli::parse();
 
-    let input_content = std::fs::read_to_string(&cli.input);
-    if let Err(e) = input_content {
-        panic!(
-            "Failed to read input file {} with error {}",
-            cli.input.display(),
-            e
-        );
-    }
-    let input_content = input_content.unwrap();
-
-    let ast = crate::grammar::ProgramParser::new().parse(&input_content);
+    let ast = loader::load_program(&cli.input);
     if let Err(e) = ast {
-        panic!("Failed to parse into AST with error {}", e);
+        panic!("Failed to load program with error {}", e);
     }
     let ast = ast.unwrap();
 
@@ -78,14 +78,15 @@ fn main() {
         CompileTarget::Ir {
             output,
             no_optimize,
+            leak_report,
         } => {
-            let res = ir_target(&ast, &output, no_optimize);
+            let res = ir_target(&ast, &output, no_optimize, leak_report);
             if let Err(e) = res {
                 panic!("Failed to compile with error {}", e);
             }
         }
-        CompileTarget::Jit {} => {
-            let res = jit_target(&ast);
+        CompileTarget::Jit { leak_report } => {
+            let res = jit_target(&ast, leak_report);
             if let Err(e) = res {
                 panic!("Failed to compile with error {}", e);
             }
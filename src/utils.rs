@@ -1,11 +1,15 @@
 pub mod nodes {
     pub use crate::ast::{
         expression::{
-            Arithmetic, Compare, Float, FunctionCall, Int, Not, StructCtor, UnaryMinus, Variable,
+            Accessor, AddressOf, Arithmetic, ArrayCtor, ArrayRepeat, As, BitNot, Bool, Compare,
+            Deref, EnumCtor, FieldInit, Float, FunctionCall, Int, ItemPath, Logical, NewArray,
+            NewObject, Not, Null, Str, StructCtor, TupleCtor, UnaryMinus, Variable,
         },
         statement::{
-            Assignment, ExprStatement, For, FuncDef, FuncImpl, If, Let, Program, Return, StructDef,
-            While,
+            Assignment, Break, Continue, Defer, Delete, DoWhile, EnumDef, EnumVariantDef,
+            ExprStatement, For, FuncDef, FuncImpl, Global, If, Impl, Import, InfiniteLoop, Let,
+            LetTuple, Match, MatchArm, Module, Program, Return, StructDef, Switch, SwitchCase,
+            TypeAlias, VariantPattern, While,
         },
     };
 }
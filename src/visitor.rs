@@ -7,11 +7,22 @@ pub trait Visitor {
     acceptor_func!(For);
     acceptor_func!(FuncDef);
     acceptor_func!(FuncImpl);
+    acceptor_func!(Impl);
+    acceptor_func!(Global);
+    acceptor_func!(TypeAlias);
     acceptor_func!(If);
+    acceptor_func!(Switch);
+    acceptor_func!(Match);
     acceptor_func!(Let);
+    acceptor_func!(LetTuple);
     acceptor_func!(Program);
+    acceptor_func!(Import);
+    acceptor_func!(Module);
     acceptor_func!(StructDef);
+    acceptor_func!(EnumDef);
     acceptor_func!(While);
+    acceptor_func!(InfiniteLoop);
+    acceptor_func!(DoWhile);
     acceptor_func!(Arithmetic);
     acceptor_func!(Compare);
     acceptor_func!(Int);
@@ -22,7 +33,26 @@ pub trait Visitor {
     acceptor_func!(Variable);
     acceptor_func!(ExprStatement);
     acceptor_func!(StructCtor);
+    acceptor_func!(EnumCtor);
+    acceptor_func!(ItemPath);
     acceptor_func!(Return);
+    acceptor_func!(Break);
+    acceptor_func!(Continue);
+    acceptor_func!(Deref);
+    acceptor_func!(AddressOf);
+    acceptor_func!(Null);
+    acceptor_func!(Str);
+    acceptor_func!(Bool);
+    acceptor_func!(Logical);
+    acceptor_func!(BitNot);
+    acceptor_func!(As);
+    acceptor_func!(ArrayCtor);
+    acceptor_func!(ArrayRepeat);
+    acceptor_func!(TupleCtor);
+    acceptor_func!(NewObject);
+    acceptor_func!(NewArray);
+    acceptor_func!(Delete);
+    acceptor_func!(Defer);
 }
 
 pub trait Acceptor {